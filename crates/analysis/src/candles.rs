@@ -0,0 +1,234 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use trading_core::{Trade, TradeSide};
+
+/// Candle resolution, expressed as its bucket width in seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Resolution {
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+    OneHour,
+    OneDay,
+}
+
+impl Resolution {
+    pub fn seconds(self) -> i64 {
+        match self {
+            Resolution::OneMinute => 60,
+            Resolution::FiveMinutes => 300,
+            Resolution::FifteenMinutes => 900,
+            Resolution::OneHour => 3600,
+            Resolution::OneDay => 86400,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Resolution::OneMinute => "1m",
+            Resolution::FiveMinutes => "5m",
+            Resolution::FifteenMinutes => "15m",
+            Resolution::OneHour => "1h",
+            Resolution::OneDay => "1d",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "1m" => Some(Resolution::OneMinute),
+            "5m" => Some(Resolution::FiveMinutes),
+            "15m" => Some(Resolution::FifteenMinutes),
+            "1h" => Some(Resolution::OneHour),
+            "1d" => Some(Resolution::OneDay),
+            _ => None,
+        }
+    }
+
+    /// The resolution set `ChartAnalyzer::analyze_multi_resolution` expects a candle series
+    /// for - the bot's incremental candle backfill keeps all of these current per token.
+    pub fn chart_set() -> [Resolution; 4] {
+        [
+            Resolution::OneMinute,
+            Resolution::FiveMinutes,
+            Resolution::FifteenMinutes,
+            Resolution::OneHour,
+        ]
+    }
+}
+
+/// An OHLCV candle for a single `(token_mint, resolution, bucket_start)`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Candle {
+    pub token_mint: Pubkey,
+    pub resolution: Resolution,
+    pub bucket_start: i64,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: Decimal,
+    /// Number of trades aggregated into this bucket (0 for a gap-filled bucket)
+    pub trade_count: u32,
+    /// True for a synthetic, gap-filled bucket with no trades of its own
+    pub is_gap_fill: bool,
+    /// True for the most recent bucket, which should be recomputed as new trades arrive
+    pub is_open: bool,
+}
+
+pub struct CandleBuilder;
+
+impl CandleBuilder {
+    /// Aggregate a token's trade stream into OHLCV candles at the given resolution.
+    ///
+    /// Trades are bucketed by `floor(block_time / interval)`. Empty buckets between the
+    /// first and last trade are gap-filled by carrying the previous close forward with
+    /// zero volume, so chart patterns can walk a continuous series. The final bucket is
+    /// marked `is_open` since it may still receive more trades.
+    pub fn build_candles(trades: &[Trade], resolution: Resolution) -> Vec<Candle> {
+        if trades.is_empty() {
+            return Vec::new();
+        }
+
+        let interval = resolution.seconds();
+        let token_mint = trades[0].token_mint;
+
+        let mut sorted: Vec<&Trade> = trades.iter().collect();
+        sorted.sort_by_key(|t| t.block_time);
+
+        let mut buckets: std::collections::BTreeMap<i64, Candle> = std::collections::BTreeMap::new();
+
+        for trade in &sorted {
+            let bucket_start = (trade.block_time.div_euclid(interval)) * interval;
+            let price = trade.price_usd;
+            // Notional volume in quote-currency terms: a buy's `amount_in` is the SOL/USDC
+            // spent, a sell's `amount_out` is the SOL/USDC received - this keeps volume
+            // comparable across buys and sells instead of mixing token units with quote units.
+            let notional = match trade.side {
+                TradeSide::Buy => trade.amount_in,
+                TradeSide::Sell => trade.amount_out,
+            };
+
+            buckets
+                .entry(bucket_start)
+                .and_modify(|candle| {
+                    candle.high = candle.high.max(price);
+                    candle.low = candle.low.min(price);
+                    candle.close = price;
+                    candle.volume += notional;
+                    candle.trade_count += 1;
+                })
+                .or_insert(Candle {
+                    token_mint,
+                    resolution,
+                    bucket_start,
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: notional,
+                    trade_count: 1,
+                    is_gap_fill: false,
+                    is_open: false,
+                });
+        }
+
+        let first_bucket = *buckets.keys().next().unwrap();
+        let last_bucket = *buckets.keys().last().unwrap();
+
+        let mut filled = Vec::new();
+        let mut last_close = buckets[&first_bucket].open;
+        let mut cursor = first_bucket;
+
+        while cursor <= last_bucket {
+            if let Some(candle) = buckets.get(&cursor) {
+                last_close = candle.close;
+                filled.push(candle.clone());
+            } else {
+                filled.push(Candle {
+                    token_mint,
+                    resolution,
+                    bucket_start: cursor,
+                    open: last_close,
+                    high: last_close,
+                    low: last_close,
+                    close: last_close,
+                    volume: Decimal::ZERO,
+                    trade_count: 0,
+                    is_gap_fill: true,
+                    is_open: false,
+                });
+            }
+            cursor += interval;
+        }
+
+        if let Some(last) = filled.last_mut() {
+            last.is_open = true;
+        }
+
+        filled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::str::FromStr;
+    use uuid::Uuid;
+
+    fn trade_at(block_time: i64, price: &str, notional: &str) -> Trade {
+        Trade {
+            id: Uuid::new_v4(),
+            wallet: Pubkey::new_unique(),
+            token_mint: Pubkey::new_unique(),
+            side: TradeSide::Buy,
+            amount_in: notional.parse().unwrap(),
+            amount_out: Decimal::from(1),
+            price_usd: price.parse().unwrap(),
+            market_cap_at_trade: Decimal::ZERO,
+            signature: "sig".to_string(),
+            timestamp: Utc::now(),
+            block_time,
+            dex: "Jupiter".to_string(),
+        }
+    }
+
+    #[test]
+    fn aggregates_ohlcv_within_a_bucket() {
+        let trades = vec![
+            trade_at(0, "1.0", "10"),
+            trade_at(10, "1.2", "5"),
+            trade_at(50, "0.9", "5"),
+        ];
+
+        let candles = CandleBuilder::build_candles(&trades, Resolution::OneMinute);
+        assert_eq!(candles.len(), 1);
+
+        let candle = &candles[0];
+        assert_eq!(candle.open, Decimal::from_str("1.0").unwrap());
+        assert_eq!(candle.high, Decimal::from_str("1.2").unwrap());
+        assert_eq!(candle.low, Decimal::from_str("0.9").unwrap());
+        assert_eq!(candle.close, Decimal::from_str("0.9").unwrap());
+        assert_eq!(candle.volume, Decimal::from(20));
+        assert_eq!(candle.trade_count, 3);
+        assert!(candle.is_open);
+    }
+
+    #[test]
+    fn gap_fills_empty_buckets_with_previous_close() {
+        let trades = vec![trade_at(0, "1.0", "10"), trade_at(180, "1.5", "5")];
+
+        let candles = CandleBuilder::build_candles(&trades, Resolution::OneMinute);
+        assert_eq!(candles.len(), 4);
+
+        assert!(!candles[0].is_gap_fill);
+        assert!(candles[1].is_gap_fill);
+        assert_eq!(candles[1].volume, Decimal::ZERO);
+        assert_eq!(candles[1].trade_count, 0);
+        assert_eq!(candles[1].open, candles[0].close);
+        assert!(candles[2].is_gap_fill);
+        assert!(!candles[3].is_gap_fill);
+        assert!(candles[3].is_open);
+    }
+}
@@ -0,0 +1,196 @@
+// Capital-allocation/rebalancing engine over tracked wallets - spreads a fixed capital
+// pool across wallets already scored by `WalletRepository` (smart_money_score, risk_score),
+// the same two-pass bottom-up/top-down approach a portfolio rebalancer uses: first derive
+// each wallet's allocation bounds from its risk, then set target weights from its score and
+// iteratively clamp/redistribute until the weights converge.
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+
+/// Per-wallet inputs the allocator needs - scores and current allocation are assumed
+/// already fetched from `WalletRepository`; this module only decides how to spread
+/// capital across them.
+#[derive(Debug, Clone, Copy)]
+pub struct WalletAllocationInput {
+    pub smart_money_score: f64,
+    pub risk_score: f64,
+    pub current_allocation: Decimal,
+}
+
+/// Shared constraints applied to every wallet in a [`CapitalAllocator::rebalance`] call.
+#[derive(Debug, Clone, Copy)]
+pub struct AllocationConstraints {
+    /// Hard ceiling on any single wallet's weight before the risk-based cap is applied.
+    pub max_weight_per_wallet: f64,
+    /// Floor weight every wallet is still allocated, however poor its score.
+    pub min_weight_per_wallet: f64,
+    /// Reallocations smaller than this notional are suppressed from the output so it
+    /// doesn't churn on noise.
+    pub min_trade_volume: Decimal,
+}
+
+/// A wallet's allocation target coming out of a rebalance, and the delta from its
+/// current allocation needed to reach it.
+#[derive(Debug, Clone)]
+pub struct AllocationTarget {
+    pub wallet: Pubkey,
+    pub target_notional: Decimal,
+    pub delta: Decimal,
+}
+
+pub struct CapitalAllocator;
+
+impl CapitalAllocator {
+    /// Allocate `pool` across `wallets` under `constraints`, returning only the targets
+    /// whose delta from the current allocation clears `min_trade_volume`.
+    pub fn rebalance(
+        pool: Decimal,
+        wallets: &HashMap<Pubkey, WalletAllocationInput>,
+        constraints: &AllocationConstraints,
+    ) -> Vec<AllocationTarget> {
+        if wallets.is_empty() || pool <= Decimal::ZERO {
+            return Vec::new();
+        }
+
+        // First pass (bottom-up): a wallet's max weight shrinks as its risk_score rises -
+        // at risk_score 1.0 it's cut to 20% of the hard cap, at 0.0 it keeps the full cap.
+        let bounds: HashMap<Pubkey, (f64, f64)> = wallets
+            .iter()
+            .map(|(wallet, input)| {
+                let risk_adjusted_max =
+                    constraints.max_weight_per_wallet * (1.0 - input.risk_score.clamp(0.0, 1.0) * 0.8);
+                let max_weight = risk_adjusted_max
+                    .max(constraints.min_weight_per_wallet)
+                    .min(constraints.max_weight_per_wallet);
+                (*wallet, (constraints.min_weight_per_wallet, max_weight))
+            })
+            .collect();
+
+        // Second pass (top-down): initial target weight proportional to smart_money_score.
+        let score_sum: f64 = wallets.values().map(|input| input.smart_money_score.max(0.0)).sum();
+        let mut weights: HashMap<Pubkey, f64> = if score_sum > 0.0 {
+            wallets
+                .iter()
+                .map(|(wallet, input)| (*wallet, input.smart_money_score.max(0.0) / score_sum))
+                .collect()
+        } else {
+            let equal_weight = 1.0 / wallets.len() as f64;
+            wallets.keys().map(|wallet| (*wallet, equal_weight)).collect()
+        };
+
+        // Clamp to bounds, then redistribute the weight freed (or consumed) by clamped
+        // wallets among the still-unclamped ones, repeating until nothing new clamps.
+        let mut clamped: HashMap<Pubkey, f64> = HashMap::new();
+        const MAX_ITERATIONS: usize = 50;
+
+        for _ in 0..MAX_ITERATIONS {
+            let newly_clamped: Vec<(Pubkey, f64)> = weights
+                .iter()
+                .filter(|(wallet, _)| !clamped.contains_key(*wallet))
+                .filter_map(|(wallet, weight)| {
+                    let (min_weight, max_weight) = bounds[wallet];
+                    if *weight > max_weight {
+                        Some((*wallet, max_weight))
+                    } else if *weight < min_weight {
+                        Some((*wallet, min_weight))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+
+            if newly_clamped.is_empty() {
+                break;
+            }
+
+            for (wallet, clamped_weight) in &newly_clamped {
+                clamped.insert(*wallet, *clamped_weight);
+            }
+
+            let unclamped: Vec<Pubkey> =
+                weights.keys().filter(|wallet| !clamped.contains_key(*wallet)).copied().collect();
+            if unclamped.is_empty() {
+                break;
+            }
+
+            let clamped_total: f64 = clamped.values().sum();
+            let remaining_weight = (1.0 - clamped_total).max(0.0);
+            let unclamped_score_sum: f64 = unclamped.iter().map(|wallet| wallets[wallet].smart_money_score.max(0.0)).sum();
+
+            for wallet in &unclamped {
+                let new_weight = if unclamped_score_sum > 0.0 {
+                    remaining_weight * (wallets[wallet].smart_money_score.max(0.0) / unclamped_score_sum)
+                } else {
+                    remaining_weight / unclamped.len() as f64
+                };
+                weights.insert(*wallet, new_weight);
+            }
+        }
+
+        for (wallet, weight) in clamped {
+            weights.insert(wallet, weight);
+        }
+
+        // The clamp loop only ever raises a weight up to `min_weight_per_wallet`, never
+        // shrinks one back down - so if the floors alone sum past 1.0 (e.g. enough wallets
+        // that `wallets.len() * min_weight_per_wallet > 1.0`), every unclamped wallet
+        // converges to 0 while the floored ones alone already over-commit the pool. Pro-rate
+        // every weight down so the total never exceeds 1.0.
+        let total_weight: f64 = weights.values().sum();
+        if total_weight > 1.0 {
+            for weight in weights.values_mut() {
+                *weight /= total_weight;
+            }
+        }
+
+        let mut targets: Vec<AllocationTarget> = wallets
+            .keys()
+            .map(|wallet| {
+                let weight = weights.get(wallet).copied().unwrap_or(0.0);
+                let target_notional = pool * Decimal::try_from(weight).unwrap_or(Decimal::ZERO);
+                let delta = target_notional - wallets[wallet].current_allocation;
+                AllocationTarget { wallet: *wallet, target_notional, delta }
+            })
+            .filter(|target| target.delta.abs() >= constraints.min_trade_volume)
+            .collect();
+
+        targets.sort_by(|a, b| b.target_notional.cmp(&a.target_notional));
+
+        targets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebalance_never_over_commits_the_pool_when_floors_alone_exceed_it() {
+        // 10 wallets with a 0.15 floor each sum to 1.5 - more than the whole pool - before
+        // any score-based weighting even runs.
+        let constraints = AllocationConstraints {
+            max_weight_per_wallet: 0.5,
+            min_weight_per_wallet: 0.15,
+            min_trade_volume: Decimal::ZERO,
+        };
+
+        let wallets: HashMap<Pubkey, WalletAllocationInput> = (0..10)
+            .map(|_| {
+                (
+                    Pubkey::new_unique(),
+                    WalletAllocationInput {
+                        smart_money_score: 0.5,
+                        risk_score: 0.0,
+                        current_allocation: Decimal::ZERO,
+                    },
+                )
+            })
+            .collect();
+
+        let pool = Decimal::from(100_000);
+        let targets = CapitalAllocator::rebalance(pool, &wallets, &constraints);
+
+        let total_notional: Decimal = targets.iter().map(|t| t.target_notional).sum();
+        assert!(total_notional <= pool, "allocator over-committed: {total_notional} > {pool}");
+    }
+}
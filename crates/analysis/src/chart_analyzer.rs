@@ -1,5 +1,8 @@
 use trading_core::*;
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
+use std::collections::HashMap;
+use crate::candles::{Candle, Resolution};
 
 #[derive(Debug, Clone)]
 pub struct ChartSignal {
@@ -19,6 +22,27 @@ pub enum TradeAction {
     StrongSell,
 }
 
+/// One resolution's momentum/swing-high-low/breakout read, computed by `ChartAnalyzer::read_resolution`
+/// and combined across resolutions by `ChartAnalyzer::analyze_multi_resolution`.
+#[derive(Debug, Clone, Copy)]
+struct ResolutionRead {
+    resolution: Resolution,
+    /// Percentage close-to-close move over the lookback window.
+    momentum_pct: f64,
+    swing_high: Decimal,
+    swing_low: Decimal,
+    /// True if the latest candle closed above the lookback window's swing high on
+    /// above-average volume.
+    breakout_confirmed: bool,
+    /// Wilder RSI over the window's close series, `None` if there weren't enough candles
+    /// to seed it.
+    rsi: Option<f64>,
+}
+
+/// Lookback period `ChartAnalyzer::calculate_rsi`'s Wilder smoothing is seeded over - the
+/// standard 14-bar RSI.
+const RSI_PERIOD: usize = 14;
+
 pub struct ChartAnalyzer;
 
 impl ChartAnalyzer {
@@ -126,33 +150,243 @@ impl ChartAnalyzer {
         }
     }
 
-    /// Calculate RSI approximation
-    pub fn calculate_rsi_approx(
-        price_5m: f64,
-        price_1h: f64,
-        price_24h: f64,
-    ) -> f64 {
-        let gains = [
-            if price_5m > 0.0 { price_5m } else { 0.0 },
-            if price_1h > 0.0 { price_1h } else { 0.0 },
-            if price_24h > 0.0 { price_24h } else { 0.0 },
-        ];
-
-        let losses = [
-            if price_5m < 0.0 { -price_5m } else { 0.0 },
-            if price_1h < 0.0 { -price_1h } else { 0.0 },
-            if price_24h < 0.0 { -price_24h } else { 0.0 },
-        ];
-
-        let avg_gain = gains.iter().sum::<f64>() / gains.len() as f64;
-        let avg_loss = losses.iter().sum::<f64>() / losses.len() as f64;
+    /// Analyze a real OHLCV candle series instead of the point-in-time `MarketData` snapshot.
+    /// Derives the same 5m/1h/24h percentage-change inputs `analyze_entry_exit` expects by
+    /// walking back from the most recent (possibly still-open) candle.
+    pub fn analyze_candles(candles: &[Candle], liquidity_usd: Decimal) -> ChartSignal {
+        let Some(latest) = candles.last() else {
+            return ChartSignal {
+                action: TradeAction::Hold,
+                confidence: 0.0,
+                reason: "No candle data available".into(),
+                suggested_entry: Decimal::ZERO,
+                suggested_exit: Decimal::ZERO,
+            };
+        };
+
+        let price_now = latest.close;
+        let volume_24h: Decimal = candles.iter().rev().take(288).map(|c| c.volume).sum(); // 288 * 5m = 24h
+
+        let pct_change = |back: usize| -> f64 {
+            let idx = candles.len().saturating_sub(1 + back);
+            let reference = candles[idx].close;
+            if reference.is_zero() {
+                return 0.0;
+            }
+            ((price_now - reference) / reference * Decimal::from(100))
+                .to_string()
+                .parse::<f64>()
+                .unwrap_or(0.0)
+        };
+
+        let synthetic = Token {
+            mint: latest.token_mint,
+            symbol: String::new(),
+            name: String::new(),
+            decimals: 9,
+            metadata: TokenMetadata::default(),
+            security: SecurityInfo::default(),
+            market_data: MarketData {
+                price_usd: price_now,
+                price_sol: Decimal::ZERO,
+                market_cap: Decimal::ZERO,
+                liquidity_usd,
+                volume_24h,
+                price_change_24h: pct_change(288),
+                price_change_1h: pct_change(12),
+                price_change_5m: pct_change(1),
+                holders: None,
+                dex: None,
+            },
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+
+        Self::analyze_entry_exit(&synthetic)
+    }
+
+    /// Classic Wilder RSI over a close-price series. Seeds `avg_gain`/`avg_loss` as the
+    /// simple mean of the first `period` period-over-period gains/losses, then smooths every
+    /// later change in with Wilder's recursive average (`avg = (prev_avg*(period-1) + x) /
+    /// period`) instead of a plain moving average. Needs at least `period + 1` closes;
+    /// returns `None` otherwise, or if any period-over-period delta has no lossless `f64`
+    /// representation - an out-of-range delta silently folded in as `0.0` would look like
+    /// an ordinary flat period rather than a conversion failure.
+    pub fn calculate_rsi(closes: &[Decimal], period: usize) -> Option<f64> {
+        if period == 0 || closes.len() < period + 1 {
+            return None;
+        }
+
+        let mut deltas: Vec<f64> = Vec::with_capacity(closes.len() - 1);
+        for w in closes.windows(2) {
+            deltas.push((w[1] - w[0]).to_f64()?);
+        }
+
+        let seed = &deltas[..period];
+        let mut avg_gain = seed.iter().filter(|d| **d > 0.0).sum::<f64>() / period as f64;
+        let mut avg_loss = seed.iter().filter(|d| **d < 0.0).map(|d| -d).sum::<f64>() / period as f64;
+
+        for delta in &deltas[period..] {
+            let gain = delta.max(0.0);
+            let loss = (-delta).max(0.0);
+            avg_gain = (avg_gain * (period - 1) as f64 + gain) / period as f64;
+            avg_loss = (avg_loss * (period - 1) as f64 + loss) / period as f64;
+        }
 
         if avg_loss == 0.0 {
-            return 100.0;
+            return Some(100.0);
         }
 
         let rs = avg_gain / avg_loss;
-        100.0 - (100.0 / (1.0 + rs))
+        Some(100.0 - 100.0 / (1.0 + rs))
+    }
+
+    /// Analyze a token's candle series across every resolution in `Resolution::chart_set`
+    /// (1m/5m/15m/1h), combining each resolution's momentum/swing-high-low/breakout read into
+    /// one signal instead of `analyze_candles`' single-resolution percentage changes. Falls
+    /// back to `analyze_candles` on the fastest available resolution when no multi-resolution
+    /// breakout or aligned trend is found, so callers always get a real-bars-based signal.
+    pub fn analyze_multi_resolution(
+        candles_by_resolution: &HashMap<Resolution, Vec<Candle>>,
+        liquidity_usd: Decimal,
+    ) -> ChartSignal {
+        const LOOKBACK: usize = 20;
+
+        let reads: Vec<ResolutionRead> = Resolution::chart_set()
+            .into_iter()
+            .filter_map(|res| {
+                candles_by_resolution
+                    .get(&res)
+                    .and_then(|candles| Self::read_resolution(res, candles, LOOKBACK))
+            })
+            .collect();
+
+        let Some(fastest) = reads.first() else {
+            return ChartSignal {
+                action: TradeAction::Hold,
+                confidence: 0.0,
+                reason: "No candle data available".into(),
+                suggested_entry: Decimal::ZERO,
+                suggested_exit: Decimal::ZERO,
+            };
+        };
+        let fastest_resolution = fastest.resolution;
+
+        let price_now = candles_by_resolution
+            .get(&fastest_resolution)
+            .and_then(|c| c.last())
+            .map(|c| c.close)
+            .unwrap_or(Decimal::ZERO);
+
+        let breakouts_confirmed = reads.iter().filter(|r| r.breakout_confirmed).count();
+        let aligned_uptrend = reads.iter().all(|r| r.momentum_pct > 0.0);
+        let aligned_downtrend = reads.iter().all(|r| r.momentum_pct < 0.0);
+
+        let nearest_resistance = reads
+            .iter()
+            .map(|r| r.swing_high)
+            .filter(|&h| h > price_now)
+            .fold(None, |acc: Option<Decimal>, h| Some(acc.map_or(h, |a| a.min(h))));
+        let nearest_support = reads
+            .iter()
+            .map(|r| r.swing_low)
+            .filter(|&l| l < price_now)
+            .fold(None, |acc: Option<Decimal>, l| Some(acc.map_or(l, |a| a.max(l))));
+
+        // Two or more resolutions confirming a breakout above their own swing high, with every
+        // tracked resolution's momentum pointing the same way, is a much stronger signal than
+        // any single resolution's point-in-time percentage change. A real RSI on the fastest
+        // resolution tempers it when the move is already overbought rather than chasing it.
+        if breakouts_confirmed >= 2 && aligned_uptrend {
+            let rsi_overbought = fastest.rsi.is_some_and(|rsi| rsi > 80.0);
+            let confidence = (0.6 + 0.1 * breakouts_confirmed as f64).min(0.95);
+            return ChartSignal {
+                action: if rsi_overbought { TradeAction::Buy } else { TradeAction::StrongBuy },
+                confidence: if rsi_overbought { confidence * 0.7 } else { confidence },
+                reason: format!(
+                    "Breakout confirmed across {} resolution(s) with aligned uptrend{}",
+                    breakouts_confirmed,
+                    if rsi_overbought { " (RSI overbought, sizing down)" } else { "" },
+                ),
+                suggested_entry: price_now,
+                suggested_exit: nearest_resistance
+                    .map(|r| r * Decimal::from_f64_retain(1.1).unwrap())
+                    .unwrap_or(price_now * Decimal::from_f64_retain(1.3).unwrap()),
+            };
+        }
+
+        if aligned_downtrend {
+            let rsi_oversold = fastest.rsi.is_some_and(|rsi| rsi < 20.0);
+            return ChartSignal {
+                action: if rsi_oversold { TradeAction::Hold } else { TradeAction::Sell },
+                confidence: if rsi_oversold { 0.4 } else { 0.7 },
+                reason: if rsi_oversold {
+                    "Momentum down but RSI oversold - waiting for a bounce".into()
+                } else {
+                    "Momentum down across every tracked resolution".into()
+                },
+                suggested_entry: Decimal::ZERO,
+                suggested_exit: nearest_support.unwrap_or(price_now),
+            };
+        }
+
+        // No multi-resolution consensus - fall back to the same decision surface
+        // `analyze_candles` already gives single-resolution callers.
+        match candles_by_resolution.get(&fastest_resolution) {
+            Some(fast_candles) => Self::analyze_candles(fast_candles, liquidity_usd),
+            None => ChartSignal {
+                action: TradeAction::Hold,
+                confidence: 0.5,
+                reason: "No clear multi-resolution setup - waiting".into(),
+                suggested_entry: price_now,
+                suggested_exit: price_now,
+            },
+        }
+    }
+
+    /// One resolution's momentum/swing-high-low/breakout read over the `lookback` candles
+    /// preceding the latest (possibly still-open) one.
+    fn read_resolution(resolution: Resolution, candles: &[Candle], lookback: usize) -> Option<ResolutionRead> {
+        if candles.len() < 2 {
+            return None;
+        }
+
+        let latest = candles.last()?;
+        let window_start = candles.len().saturating_sub(lookback + 1);
+        let window = &candles[window_start..candles.len() - 1];
+
+        if window.is_empty() {
+            return None;
+        }
+
+        let swing_high = window.iter().map(|c| c.high).fold(window[0].high, Decimal::max);
+        let swing_low = window.iter().map(|c| c.low).fold(window[0].low, Decimal::min);
+
+        let reference = window[0].close;
+        let momentum_pct = if reference.is_zero() {
+            0.0
+        } else {
+            ((latest.close - reference) / reference * Decimal::from(100))
+                .to_string()
+                .parse::<f64>()
+                .unwrap_or(0.0)
+        };
+
+        let avg_volume = window.iter().map(|c| c.volume).sum::<Decimal>() / Decimal::from(window.len() as i64);
+        let breakout_confirmed = latest.close > swing_high
+            && (avg_volume.is_zero() || latest.volume > avg_volume * Decimal::from_f64_retain(1.5).unwrap());
+
+        let closes: Vec<Decimal> = candles[window_start..].iter().map(|c| c.close).collect();
+        let rsi = Self::calculate_rsi(&closes, RSI_PERIOD);
+
+        Some(ResolutionRead {
+            resolution,
+            momentum_pct,
+            swing_high,
+            swing_low,
+            breakout_confirmed,
+            rsi,
+        })
     }
 
     /// Detect if price is at support/resistance
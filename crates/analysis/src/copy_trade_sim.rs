@@ -0,0 +1,215 @@
+// Copy-trade replay simulator - answers "what would my results be if I had mirrored this
+// wallet?" by replaying its trade history with a copier's own sizing, fees, slippage, and
+// reaction latency, then running the result back through `WalletMetricsCalculator` the
+// same way a real account's trades would be.
+use chrono::Duration;
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use trading_core::{LotMatchingMode, Result, Trade, TradeSide, TradingError, WalletMetrics};
+use uuid::Uuid;
+
+use crate::wallet_metrics::WalletMetricsCalculator;
+
+fn checked_add(a: Decimal, b: Decimal) -> Result<Decimal> {
+    a.checked_add(b)
+        .ok_or_else(|| TradingError::ArithmeticOverflow(format!("{a} + {b} overflowed Decimal")))
+}
+
+fn checked_sub(a: Decimal, b: Decimal) -> Result<Decimal> {
+    a.checked_sub(b)
+        .ok_or_else(|| TradingError::ArithmeticOverflow(format!("{a} - {b} overflowed Decimal")))
+}
+
+/// How the copier sizes each mirrored buy.
+#[derive(Debug, Clone, Copy)]
+pub enum CopyTradeSizing {
+    /// Spend the same quote-currency amount on every mirrored buy, regardless of how
+    /// much capital is left.
+    Fixed(Decimal),
+    /// Spend a fixed percentage of the copier's running capital, starting from
+    /// `starting_capital` and updated as mirrored buys/sells settle.
+    PercentOfCapital { starting_capital: Decimal, percent: f64 },
+}
+
+/// Replay configuration for [`CopyTradeSimulator::simulate`].
+#[derive(Debug, Clone, Copy)]
+pub struct CopyTradeSimConfig {
+    pub sizing: CopyTradeSizing,
+    /// Per-trade fee, charged on both the buy spend and the sell proceeds.
+    pub fee_bps: u16,
+    /// Slippage applied against the copier, worsening the effective fill price on both
+    /// sides (fewer tokens per buy, less quote currency per sell).
+    pub slippage_bps: u16,
+    /// How long after the source wallet's fill the copier's mirrored fill lands -
+    /// models reaction latency between seeing the trade and acting on it.
+    pub entry_delay_seconds: i64,
+    /// Lot-matching mode used when running the mirrored trades back through
+    /// `calculate_metrics`.
+    pub lot_matching: LotMatchingMode,
+}
+
+/// How much of the source wallet's apparent edge survives the copier's sizing, fees,
+/// slippage, and entry delay - every field is `copier - source`, so a negative number
+/// means the copy performed worse than the original.
+#[derive(Debug, Clone, Copy)]
+pub struct CopyTradeDivergence {
+    pub total_pnl_percentage_delta: f64,
+    pub win_rate_delta: f64,
+    pub sharpe_ratio_delta: Option<f64>,
+    pub max_drawdown_delta: f64,
+}
+
+impl CopyTradeDivergence {
+    fn compute(source: &WalletMetrics, copier: &WalletMetrics) -> Self {
+        Self {
+            total_pnl_percentage_delta: copier.total_pnl_percentage - source.total_pnl_percentage,
+            win_rate_delta: copier.win_rate - source.win_rate,
+            sharpe_ratio_delta: match (source.sharpe_ratio, copier.sharpe_ratio) {
+                (Some(source_sharpe), Some(copier_sharpe)) => Some(copier_sharpe - source_sharpe),
+                _ => None,
+            },
+            max_drawdown_delta: copier.max_drawdown - source.max_drawdown,
+        }
+    }
+}
+
+/// Result of a copy-trade replay: the copier's own metrics, the source wallet's metrics
+/// they were compared against, and the divergence between the two.
+#[derive(Debug, Clone)]
+pub struct CopyTradeSimResult {
+    pub source_metrics: WalletMetrics,
+    pub copier_metrics: WalletMetrics,
+    pub divergence: CopyTradeDivergence,
+}
+
+pub struct CopyTradeSimulator;
+
+impl CopyTradeSimulator {
+    /// Replay `source_trades` as if a copier had mirrored every fill under `config`, then
+    /// compare the resulting metrics against the source wallet's own.
+    pub fn simulate(source_trades: &[Trade], config: &CopyTradeSimConfig) -> Result<CopyTradeSimResult> {
+        let source_metrics = WalletMetricsCalculator::calculate_metrics(source_trades, config.lot_matching)?;
+
+        let mirrored_trades = Self::replay_trades(source_trades, config)?;
+        let copier_metrics = WalletMetricsCalculator::calculate_metrics(&mirrored_trades, config.lot_matching)?;
+
+        let divergence = CopyTradeDivergence::compute(&source_metrics, &copier_metrics);
+
+        Ok(CopyTradeSimResult { source_metrics, copier_metrics, divergence })
+    }
+
+    /// Build the copier's own trade list: sized per `config.sizing`, degraded by fees and
+    /// slippage, and shifted by `config.entry_delay_seconds`. Sells mirror the *fraction*
+    /// of the source wallet's held position being exited, not the raw token amount, since
+    /// the copier's position in a token is sized independently of the source's.
+    fn replay_trades(source_trades: &[Trade], config: &CopyTradeSimConfig) -> Result<Vec<Trade>> {
+        let mut sorted_trades = source_trades.to_vec();
+        sorted_trades.sort_by_key(|t| t.timestamp);
+
+        let fee_factor = Decimal::from(config.fee_bps) / Decimal::from(10_000);
+        let slippage_factor = Decimal::from(config.slippage_bps) / Decimal::from(10_000);
+        let delay = Duration::seconds(config.entry_delay_seconds);
+
+        let mut source_qty: HashMap<Pubkey, Decimal> = HashMap::new();
+        let mut copier_qty: HashMap<Pubkey, Decimal> = HashMap::new();
+        let mut capital = match config.sizing {
+            CopyTradeSizing::PercentOfCapital { starting_capital, .. } => starting_capital,
+            CopyTradeSizing::Fixed(_) => Decimal::ZERO,
+        };
+
+        let mut mirrored = Vec::with_capacity(sorted_trades.len());
+
+        for trade in sorted_trades {
+            match trade.side {
+                TradeSide::Buy => {
+                    *source_qty.entry(trade.token_mint).or_insert(Decimal::ZERO) += trade.amount_out;
+
+                    let intended_spend = match config.sizing {
+                        CopyTradeSizing::Fixed(amount) => amount,
+                        CopyTradeSizing::PercentOfCapital { percent, .. } => {
+                            capital * Decimal::try_from(percent / 100.0).unwrap_or(Decimal::ZERO)
+                        }
+                    };
+
+                    if intended_spend <= Decimal::ZERO {
+                        continue;
+                    }
+
+                    let fee = intended_spend * fee_factor;
+                    let effective_spend = checked_add(intended_spend, fee)?;
+
+                    let source_price = if trade.amount_out.is_zero() {
+                        Decimal::ZERO
+                    } else {
+                        trade.amount_in / trade.amount_out
+                    };
+                    // Slippage makes the copier's effective entry price worse than the
+                    // source's, so it receives fewer tokens for the same spend.
+                    let effective_price = source_price * (Decimal::ONE + slippage_factor);
+                    let tokens_received =
+                        if effective_price.is_zero() { Decimal::ZERO } else { intended_spend / effective_price };
+
+                    if let CopyTradeSizing::PercentOfCapital { .. } = config.sizing {
+                        capital = checked_sub(capital, effective_spend)?;
+                    }
+
+                    *copier_qty.entry(trade.token_mint).or_insert(Decimal::ZERO) += tokens_received;
+
+                    mirrored.push(Trade {
+                        id: Uuid::new_v4(),
+                        amount_in: effective_spend,
+                        amount_out: tokens_received,
+                        timestamp: trade.timestamp + delay,
+                        ..trade
+                    });
+                }
+                TradeSide::Sell => {
+                    let held_before_sell = source_qty.get(&trade.token_mint).copied().unwrap_or(Decimal::ZERO);
+                    let sold_fraction = if held_before_sell.is_zero() {
+                        Decimal::ZERO
+                    } else {
+                        (trade.amount_in / held_before_sell).min(Decimal::ONE)
+                    };
+                    *source_qty.entry(trade.token_mint).or_insert(Decimal::ZERO) -=
+                        trade.amount_in.min(held_before_sell);
+
+                    let copier_held = copier_qty.get(&trade.token_mint).copied().unwrap_or(Decimal::ZERO);
+                    let tokens_sold = copier_held * sold_fraction;
+
+                    if tokens_sold <= Decimal::ZERO {
+                        continue;
+                    }
+
+                    let source_price = if trade.amount_in.is_zero() {
+                        Decimal::ZERO
+                    } else {
+                        trade.amount_out / trade.amount_in
+                    };
+                    // Slippage works against the copier here too: a worse effective exit
+                    // price, i.e. less quote currency per token sold.
+                    let effective_price = source_price * (Decimal::ONE - slippage_factor).max(Decimal::ZERO);
+                    let gross_proceeds = tokens_sold * effective_price;
+                    let fee = gross_proceeds * fee_factor;
+                    let net_proceeds = checked_sub(gross_proceeds, fee)?.max(Decimal::ZERO);
+
+                    *copier_qty.entry(trade.token_mint).or_insert(Decimal::ZERO) -= tokens_sold;
+
+                    if let CopyTradeSizing::PercentOfCapital { .. } = config.sizing {
+                        capital = checked_add(capital, net_proceeds)?;
+                    }
+
+                    mirrored.push(Trade {
+                        id: Uuid::new_v4(),
+                        amount_in: tokens_sold,
+                        amount_out: net_proceeds,
+                        timestamp: trade.timestamp + delay,
+                        ..trade
+                    });
+                }
+            }
+        }
+
+        Ok(mirrored)
+    }
+}
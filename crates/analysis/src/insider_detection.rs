@@ -1,13 +1,30 @@
 // Insider detection system
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
 use solana_sdk::pubkey::Pubkey;
 use std::collections::HashMap;
-use trading_core::{InsiderActivity, InsiderActivityType, Result, Trade};
-use chrono::Utc;
+use trading_core::{InsiderActivity, InsiderActivityType, Trade, TradeSide};
+
+/// Sliding window (minutes) within which `N_COORDINATED_WALLETS` or more tracked wallets
+/// entering the same token counts as coordinated buying rather than coincidence.
+const COORDINATED_WINDOW_MINUTES: i64 = 5;
+const N_COORDINATED_WALLETS: usize = 3;
+
+/// Width (minutes) of the time buckets `check_timing_correlation` aggregates signed volume into.
+const CORRELATION_BUCKET_MINUTES: i64 = 1;
+/// Below this many overlapping non-empty buckets, a correlation coefficient is too noisy to
+/// trust - `check_timing_correlation` returns 0.0 instead of a number nobody should act on.
+const MIN_OVERLAPPING_BUCKETS: usize = 3;
+
+/// A wallet's buys for a token mint fall in this bottom percentile of the mint's observed
+/// entry market caps to count as early accumulation.
+const EARLY_ACCUMULATION_PERCENTILE: f64 = 0.10;
 
 pub struct InsiderDetector;
 
 impl InsiderDetector {
-    /// Detect insider activity patterns
+    /// Detect insider activity patterns for `wallet`, using `all_wallet_trades` (every
+    /// tracked wallet's trade history, keyed by wallet) as the peer set to compare against.
     pub fn detect_insider_activity(
         wallet: &Pubkey,
         trades: &[Trade],
@@ -15,21 +32,277 @@ impl InsiderDetector {
     ) -> Vec<InsiderActivity> {
         let mut activities = Vec::new();
 
-        // Detect coordinated buying
-        // TODO: Implement timing correlation analysis
-        // - Check if multiple wallets buy the same token within short time window
-        // - Analyze if trades happen before major price movements
+        let mints: std::collections::HashSet<Pubkey> = trades
+            .iter()
+            .filter(|t| t.side == TradeSide::Buy)
+            .map(|t| t.token_mint)
+            .collect();
 
-        // Detect early accumulation
-        // TODO: Check if wallet buys at very low market caps consistently
+        for mint in mints {
+            if let Some(activity) =
+                Self::detect_coordinated_buying(wallet, &mint, all_wallet_trades)
+            {
+                activities.push(activity);
+            }
+
+            if let Some(activity) = Self::detect_early_accumulation(wallet, &mint, all_wallet_trades) {
+                activities.push(activity);
+            }
+        }
 
-        // Placeholder for now
         activities
     }
 
-    /// Check for timing correlation between wallets
+    /// Look for a cluster of `N_COORDINATED_WALLETS`+ wallets whose first buy of `mint` all
+    /// land within `COORDINATED_WINDOW_MINUTES` of each other, with `wallet` among them.
+    fn detect_coordinated_buying(
+        wallet: &Pubkey,
+        mint: &Pubkey,
+        all_wallet_trades: &HashMap<Pubkey, Vec<Trade>>,
+    ) -> Option<InsiderActivity> {
+        let mut first_buys: Vec<(Pubkey, DateTime<Utc>)> = all_wallet_trades
+            .iter()
+            .filter_map(|(peer, peer_trades)| {
+                peer_trades
+                    .iter()
+                    .filter(|t| t.token_mint == *mint && t.side == TradeSide::Buy)
+                    .map(|t| t.timestamp)
+                    .min()
+                    .map(|first| (*peer, first))
+            })
+            .collect();
+
+        first_buys.sort_by_key(|(_, ts)| *ts);
+
+        let window = Duration::minutes(COORDINATED_WINDOW_MINUTES);
+
+        // Slide a window over the sorted entry times and report the earliest-starting cluster
+        // that contains `wallet` and meets the minimum cluster size.
+        for start in 0..first_buys.len() {
+            let window_end = first_buys[start].1 + window;
+            let cluster: Vec<&(Pubkey, DateTime<Utc>)> = first_buys[start..]
+                .iter()
+                .take_while(|(_, ts)| *ts <= window_end)
+                .collect();
+
+            if cluster.len() < N_COORDINATED_WALLETS {
+                continue;
+            }
+
+            if !cluster.iter().any(|(peer, _)| peer == wallet) {
+                continue;
+            }
+
+            let span = cluster.last().unwrap().1 - cluster.first().unwrap().1;
+            let correlated_wallets: Vec<Pubkey> = cluster
+                .iter()
+                .map(|(peer, _)| *peer)
+                .filter(|peer| peer != wallet)
+                .collect();
+
+            // Tighter clusters and more participants are more likely to be coordinated
+            // rather than independent wallets happening to like the same token.
+            let tightness = 1.0 - (span.num_seconds() as f64 / window.num_seconds() as f64);
+            let size_factor = (cluster.len() as f64 / (N_COORDINATED_WALLETS as f64 + 2.0)).min(1.0);
+            let confidence = (0.5 + 0.3 * tightness.max(0.0) + 0.2 * size_factor).min(1.0);
+
+            return Some(InsiderActivity {
+                wallet: *wallet,
+                token_mint: *mint,
+                activity_type: InsiderActivityType::CoordinatedBuying,
+                confidence,
+                correlated_wallets,
+                timing_score: tightness.max(0.0),
+                evidence: vec![format!(
+                    "{} wallets entered {} within {} seconds",
+                    cluster.len(),
+                    mint,
+                    span.num_seconds(),
+                )],
+                detected_at: Utc::now(),
+            });
+        }
+
+        None
+    }
+
+    /// Flag `wallet` if its buys of `mint` consistently land in the bottom
+    /// `EARLY_ACCUMULATION_PERCENTILE` of every tracked wallet's observed entry market caps.
+    fn detect_early_accumulation(
+        wallet: &Pubkey,
+        mint: &Pubkey,
+        all_wallet_trades: &HashMap<Pubkey, Vec<Trade>>,
+    ) -> Option<InsiderActivity> {
+        let mut observed_market_caps: Vec<Decimal> = all_wallet_trades
+            .values()
+            .flat_map(|trades| trades.iter())
+            .filter(|t| t.token_mint == *mint && t.side == TradeSide::Buy)
+            .map(|t| t.market_cap_at_trade)
+            .collect();
+
+        if observed_market_caps.is_empty() {
+            return None;
+        }
+        observed_market_caps.sort();
+
+        let threshold_idx = ((observed_market_caps.len() as f64 - 1.0) * EARLY_ACCUMULATION_PERCENTILE)
+            .round() as usize;
+        let threshold = observed_market_caps[threshold_idx];
+
+        let wallet_buys: Vec<&Trade> = all_wallet_trades
+            .get(wallet)
+            .into_iter()
+            .flatten()
+            .filter(|t| t.token_mint == *mint && t.side == TradeSide::Buy)
+            .collect();
+
+        if wallet_buys.is_empty() || !wallet_buys.iter().all(|t| t.market_cap_at_trade <= threshold) {
+            return None;
+        }
+
+        let wallet_trades_for_mint: Vec<Trade> = wallet_buys.iter().map(|t| (*t).clone()).collect();
+
+        // A peer whose volume timing correlates highly with this wallet's, also entering early,
+        // turns a lone low-market-cap entry into a much stronger signal than either fact alone.
+        let mut best_peer: Option<(Pubkey, f64)> = None;
+        for (peer, peer_trades) in all_wallet_trades {
+            if peer == wallet {
+                continue;
+            }
+            let peer_trades_for_mint: Vec<Trade> = peer_trades
+                .iter()
+                .filter(|t| t.token_mint == *mint)
+                .cloned()
+                .collect();
+            if peer_trades_for_mint.is_empty() {
+                continue;
+            }
+
+            let correlation = Self::check_timing_correlation(&wallet_trades_for_mint, &peer_trades_for_mint);
+            if best_peer.map_or(true, |(_, best)| correlation > best) {
+                best_peer = Some((*peer, correlation));
+            }
+        }
+
+        let mut confidence = (0.5 + 0.1 * wallet_buys.len().min(5) as f64).min(0.9);
+        let mut correlated_wallets = Vec::new();
+        let mut timing_score = 0.0;
+        let mut evidence = vec![format!(
+            "{} buy(s) of {} all at or below the bottom {:.0}% of observed entry market cap ({})",
+            wallet_buys.len(),
+            mint,
+            EARLY_ACCUMULATION_PERCENTILE * 100.0,
+            threshold,
+        )];
+
+        if let Some((peer, correlation)) = best_peer {
+            if correlation > 0.5 {
+                confidence = (confidence + 0.1 * correlation).min(0.99);
+                timing_score = correlation;
+                correlated_wallets.push(peer);
+                evidence.push(format!(
+                    "Volume timing correlates {:.2} with peer wallet {}",
+                    correlation, peer,
+                ));
+            }
+        }
+
+        Some(InsiderActivity {
+            wallet: *wallet,
+            token_mint: *mint,
+            activity_type: InsiderActivityType::EarlyAccumulation,
+            confidence,
+            correlated_wallets,
+            timing_score,
+            evidence,
+            detected_at: Utc::now(),
+        })
+    }
+
+    /// Pearson correlation of two wallets' signed trade volume, bucketed into
+    /// `CORRELATION_BUCKET_MINUTES`-wide time windows over their overlapping range. A high score
+    /// combined with a low-market-cap entry is a stronger signal than either alone - see
+    /// `detect_early_accumulation` for the market-cap half of that check.
     fn check_timing_correlation(trades1: &[Trade], trades2: &[Trade]) -> f64 {
-        // TODO: Implement correlation coefficient calculation
-        0.0
+        if trades1.is_empty() || trades2.is_empty() {
+            return 0.0;
+        }
+
+        let start = trades1
+            .iter()
+            .chain(trades2.iter())
+            .map(|t| t.timestamp)
+            .min()
+            .unwrap();
+        let end = trades1
+            .iter()
+            .chain(trades2.iter())
+            .map(|t| t.timestamp)
+            .max()
+            .unwrap();
+
+        let bucket_secs = Duration::minutes(CORRELATION_BUCKET_MINUTES).num_seconds().max(1);
+        let num_buckets = (((end - start).num_seconds() / bucket_secs) + 1) as usize;
+
+        let mut x = vec![0.0; num_buckets];
+        let mut y = vec![0.0; num_buckets];
+
+        Self::bucket_signed_volume(trades1, start, bucket_secs, &mut x);
+        Self::bucket_signed_volume(trades2, start, bucket_secs, &mut y);
+
+        let overlapping_non_empty = x
+            .iter()
+            .zip(y.iter())
+            .filter(|(a, b)| **a != 0.0 && **b != 0.0)
+            .count();
+
+        if overlapping_non_empty < MIN_OVERLAPPING_BUCKETS {
+            return 0.0;
+        }
+
+        let n = x.len() as f64;
+        let mean_x = x.iter().sum::<f64>() / n;
+        let mean_y = y.iter().sum::<f64>() / n;
+
+        let mut covariance = 0.0;
+        let mut var_x = 0.0;
+        let mut var_y = 0.0;
+
+        for i in 0..x.len() {
+            let dx = x[i] - mean_x;
+            let dy = y[i] - mean_y;
+            covariance += dx * dy;
+            var_x += dx * dx;
+            var_y += dy * dy;
+        }
+
+        if var_x == 0.0 || var_y == 0.0 {
+            return 0.0;
+        }
+
+        covariance / (var_x.sqrt() * var_y.sqrt())
+    }
+
+    /// Accumulate each trade's signed SOL volume (positive for buys, negative for sells) into
+    /// the bucket it falls into relative to `start`.
+    fn bucket_signed_volume(trades: &[Trade], start: DateTime<Utc>, bucket_secs: i64, buckets: &mut [f64]) {
+        use rust_decimal::prelude::ToPrimitive;
+
+        for trade in trades {
+            let offset = (trade.timestamp - start).num_seconds();
+            if offset < 0 {
+                continue;
+            }
+            let idx = (offset / bucket_secs) as usize;
+            if idx >= buckets.len() {
+                continue;
+            }
+
+            let signed_volume = match trade.side {
+                TradeSide::Buy => trade.amount_in.to_f64().unwrap_or(0.0),
+                TradeSide::Sell => -trade.amount_out.to_f64().unwrap_or(0.0),
+            };
+            buckets[idx] += signed_volume;
+        }
     }
 }
@@ -3,9 +3,17 @@ pub mod smart_money_score;
 pub mod insider_detection;
 pub mod pattern_recognition;
 pub mod chart_analyzer;
+pub mod candles;
+pub mod position_sizing;
+pub mod copy_trade_sim;
+pub mod capital_allocation;
 
 pub use wallet_metrics::*;
 pub use smart_money_score::*;
 pub use insider_detection::*;
 pub use pattern_recognition::*;
 pub use chart_analyzer::*;
+pub use candles::*;
+pub use position_sizing::*;
+pub use copy_trade_sim::*;
+pub use capital_allocation::*;
@@ -0,0 +1,125 @@
+// Dynamic position sizing: replaces `RiskLimits::default`'s flat `max_position_size_usd`
+// with a rule pipeline driven by the signal's own wallet/market/portfolio state, so a
+// weak-track-record wallet or a thin-liquidity token gets a smaller size instead of every
+// trade reaching for the static cap, and a breached daily loss limit blocks the trade outright.
+use rust_decimal::Decimal;
+use trading_core::{MarketData, Portfolio, RiskLimits, StrategyMode, WalletAnalysis};
+
+/// Which rule, if any, bound the final size below the percentage cap - populates
+/// `CopyTradeSignal.reasons` so a shrunk or blocked signal explains itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizingConstraint {
+    /// No rule reduced the size below the portfolio-percentage cap.
+    None,
+    /// Scaled down to bound price impact against thin liquidity.
+    LiquidityConstrained,
+    /// Today's realized loss already breached `max_daily_loss_usd`; trading blocked.
+    DailyLossLimitBreached,
+}
+
+/// The sizing engine's output: an approved `amount_usd` (zero if blocked), the slippage
+/// tolerance to use, which constraint decided the final number, and the reasoning trail.
+#[derive(Debug, Clone)]
+pub struct SizingDecision {
+    pub approved_size_usd: Decimal,
+    pub max_slippage_bps: u16,
+    pub constraint: SizingConstraint,
+    pub reasons: Vec<String>,
+}
+
+pub struct PositionSizer;
+
+impl PositionSizer {
+    /// Compute a position size for a prospective copy-trade, scaling a flat
+    /// `risk_limits.max_position_size_percentage` of `portfolio.total_value_usd` by the
+    /// source wallet's track record and the token's liquidity headroom.
+    pub fn size(
+        wallet_analysis: &WalletAnalysis,
+        market_data: &MarketData,
+        portfolio: &Portfolio,
+        strategy: StrategyMode,
+        risk_limits: &RiskLimits,
+    ) -> SizingDecision {
+        let max_slippage_bps = Self::slippage_for_strategy(strategy, risk_limits);
+
+        // Blocked outright if today's realized loss already breached the daily cap -
+        // `pnl_today` is negative when losing, so the magnitude is what's compared.
+        if portfolio.pnl_today.is_sign_negative()
+            && portfolio.pnl_today.abs() >= risk_limits.max_daily_loss_usd
+        {
+            return SizingDecision {
+                approved_size_usd: Decimal::ZERO,
+                max_slippage_bps,
+                constraint: SizingConstraint::DailyLossLimitBreached,
+                reasons: vec![format!(
+                    "Daily loss limit breached: ${} lost today >= ${} max - no new positions today",
+                    portfolio.pnl_today.abs(),
+                    risk_limits.max_daily_loss_usd
+                )],
+            };
+        }
+
+        let mut reasons = Vec::new();
+
+        // Base size: the percentage cap applied to current portfolio value, scaled by
+        // the wallet's track record rather than always reaching for the max.
+        let pct_cap = portfolio.total_value_usd
+            * Decimal::try_from(risk_limits.max_position_size_percentage / 100.0).unwrap_or(Decimal::ZERO);
+        let confidence = Self::confidence_factor(wallet_analysis);
+        let mut size = pct_cap * Decimal::try_from(confidence).unwrap_or(Decimal::ONE);
+        reasons.push(format!(
+            "Base size ${} = {:.0}% of portfolio (${}) scaled by {:.2}x wallet confidence",
+            size, risk_limits.max_position_size_percentage, portfolio.total_value_usd, confidence
+        ));
+
+        let mut constraint = SizingConstraint::None;
+
+        // Scale down as liquidity approaches the configured floor, to bound price impact:
+        // at `min_liquidity_usd` the size is halved, at 2x the floor (or above) it's
+        // unconstrained. Below the floor the signal should already be rejected upstream,
+        // but this still shrinks rather than sizing into it.
+        if risk_limits.min_liquidity_usd > Decimal::ZERO {
+            let liquidity_ratio = (market_data.liquidity_usd / risk_limits.min_liquidity_usd)
+                .min(Decimal::from(2))
+                / Decimal::from(2);
+
+            if liquidity_ratio < Decimal::ONE {
+                let scaled = size * liquidity_ratio.max(Decimal::ZERO);
+                reasons.push(format!(
+                    "Scaled from ${} to ${} - liquidity ${} is close to the ${} floor",
+                    size, scaled, market_data.liquidity_usd, risk_limits.min_liquidity_usd
+                ));
+                size = scaled;
+                constraint = SizingConstraint::LiquidityConstrained;
+            }
+        }
+
+        SizingDecision {
+            approved_size_usd: size.max(Decimal::ZERO),
+            max_slippage_bps,
+            constraint,
+            reasons,
+        }
+    }
+
+    /// 0.3x-1.3x multiplier from the wallet's win rate, smart-money score, and max
+    /// drawdown - a strong track record sizes slightly above the flat base, a weak one
+    /// is scaled well below it instead of being rejected outright.
+    fn confidence_factor(analysis: &WalletAnalysis) -> f64 {
+        let win_rate_factor = (analysis.metrics.win_rate / 100.0).clamp(0.0, 1.0);
+        let score_factor = analysis.smart_money_score.clamp(0.0, 1.0);
+        let drawdown_penalty = (analysis.metrics.max_drawdown / 100.0).clamp(0.0, 1.0);
+
+        (0.5 + win_rate_factor * 0.4 + score_factor * 0.4 - drawdown_penalty * 0.3).clamp(0.3, 1.3)
+    }
+
+    /// Tighter tolerance for slower strategies (less urgency, more reason to wait for a
+    /// good fill), looser for `Scalping` where missing the fill defeats the trade.
+    fn slippage_for_strategy(strategy: StrategyMode, risk_limits: &RiskLimits) -> u16 {
+        match strategy {
+            StrategyMode::Scalping => risk_limits.max_slippage_bps.saturating_mul(2),
+            StrategyMode::DayTrading => risk_limits.max_slippage_bps,
+            StrategyMode::SwingTrading => risk_limits.max_slippage_bps / 2,
+        }
+    }
+}
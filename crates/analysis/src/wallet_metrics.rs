@@ -1,26 +1,110 @@
 use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use solana_sdk::pubkey::Pubkey;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use tracing::{debug, info};
 use trading_core::{
-    PositionStatus, Result, Trade, TradeSide, TradePosition, TradingError, WalletAnalysis,
-    WalletMetrics,
+    LotMatchingMode, PositionStatus, Result, ReturnSeriesMetrics, Trade, TradeSide, TradePosition,
+    TradingError, WalletAnalysis, WalletMetrics,
 };
 use uuid::Uuid;
 
+/// Average calendar year length used to annualize per-trade returns.
+const SECONDS_PER_YEAR: f64 = 365.25 * 24.0 * 3600.0;
+
+/// Default plausibility bound for `TradePosition::hold_time_seconds` - a position held
+/// longer than this is flagged `timestamp_suspect` rather than trusted outright. 30 days
+/// comfortably covers this bot's actual hold times (minutes to a few days) with headroom.
+pub const DEFAULT_MAX_PLAUSIBLE_HOLD_SECONDS: f64 = 30.0 * 24.0 * 3600.0;
+
+/// Lossless `Decimal` -> `f64` conversion for the metrics below - `Decimal::to_string().parse()`
+/// silently swallowed out-of-range values as `0.0` via `unwrap_or`, which looks like a
+/// perfectly ordinary metric rather than a conversion failure.
+fn to_f64(value: Decimal) -> Result<f64> {
+    value
+        .to_f64()
+        .ok_or_else(|| TradingError::ArithmeticOverflow(format!("{value} has no lossless f64 representation")))
+}
+
+fn checked_add(a: Decimal, b: Decimal) -> Result<Decimal> {
+    a.checked_add(b)
+        .ok_or_else(|| TradingError::ArithmeticOverflow(format!("{a} + {b} overflowed Decimal")))
+}
+
+fn checked_sub(a: Decimal, b: Decimal) -> Result<Decimal> {
+    a.checked_sub(b)
+        .ok_or_else(|| TradingError::ArithmeticOverflow(format!("{a} - {b} overflowed Decimal")))
+}
+
+fn checked_mul(a: Decimal, b: Decimal) -> Result<Decimal> {
+    a.checked_mul(b)
+        .ok_or_else(|| TradingError::ArithmeticOverflow(format!("{a} * {b} overflowed Decimal")))
+}
+
+fn checked_div(a: Decimal, b: Decimal) -> Result<Decimal> {
+    a.checked_div(b)
+        .ok_or_else(|| TradingError::ArithmeticOverflow(format!("{a} / {b} overflowed or divided by zero")))
+}
+
+/// Chronological equity-curve stats that feed into [`WalletMetrics`]'s drawdown fields -
+/// internal to how [`WalletMetricsCalculator::calculate_equity_curve`] reports them.
+struct EquityCurveMetrics {
+    max_drawdown: f64,
+    max_drawdown_start: Option<DateTime<Utc>>,
+    max_drawdown_end: Option<DateTime<Utc>>,
+    underwater_seconds: f64,
+}
+
+/// An open buy lot awaiting a matching sell under [`LotMatchingMode::Fifo`] /
+/// [`LotMatchingMode::Lifo`] - `remaining_qty` shrinks as sells partially consume it and
+/// the lot is dropped once it reaches zero.
+struct Lot {
+    trade: Trade,
+    remaining_qty: Decimal,
+}
+
+/// Running weighted-average cost basis for a token under [`LotMatchingMode::AverageCost`].
+/// `last_buy` is kept only as a representative `Trade` (timestamp, dex, price) to
+/// synthesize an `entry_trade` for positions drawn from the pool - the pool itself has no
+/// per-lot timestamps to report.
+struct AverageCostPool {
+    quantity: Decimal,
+    cost_basis: Decimal,
+    last_buy: Trade,
+}
+
+/// Clone `trade` but override its `amount_in`/`amount_out` - used to represent the slice
+/// of a buy or sell consumed by a single matched fill, so a `TradePosition` can still
+/// carry a single `Trade` on each side even when a fill only partially matched it.
+fn with_amounts(trade: &Trade, amount_in: Decimal, amount_out: Decimal) -> Trade {
+    Trade {
+        amount_in,
+        amount_out,
+        ..trade.clone()
+    }
+}
+
 /// Wallet metrics calculator
 pub struct WalletMetricsCalculator;
 
 impl WalletMetricsCalculator {
-    /// Calculate comprehensive wallet metrics from trade history
-    pub fn calculate_metrics(trades: &[Trade]) -> Result<WalletMetrics> {
+    /// Calculate comprehensive wallet metrics from trade history. `mode` controls which
+    /// buy lot(s) each sell is matched against - see [`LotMatchingMode`].
+    pub fn calculate_metrics(trades: &[Trade], mode: LotMatchingMode) -> Result<WalletMetrics> {
         if trades.is_empty() {
             return Ok(WalletMetrics::default());
         }
 
-        // Group trades into positions (match buys with sells)
-        let positions = Self::group_trades_into_positions(trades);
+        // Group trades into positions (match buys with sells). Positions flagged
+        // `timestamp_suspect` (a sell before its matched buy, or a hold time past
+        // `DEFAULT_MAX_PLAUSIBLE_HOLD_SECONDS`) are dropped here rather than trusted into
+        // Sharpe/drawdown/equity-curve - corrupt feed data shouldn't poison metrics that
+        // otherwise look perfectly ordinary.
+        let positions: Vec<TradePosition> = Self::group_trades_into_positions(trades, DEFAULT_MAX_PLAUSIBLE_HOLD_SECONDS, mode)?
+            .into_iter()
+            .filter(|p| !p.timestamp_suspect)
+            .collect();
 
         let mut total_pnl = Decimal::ZERO;
         let mut winning_trades = 0;
@@ -32,7 +116,7 @@ impl WalletMetricsCalculator {
 
         for position in &positions {
             if let Some(pnl) = position.pnl {
-                total_pnl += pnl;
+                total_pnl = checked_add(total_pnl, pnl)?;
                 pnl_values.push(pnl);
 
                 if pnl > Decimal::ZERO {
@@ -79,32 +163,42 @@ impl WalletMetricsCalculator {
 
         // Calculate Sharpe ratio (simplified)
         let sharpe_ratio = if !pnl_values.is_empty() {
-            Some(Self::calculate_sharpe_ratio(&pnl_values))
+            Some(Self::calculate_sharpe_ratio(&pnl_values)?)
         } else {
             None
         };
 
-        // Calculate max drawdown
-        let max_drawdown = Self::calculate_max_drawdown(&pnl_values);
+        // Chronological equity curve (by exit timestamp, not match order) for max drawdown
+        // and underwater duration.
+        let equity_curve = Self::calculate_equity_curve(&positions)?;
+        let max_drawdown = equity_curve.max_drawdown;
+
+        let return_metrics =
+            Self::calculate_return_series_metrics(&positions, avg_hold_time_seconds, max_drawdown)?;
 
         // Calculate volume metrics
         let now = Utc::now();
         let (trades_24h, volume_24h) = Self::calculate_time_window_stats(trades, now, 24 * 3600);
         let (trades_7d, volume_7d) = Self::calculate_time_window_stats(trades, now, 7 * 24 * 3600);
 
-        // Calculate total PnL percentage
-        let total_pnl_percentage = if !pnl_values.is_empty() {
-            // Approximate as average PnL percentage
-            pnl_values
-                .iter()
-                .map(|&pnl| (pnl / Decimal::from(100)) * Decimal::from(100)) // Simplified
-                .sum::<Decimal>()
-                .to_string()
-                .parse::<f64>()
-                .unwrap_or(0.0)
-                / pnl_values.len() as f64
-        } else {
-            0.0
+        // Total PnL percentage, weighted by each position's entry size rather than a flat
+        // average - a 50% gain on a $10 position shouldn't move the number as much as a 5%
+        // gain on a $10,000 one.
+        let total_pnl_percentage = {
+            let mut weighted_sum = Decimal::ZERO;
+            let mut weight_total = Decimal::ZERO;
+
+            for position in &positions {
+                if let Some(pnl_pct) = position.pnl_percentage {
+                    let weight = position.entry_trade.amount_in;
+                    let weighted_pct = Decimal::from_f64_retain(pnl_pct).unwrap_or(Decimal::ZERO) * weight;
+
+                    weighted_sum = checked_add(weighted_sum, weighted_pct)?;
+                    weight_total = checked_add(weight_total, weight)?;
+                }
+            }
+
+            if weight_total.is_zero() { 0.0 } else { to_f64(weighted_sum / weight_total)? }
         };
 
         Ok(WalletMetrics {
@@ -120,17 +214,33 @@ impl WalletMetricsCalculator {
             largest_loss,
             sharpe_ratio,
             max_drawdown,
+            max_drawdown_start: equity_curve.max_drawdown_start,
+            max_drawdown_end: equity_curve.max_drawdown_end,
+            underwater_seconds: equity_curve.underwater_seconds,
             trades_last_24h: trades_24h,
             trades_last_7d: trades_7d,
             volume_24h,
             volume_7d,
+            return_metrics,
         })
     }
 
-    /// Group trades into positions (match buys with sells)
-    fn group_trades_into_positions(trades: &[Trade]) -> Vec<TradePosition> {
+    /// Group trades into positions (match buys with sells), consuming buy lots in the
+    /// order `mode` dictates. A sell smaller than the lot it's matched against splits
+    /// that lot (the remainder stays open for the next sell); a sell larger than the
+    /// lot spans into the next one, producing multiple [`TradePosition`]s from one sell.
+    /// `max_plausible_hold_seconds` bounds how long a position is allowed to have been
+    /// held before it's flagged as suspect, the same way `ExecutionGuard::check_staleness`
+    /// bounds slot drift - corrupt or out-of-order feed data shouldn't silently poison
+    /// downstream metrics.
+    fn group_trades_into_positions(
+        trades: &[Trade],
+        max_plausible_hold_seconds: f64,
+        mode: LotMatchingMode,
+    ) -> Result<Vec<TradePosition>> {
         let mut positions = Vec::new();
-        let mut open_positions: HashMap<Pubkey, Vec<Trade>> = HashMap::new();
+        let mut open_lots: HashMap<Pubkey, VecDeque<Lot>> = HashMap::new();
+        let mut avg_pools: HashMap<Pubkey, AverageCostPool> = HashMap::new();
 
         // Sort trades by timestamp
         let mut sorted_trades = trades.to_vec();
@@ -138,59 +248,114 @@ impl WalletMetricsCalculator {
 
         for trade in sorted_trades {
             match trade.side {
-                TradeSide::Buy => {
-                    // Open new position
-                    open_positions
-                        .entry(trade.token_mint)
-                        .or_insert_with(Vec::new)
-                        .push(trade);
-                }
-                TradeSide::Sell => {
-                    // Close position
-                    if let Some(buys) = open_positions.get_mut(&trade.token_mint) {
-                        if let Some(buy_trade) = buys.pop() {
-                            let hold_time = trade
-                                .timestamp
-                                .signed_duration_since(buy_trade.timestamp)
-                                .num_seconds() as f64;
-
-                            let pnl = trade.amount_out - buy_trade.amount_in;
-                            let pnl_percentage = if buy_trade.amount_in > Decimal::ZERO {
-                                ((pnl / buy_trade.amount_in) * Decimal::from(100))
-                                    .to_string()
-                                    .parse::<f64>()
-                                    .unwrap_or(0.0)
-                            } else {
-                                0.0
-                            };
-
-                            positions.push(TradePosition {
-                                id: Uuid::new_v4(),
-                                wallet: trade.wallet,
-                                token_mint: trade.token_mint,
-                                entry_trade: buy_trade,
-                                exit_trade: Some(trade),
-                                pnl: Some(pnl),
-                                pnl_percentage: Some(pnl_percentage),
-                                hold_time_seconds: Some(hold_time),
-                                entry_market_cap: Decimal::ZERO, // Filled later with market data
-                                exit_market_cap: Some(Decimal::ZERO),
-                                status: PositionStatus::Closed,
-                            });
+                TradeSide::Buy => match mode {
+                    LotMatchingMode::AverageCost => {
+                        let pool = avg_pools.entry(trade.token_mint).or_insert_with(|| AverageCostPool {
+                            quantity: Decimal::ZERO,
+                            cost_basis: Decimal::ZERO,
+                            last_buy: trade.clone(),
+                        });
+                        pool.quantity = checked_add(pool.quantity, trade.amount_out)?;
+                        pool.cost_basis = checked_add(pool.cost_basis, trade.amount_in)?;
+                        pool.last_buy = trade;
+                    }
+                    LotMatchingMode::Fifo | LotMatchingMode::Lifo => {
+                        open_lots.entry(trade.token_mint).or_default().push_back(Lot {
+                            remaining_qty: trade.amount_out,
+                            trade,
+                        });
+                    }
+                },
+                TradeSide::Sell => match mode {
+                    LotMatchingMode::AverageCost => {
+                        if let Some(pool) = avg_pools.get_mut(&trade.token_mint) {
+                            if !pool.quantity.is_zero() {
+                                let avg_price = checked_div(pool.cost_basis, pool.quantity)?;
+                                let matched_qty = trade.amount_in.min(pool.quantity);
+                                let matched_cost = checked_mul(avg_price, matched_qty)?;
+                                let proceeds = if trade.amount_in.is_zero() {
+                                    Decimal::ZERO
+                                } else {
+                                    checked_mul(trade.amount_out, checked_div(matched_qty, trade.amount_in)?)?
+                                };
+
+                                pool.quantity = checked_sub(pool.quantity, matched_qty)?;
+                                pool.cost_basis = checked_sub(pool.cost_basis, matched_cost)?;
+
+                                let entry_trade = with_amounts(&pool.last_buy, matched_cost, matched_qty);
+                                let exit_trade = with_amounts(&trade, matched_qty, proceeds);
+
+                                positions.push(Self::build_closed_position(
+                                    entry_trade,
+                                    exit_trade,
+                                    max_plausible_hold_seconds,
+                                )?);
+                            }
                         }
                     }
-                }
+                    LotMatchingMode::Fifo | LotMatchingMode::Lifo => {
+                        if let Some(lots) = open_lots.get_mut(&trade.token_mint) {
+                            let mut sell_remaining = trade.amount_in;
+
+                            while sell_remaining > Decimal::ZERO {
+                                let lot = match mode {
+                                    LotMatchingMode::Fifo => lots.front_mut(),
+                                    LotMatchingMode::Lifo => lots.back_mut(),
+                                    LotMatchingMode::AverageCost => unreachable!(),
+                                };
+                                let Some(lot) = lot else { break };
+
+                                let matched_qty = sell_remaining.min(lot.remaining_qty);
+                                let buy_cost = if lot.trade.amount_out.is_zero() {
+                                    Decimal::ZERO
+                                } else {
+                                    checked_mul(lot.trade.amount_in, checked_div(matched_qty, lot.trade.amount_out)?)?
+                                };
+                                let proceeds = if trade.amount_in.is_zero() {
+                                    Decimal::ZERO
+                                } else {
+                                    checked_mul(trade.amount_out, checked_div(matched_qty, trade.amount_in)?)?
+                                };
+
+                                let entry_trade = with_amounts(&lot.trade, buy_cost, matched_qty);
+                                let exit_trade = with_amounts(&trade, matched_qty, proceeds);
+
+                                lot.remaining_qty = checked_sub(lot.remaining_qty, matched_qty)?;
+                                sell_remaining = checked_sub(sell_remaining, matched_qty)?;
+                                let lot_exhausted = lot.remaining_qty.is_zero();
+
+                                positions.push(Self::build_closed_position(
+                                    entry_trade,
+                                    exit_trade,
+                                    max_plausible_hold_seconds,
+                                )?);
+
+                                if lot_exhausted {
+                                    match mode {
+                                        LotMatchingMode::Fifo => {
+                                            lots.pop_front();
+                                        }
+                                        LotMatchingMode::Lifo => {
+                                            lots.pop_back();
+                                        }
+                                        LotMatchingMode::AverageCost => unreachable!(),
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
             }
         }
 
-        // Add remaining open positions
-        for (token_mint, buys) in open_positions {
-            for buy_trade in buys {
+        // Remaining open positions: unconsumed buy lots (FIFO/LIFO)...
+        for (token_mint, lots) in open_lots {
+            for lot in lots {
                 positions.push(TradePosition {
                     id: Uuid::new_v4(),
-                    wallet: buy_trade.wallet,
+                    wallet: lot.trade.wallet,
                     token_mint,
-                    entry_trade: buy_trade,
+                    entry_trade: lot.trade,
                     exit_trade: None,
                     pnl: None,
                     pnl_percentage: None,
@@ -198,74 +363,241 @@ impl WalletMetricsCalculator {
                     entry_market_cap: Decimal::ZERO,
                     exit_market_cap: None,
                     status: PositionStatus::Open,
+                    timestamp_suspect: false,
                 });
             }
         }
 
-        positions
+        // ...and leftover average-cost pools.
+        for (token_mint, pool) in avg_pools {
+            if pool.quantity.is_zero() {
+                continue;
+            }
+
+            let entry_trade = with_amounts(&pool.last_buy, pool.cost_basis, pool.quantity);
+            positions.push(TradePosition {
+                id: Uuid::new_v4(),
+                wallet: entry_trade.wallet,
+                token_mint,
+                entry_trade,
+                exit_trade: None,
+                pnl: None,
+                pnl_percentage: None,
+                hold_time_seconds: None,
+                entry_market_cap: Decimal::ZERO,
+                exit_market_cap: None,
+                status: PositionStatus::Open,
+                timestamp_suspect: false,
+            });
+        }
+
+        Ok(positions)
+    }
+
+    /// Build a closed [`TradePosition`] from a matched entry/exit trade pair - shared by
+    /// every [`LotMatchingMode`], since all three reduce a match down to one `Trade`
+    /// (possibly synthesized via [`with_amounts`]) on each side.
+    fn build_closed_position(
+        entry_trade: Trade,
+        exit_trade: Trade,
+        max_plausible_hold_seconds: f64,
+    ) -> Result<TradePosition> {
+        let raw_hold_time = exit_trade.timestamp.signed_duration_since(entry_trade.timestamp).num_seconds() as f64;
+
+        // A sell timestamped before its matched buy is clamped to a zero hold time
+        // rather than trusted - it can't reflect a real position, only feed corruption
+        // or clock skew.
+        let timestamp_suspect = raw_hold_time < 0.0 || raw_hold_time > max_plausible_hold_seconds;
+        let hold_time = raw_hold_time.max(0.0);
+
+        let pnl = checked_sub(exit_trade.amount_out, entry_trade.amount_in)?;
+        let pnl_percentage = if entry_trade.amount_in > Decimal::ZERO {
+            to_f64(checked_mul(checked_div(pnl, entry_trade.amount_in)?, Decimal::from(100))?)?
+        } else {
+            0.0
+        };
+
+        Ok(TradePosition {
+            id: Uuid::new_v4(),
+            wallet: exit_trade.wallet,
+            token_mint: exit_trade.token_mint,
+            entry_trade,
+            exit_trade: Some(exit_trade),
+            pnl: Some(pnl),
+            pnl_percentage: Some(pnl_percentage),
+            hold_time_seconds: Some(hold_time),
+            entry_market_cap: Decimal::ZERO, // Filled later with market data
+            exit_market_cap: Some(Decimal::ZERO),
+            status: PositionStatus::Closed,
+            timestamp_suspect,
+        })
     }
 
     /// Calculate Sharpe ratio
-    fn calculate_sharpe_ratio(pnl_values: &[Decimal]) -> f64 {
+    fn calculate_sharpe_ratio(pnl_values: &[Decimal]) -> Result<f64> {
         if pnl_values.is_empty() {
-            return 0.0;
+            return Ok(0.0);
         }
 
-        let mean: f64 = pnl_values
-            .iter()
-            .map(|&v| v.to_string().parse::<f64>().unwrap_or(0.0))
-            .sum::<f64>()
-            / pnl_values.len() as f64;
-
-        let variance: f64 = pnl_values
-            .iter()
-            .map(|&v| {
-                let val = v.to_string().parse::<f64>().unwrap_or(0.0);
-                (val - mean).powi(2)
-            })
-            .sum::<f64>()
-            / pnl_values.len() as f64;
+        let values = pnl_values.iter().map(|&v| to_f64(v)).collect::<Result<Vec<f64>>>()?;
 
+        let mean: f64 = values.iter().sum::<f64>() / values.len() as f64;
+        let variance: f64 = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
         let std_dev = variance.sqrt();
 
-        if std_dev > 0.0 {
-            mean / std_dev
-        } else {
-            0.0
-        }
+        Ok(if std_dev > 0.0 { mean / std_dev } else { 0.0 })
     }
 
     /// Calculate maximum drawdown
-    fn calculate_max_drawdown(pnl_values: &[Decimal]) -> f64 {
-        if pnl_values.is_empty() {
-            return 0.0;
-        }
+    fn calculate_equity_curve(positions: &[TradePosition]) -> Result<EquityCurveMetrics> {
+        let mut closed: Vec<&TradePosition> = positions
+            .iter()
+            .filter(|p| p.status == PositionStatus::Closed && p.exit_trade.is_some())
+            .collect();
+        closed.sort_by_key(|p| p.exit_trade.as_ref().map(|t| t.timestamp));
 
         let mut cumulative_pnl = Decimal::ZERO;
         let mut peak = Decimal::ZERO;
-        let mut max_dd = 0.0;
+        let mut peak_time: Option<DateTime<Utc>> = None;
+
+        let mut max_drawdown = 0.0;
+        let mut max_drawdown_start = None;
+        let mut max_drawdown_end = None;
+
+        let mut underwater_seconds = 0.0;
+        let mut underwater_since: Option<DateTime<Utc>> = None;
+
+        for position in &closed {
+            let Some(pnl) = position.pnl else { continue };
+            // Already filtered to `exit_trade.is_some()` above.
+            let exit_time = position.exit_trade.as_ref().unwrap().timestamp;
+
+            cumulative_pnl = checked_add(cumulative_pnl, pnl)?;
 
-        for &pnl in pnl_values {
-            cumulative_pnl += pnl;
-            if cumulative_pnl > peak {
+            if cumulative_pnl >= peak {
+                if let Some(since) = underwater_since.take() {
+                    underwater_seconds += exit_time.signed_duration_since(since).num_seconds() as f64;
+                }
                 peak = cumulative_pnl;
+                peak_time = Some(exit_time);
+                continue;
+            }
+
+            if underwater_since.is_none() {
+                underwater_since = peak_time;
             }
 
             let drawdown = if peak > Decimal::ZERO {
-                ((peak - cumulative_pnl) / peak * Decimal::from(100))
-                    .to_string()
-                    .parse::<f64>()
-                    .unwrap_or(0.0)
+                to_f64((peak - cumulative_pnl) / peak * Decimal::from(100))?
             } else {
                 0.0
             };
 
-            if drawdown > max_dd {
-                max_dd = drawdown;
+            if drawdown > max_drawdown {
+                max_drawdown = drawdown;
+                max_drawdown_start = peak_time;
+                max_drawdown_end = Some(exit_time);
             }
         }
 
-        max_dd
+        if let (Some(since), Some(last)) = (underwater_since, closed.last()) {
+            let last_exit_time = last.exit_trade.as_ref().unwrap().timestamp;
+            underwater_seconds += last_exit_time.signed_duration_since(since).num_seconds() as f64;
+        }
+
+        Ok(EquityCurveMetrics {
+            max_drawdown,
+            max_drawdown_start,
+            max_drawdown_end,
+            underwater_seconds,
+        })
+    }
+
+    /// Comprehensive return-series risk/reward metrics (see [`ReturnSeriesMetrics`]),
+    /// computed from each closed position's `pnl_percentage` rather than raw dollar PnL, so
+    /// it's comparable across wallets regardless of position size. Needs at least two closed
+    /// positions with a recorded `pnl_percentage` to produce anything but `None`s.
+    fn calculate_return_series_metrics(
+        positions: &[TradePosition],
+        avg_hold_time_seconds: f64,
+        max_drawdown: f64,
+    ) -> Result<ReturnSeriesMetrics> {
+        let closed: Vec<&TradePosition> = positions
+            .iter()
+            .filter(|p| p.status == PositionStatus::Closed)
+            .collect();
+
+        let returns: Vec<f64> = closed.iter().filter_map(|p| p.pnl_percentage).map(|pct| pct / 100.0).collect();
+
+        if returns.len() < 2 {
+            return Ok(ReturnSeriesMetrics::default());
+        }
+
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / returns.len() as f64;
+        let stddev = variance.sqrt();
+
+        let trades_per_year = if avg_hold_time_seconds > 0.0 {
+            Some(SECONDS_PER_YEAR / avg_hold_time_seconds)
+        } else {
+            None
+        };
+
+        let annualized_sharpe = match (stddev > 0.0, trades_per_year) {
+            (true, Some(trades_per_year)) => Some(mean / stddev * trades_per_year.sqrt()),
+            _ => None,
+        };
+
+        // Target return of 0: only negative deviations count toward the sum, but it's
+        // divided by the full sample count, not just the negative ones.
+        const SORTINO_TARGET: f64 = 0.0;
+        let downside_variance =
+            returns.iter().map(|r| (r - SORTINO_TARGET).min(0.0).powi(2)).sum::<f64>() / returns.len() as f64;
+        let downside_deviation = downside_variance.sqrt();
+
+        let sortino = if downside_deviation > 0.0 { Some(mean / downside_deviation) } else { None };
+
+        let max_drawdown_fraction = max_drawdown / 100.0;
+        let calmar = match (trades_per_year, max_drawdown_fraction > 0.0) {
+            (Some(trades_per_year), true) => Some((mean * trades_per_year) / max_drawdown_fraction),
+            _ => None,
+        };
+
+        let mut winning_pnl = Decimal::ZERO;
+        let mut losing_pnl = Decimal::ZERO;
+        let mut total_pnl = Decimal::ZERO;
+        let mut win_count = 0;
+        let mut loss_count = 0;
+
+        for pnl in closed.iter().filter_map(|p| p.pnl) {
+            total_pnl = checked_add(total_pnl, pnl)?;
+
+            if pnl > Decimal::ZERO {
+                winning_pnl = checked_add(winning_pnl, pnl)?;
+                win_count += 1;
+            } else if pnl < Decimal::ZERO {
+                losing_pnl = checked_add(losing_pnl, pnl)?;
+                loss_count += 1;
+            }
+        }
+
+        let profit_factor = if !losing_pnl.is_zero() { Some(to_f64(winning_pnl / losing_pnl.abs())?) } else { None };
+
+        let win_rate = win_count as f64 / closed.len() as f64;
+        let avg_win = to_f64(winning_pnl)? / win_count.max(1) as f64;
+        let avg_loss = to_f64(losing_pnl)? / loss_count.max(1) as f64;
+        let expectancy = Some(win_rate * avg_win + (1.0 - win_rate) * avg_loss);
+
+        let recovery_factor = if max_drawdown > 0.0 { Some(to_f64(total_pnl)? / max_drawdown) } else { None };
+
+        Ok(ReturnSeriesMetrics {
+            annualized_sharpe,
+            sortino,
+            calmar,
+            profit_factor,
+            expectancy,
+            recovery_factor,
+        })
     }
 
     /// Calculate stats for a time window
@@ -323,13 +655,15 @@ impl WalletMetricsCalculator {
         (entry_range.0, entry_range.1, exit_range.0, exit_range.1)
     }
 
-    /// Build full wallet analysis
+    /// Build full wallet analysis. `mode` controls which buy lot(s) each sell is matched
+    /// against - see [`LotMatchingMode`].
     pub fn build_wallet_analysis(
         wallet: &Pubkey,
         trades: &[Trade],
+        mode: LotMatchingMode,
     ) -> Result<WalletAnalysis> {
-        let metrics = Self::calculate_metrics(trades)?;
-        let positions = Self::group_trades_into_positions(trades);
+        let metrics = Self::calculate_metrics(trades, mode)?;
+        let positions = Self::group_trades_into_positions(trades, DEFAULT_MAX_PLAUSIBLE_HOLD_SECONDS, mode)?;
 
         // Calculate smart money score (simplified)
         let smart_money_score = Self::calculate_simple_smart_score(&metrics);
@@ -458,7 +792,7 @@ mod tests {
 
     #[test]
     fn test_calculate_metrics_empty() {
-        let metrics = WalletMetricsCalculator::calculate_metrics(&[]);
+        let metrics = WalletMetricsCalculator::calculate_metrics(&[], LotMatchingMode::default());
         assert!(metrics.is_ok());
         let m = metrics.unwrap();
         assert_eq!(m.total_trades, 0);
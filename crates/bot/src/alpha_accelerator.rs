@@ -1,13 +1,21 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use solana_sdk::pubkey::Pubkey;
 use rust_decimal::Decimal;
 use chrono::{DateTime, Utc};
 use trading_core::*;
 use trading_analysis::*;
 
+/// Smooth 0..1 squash, used to turn an unbounded `conviction` score into a `confidence`
+/// that saturates toward 1.0 instead of clamping at a fixed bonus.
+fn logistic(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
 pub struct AlphaAccelerator {
     convergence_threshold: u32,  // How many wallets = strong signal
     time_window_minutes: i64,     // How recent
+    breakout_factor: f64,         // How far above its own p90 baseline a bucket must sit to count as a breakout
+    recency_lambda: f64,          // Decay rate for a buy's recency weight, exp(-lambda * minutes_since_trade)
 }
 
 impl AlphaAccelerator {
@@ -15,18 +23,37 @@ impl AlphaAccelerator {
         Self {
             convergence_threshold,
             time_window_minutes,
+            breakout_factor: 3.0,
+            // Halves roughly every 15 minutes: exp(-lambda * 15) = 0.5.
+            recency_lambda: std::f64::consts::LN_2 / 15.0,
         }
     }
 
+    pub fn with_breakout_factor(mut self, breakout_factor: f64) -> Self {
+        self.breakout_factor = breakout_factor;
+        self
+    }
+
+    pub fn with_recency_lambda(mut self, recency_lambda: f64) -> Self {
+        self.recency_lambda = recency_lambda;
+        self
+    }
+
+    /// Smart-money convergence, weighted by how recent each buy was, how committed the
+    /// buying wallet's track record is, and how large the buy was relative to the token's
+    /// own recent trade sizes - so a token bought 30 seconds ago by a few highly-scored
+    /// wallets outranks one bought just inside the window by many wallets making token-sized
+    /// trades.
     pub async fn find_ultra_high_confidence_signals(
         &self,
         wallets: &HashMap<Pubkey, WalletAnalysis>,
         recent_trades: &HashMap<Pubkey, Vec<Trade>>,
     ) -> Vec<UltraSignal> {
-        let mut token_activity: HashMap<Pubkey, TokenActivity> = HashMap::new();
-
-        // Scan last hour of activity
         let cutoff = Utc::now() - chrono::Duration::minutes(self.time_window_minutes);
+        let now = Utc::now();
+
+        // Qualifying elite buys per token: (wallet, trade, wallet's smart_money_score).
+        let mut buys_by_token: HashMap<Pubkey, Vec<(Pubkey, &Trade, f64)>> = HashMap::new();
 
         for (wallet, trades) in recent_trades {
             // Only elite wallets (80%+ win rate)
@@ -36,44 +63,62 @@ impl AlphaAccelerator {
                 }
 
                 for trade in trades {
-                    if trade.timestamp < cutoff {
+                    if trade.timestamp < cutoff || trade.side != TradeSide::Buy {
                         continue;
                     }
 
-                    if trade.side != TradeSide::Buy {
-                        continue;
-                    }
-
-                    let activity = token_activity
+                    buys_by_token
                         .entry(trade.token_mint)
-                        .or_insert_with(|| TokenActivity::new(trade.token_mint));
-
-                    activity.smart_wallets_bought.push(*wallet);
-                    activity.total_volume += trade.amount_in;
-                    activity.avg_smart_score += analysis.smart_money_score;
+                        .or_default()
+                        .push((*wallet, trade, analysis.smart_money_score));
                 }
             }
         }
 
-        // Find convergence signals
         let mut ultra_signals = vec![];
 
-        for (token, activity) in token_activity {
-            let wallet_count = activity.smart_wallets_bought.len() as u32;
-
-            if wallet_count >= self.convergence_threshold {
-                let avg_score = activity.avg_smart_score / wallet_count as f64;
-
-                ultra_signals.push(UltraSignal {
-                    token_mint: token,
-                    confidence: 0.8 + (wallet_count as f64 * 0.05).min(0.2),
-                    smart_wallets_count: wallet_count,
-                    avg_smart_score: avg_score,
-                    total_volume: activity.total_volume,
-                    signal_type: SignalType::SmartMoneyConvergence,
-                    detected_at: Utc::now(),
-                });
+        for (token, buys) in &buys_by_token {
+            let distinct_wallets: HashSet<Pubkey> = buys.iter().map(|(wallet, _, _)| *wallet).collect();
+
+            if (distinct_wallets.len() as u32) < self.convergence_threshold {
+                continue;
+            }
+
+            let mut sizes: Vec<Decimal> = buys.iter().map(|(_, trade, _)| trade.amount_in).collect();
+            sizes.sort();
+            let median_size = sizes[sizes.len() / 2];
+
+            let mut conviction = 0.0;
+            let mut total_volume = Decimal::ZERO;
+            let mut score_sum = 0.0;
+
+            for (_, trade, score) in buys {
+                let minutes_since = (now - trade.timestamp).num_seconds() as f64 / 60.0;
+                let recency_weight = (-self.recency_lambda * minutes_since.max(0.0)).exp();
+
+                let size_factor = if median_size.is_zero() {
+                    1.0
+                } else {
+                    (trade.amount_in / median_size).to_string().parse::<f64>().unwrap_or(1.0)
+                };
+
+                conviction += recency_weight * score * size_factor;
+                total_volume += trade.amount_in;
+                score_sum += score;
             }
+
+            let confidence = (0.5 + 0.5 * logistic(conviction - self.convergence_threshold as f64)).min(0.99);
+
+            ultra_signals.push(UltraSignal {
+                token_mint: *token,
+                confidence,
+                conviction,
+                smart_wallets_count: distinct_wallets.len() as u32,
+                avg_smart_score: score_sum / buys.len() as f64,
+                total_volume,
+                signal_type: SignalType::SmartMoneyConvergence,
+                detected_at: now,
+            });
         }
 
         // Sort by confidence
@@ -84,6 +129,108 @@ impl AlphaAccelerator {
         ultra_signals
     }
 
+    /// Flag tokens whose most recent minute of buy volume (summed over every wallet in
+    /// `recent_trades`) is anomalously high relative to their own trailing baseline, rather than a threshold shared
+    /// across every token. Requires at least `MIN_BUCKETS` minute-buckets of history and
+    /// at least one elite wallet (`smart_money_score >= 0.8`) participating in the
+    /// breakout bucket itself, so a single large trade from an untracked wallet can't
+    /// trigger it alone.
+    pub fn find_volume_breakouts(
+        &self,
+        wallets: &HashMap<Pubkey, WalletAnalysis>,
+        recent_trades: &HashMap<Pubkey, Vec<Trade>>,
+    ) -> Vec<UltraSignal> {
+        const MIN_BUCKETS: usize = 10;
+
+        let cutoff = Utc::now() - chrono::Duration::minutes(self.time_window_minutes);
+
+        // One bucket per minute per token, plus the elite wallets (and their scores)
+        // that bought in each bucket.
+        let mut buckets_by_token: HashMap<Pubkey, HashMap<i64, Decimal>> = HashMap::new();
+        let mut elite_scores_by_bucket: HashMap<(Pubkey, i64), Vec<f64>> = HashMap::new();
+
+        for (wallet, trades) in recent_trades {
+            let elite_score = wallets
+                .get(wallet)
+                .map(|analysis| analysis.smart_money_score)
+                .filter(|score| *score >= 0.8);
+
+            for trade in trades {
+                if trade.timestamp < cutoff || trade.side != TradeSide::Buy {
+                    continue;
+                }
+
+                let bucket = trade.timestamp.timestamp() / 60;
+                *buckets_by_token
+                    .entry(trade.token_mint)
+                    .or_default()
+                    .entry(bucket)
+                    .or_insert(Decimal::ZERO) += trade.amount_in;
+
+                if let Some(score) = elite_score {
+                    elite_scores_by_bucket
+                        .entry((trade.token_mint, bucket))
+                        .or_default()
+                        .push(score);
+                }
+            }
+        }
+
+        let mut signals = vec![];
+
+        for (token, buckets) in &buckets_by_token {
+            if buckets.len() < MIN_BUCKETS {
+                continue;
+            }
+
+            let mut ordered: Vec<(i64, Decimal)> = buckets.iter().map(|(bucket, volume)| (*bucket, *volume)).collect();
+            ordered.sort_by_key(|(bucket, _)| *bucket);
+
+            let (latest_bucket, latest_volume) = *ordered.last().unwrap();
+
+            let mut sorted_volumes: Vec<Decimal> =
+                ordered[..ordered.len() - 1].iter().map(|(_, volume)| *volume).collect();
+            sorted_volumes.sort();
+            let p90_baseline = sorted_volumes[sorted_volumes.len() * 90 / 100];
+
+            let breakout_threshold = p90_baseline * Decimal::from_f64_retain(self.breakout_factor).unwrap_or(Decimal::from(3));
+
+            if latest_volume <= breakout_threshold {
+                continue;
+            }
+
+            let Some(elite_scores) = elite_scores_by_bucket.get(&(*token, latest_bucket)) else {
+                continue;
+            };
+
+            let avg_score = elite_scores.iter().sum::<f64>() / elite_scores.len() as f64;
+
+            let overshoot = if breakout_threshold.is_zero() {
+                1.0
+            } else {
+                ((latest_volume - breakout_threshold) / breakout_threshold)
+                    .to_string()
+                    .parse::<f64>()
+                    .unwrap_or(1.0)
+            };
+
+            signals.push(UltraSignal {
+                token_mint: *token,
+                confidence: (0.75 + overshoot * 0.1).min(0.99),
+                conviction: avg_score * elite_scores.len() as f64,
+                smart_wallets_count: elite_scores.len() as u32,
+                avg_smart_score: avg_score,
+                total_volume: latest_volume,
+                signal_type: SignalType::VolumeBreakout,
+                detected_at: Utc::now(),
+            });
+        }
+
+        signals.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap());
+
+        signals
+    }
+
     /// Detect wallets that are on a winning streak (HOT)
     pub fn find_hot_wallets(
         &self,
@@ -105,27 +252,14 @@ impl AlphaAccelerator {
     }
 }
 
-#[derive(Debug, Clone)]
-struct TokenActivity {
-    smart_wallets_bought: Vec<Pubkey>,
-    total_volume: Decimal,
-    avg_smart_score: f64,
-}
-
-impl TokenActivity {
-    fn new(_token_mint: Pubkey) -> Self {
-        Self {
-            smart_wallets_bought: vec![],
-            total_volume: Decimal::ZERO,
-            avg_smart_score: 0.0,
-        }
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct UltraSignal {
     pub token_mint: Pubkey,
     pub confidence: f64,
+    /// Raw recency/size/score-weighted conviction behind `confidence`, before the logistic
+    /// squash - lets downstream sizing tell "many small buys" apart from "a few large elite
+    /// buys" even when they land on a similar `confidence`.
+    pub conviction: f64,
     pub smart_wallets_count: u32,
     pub avg_smart_score: f64,
     pub total_volume: Decimal,
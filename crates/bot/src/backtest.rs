@@ -0,0 +1,257 @@
+// Replays a token's recorded wallet trades and candles chronologically through the
+// exact exit logic live trading uses (`PositionManager::should_exit_position_price_only`),
+// so the hardcoded thresholds it used to hide (24h time exit, 30%/15% trailing stop) can
+// be validated - and swept - against history instead of only discovered live. Unlike
+// `backtester.rs` (which replays already-closed `ClosedTrade`s from `trade_history.json`),
+// this opens its own simulated positions off recorded buy-side wallet activity in the
+// `trades` table and walks the `candles` table tick-by-tick to decide when to close them.
+use anyhow::Result;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use trading_analysis::Resolution;
+use trading_core::{StrategyMode, TradeSide};
+use trading_db::Database;
+
+use crate::money::Money;
+use crate::portfolio_monitor::{ClosedTrade, ExitTrigger, OpenPosition};
+use crate::position_manager::{ExitParams, PositionManager};
+
+/// Exit parameters to sweep, plus the position sizing used for every simulated trade.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BacktestConfig {
+    pub starting_capital: Decimal,
+    pub position_size_usd: Decimal,
+    pub stop_loss_pct: f64,
+    pub take_profit_pct: f64,
+    pub exit_params: ExitParamsConfig,
+}
+
+/// Serializable mirror of `ExitParams` (which isn't `Serialize`), so a sweep's
+/// parameters round-trip through `BacktestReport`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ExitParamsConfig {
+    pub max_hold_hours: i64,
+    pub time_exit_pnl_pct: f64,
+    pub trailing_activation_pct: f64,
+    pub trailing_giveback_pct: f64,
+    pub hard_max_hold_hours: i64,
+    pub rollover_hour_utc: Option<u32>,
+    pub warning_threshold_hours: i64,
+    pub scalping_max_hold_minutes: i64,
+}
+
+impl From<ExitParamsConfig> for ExitParams {
+    fn from(config: ExitParamsConfig) -> Self {
+        Self {
+            max_hold_hours: config.max_hold_hours,
+            time_exit_pnl_pct: config.time_exit_pnl_pct,
+            trailing_activation_pct: config.trailing_activation_pct,
+            trailing_giveback_pct: config.trailing_giveback_pct,
+            hard_max_hold_hours: config.hard_max_hold_hours,
+            rollover_hour_utc: config.rollover_hour_utc,
+            warning_threshold_hours: config.warning_threshold_hours,
+            scalping_max_hold_minutes: config.scalping_max_hold_minutes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub config: BacktestConfig,
+    pub total_trades: usize,
+    pub win_rate_pct: f64,
+    pub total_pnl: Money,
+    pub max_drawdown_pct: f64,
+    pub sharpe_ratio: f64,
+    pub trades: Vec<ClosedTrade>,
+}
+
+pub struct Backtest {
+    config: BacktestConfig,
+}
+
+impl Backtest {
+    pub fn new(config: BacktestConfig) -> Self {
+        Self { config }
+    }
+
+    /// Replay `token_mint`'s recorded buy-side trades (as entry signals) and
+    /// `resolution`-bucketed candles (as the tick-by-tick price path) between
+    /// `from`/`to` (inclusive `block_time`/`bucket_start` Unix seconds).
+    pub async fn run(
+        &self,
+        db: &Database,
+        token_mint: &Pubkey,
+        resolution: Resolution,
+        from: i64,
+        to: i64,
+    ) -> Result<BacktestReport> {
+        let signals = db.trades().get_token_trades_since(token_mint, from).await?;
+        let candles = db.candles().get_candles(token_mint, resolution, from, to).await?;
+
+        let position_manager = PositionManager::new();
+        let exit_params: ExitParams = self.config.exit_params.into();
+
+        let mut open_position: Option<OpenPosition> = None;
+        let mut closed_trades = Vec::new();
+        let mut capital = Money::new(self.config.starting_capital);
+        let mut peak_capital = capital;
+        let mut max_drawdown_pct = 0.0f64;
+
+        let mut signal_idx = 0;
+
+        for candle in &candles {
+            if candle.bucket_start > to {
+                break;
+            }
+
+            // Open on the next unconsumed buy signal, if nothing's open yet.
+            while open_position.is_none() && signal_idx < signals.len() {
+                let signal = &signals[signal_idx];
+                if signal.block_time > candle.bucket_start {
+                    break;
+                }
+                signal_idx += 1;
+
+                if signal.side != TradeSide::Buy {
+                    continue;
+                }
+
+                let entry_price = Money::new(signal.price_usd);
+                open_position = Some(OpenPosition {
+                    token_mint: *token_mint,
+                    token_symbol: token_mint.to_string(),
+                    entry_time: signal.timestamp,
+                    entry_price,
+                    entry_mc: Money::new(signal.market_cap_at_trade),
+                    amount: Money::new(self.config.position_size_usd),
+                    current_price: entry_price,
+                    current_mc: Money::new(signal.market_cap_at_trade),
+                    unrealized_pnl: Money::ZERO,
+                    unrealized_pnl_pct: 0.0,
+                    stop_loss: entry_price
+                        .checked_mul(Decimal::from_f64_retain(1.0 - self.config.stop_loss_pct / 100.0).unwrap_or(Decimal::ZERO))
+                        .unwrap_or(Money::ZERO),
+                    take_profit: entry_price
+                        .checked_mul(Decimal::from_f64_retain(1.0 + self.config.take_profit_pct / 100.0).unwrap_or(Decimal::ONE))
+                        .unwrap_or(entry_price),
+                    peak_price: entry_price,
+                    hold_time_minutes: 0,
+                    // No live strategy mode to read here, same default `BotConfig::strategy.mode` uses.
+                    expires_at: signal.timestamp + exit_params.expiry_window(StrategyMode::SwingTrading),
+                });
+            }
+
+            let Some(mut position) = open_position.take() else {
+                continue;
+            };
+
+            let current_price = Money::new(candle.close);
+            position.current_price = current_price;
+            position.unrealized_pnl_pct = Money::pct_change(position.entry_price, current_price);
+            if current_price > position.peak_price {
+                position.peak_price = current_price;
+            }
+            position.hold_time_minutes = (chrono::DateTime::<Utc>::from_timestamp(candle.bucket_start, 0)
+                .unwrap_or(position.entry_time)
+                - position.entry_time)
+                .num_minutes();
+
+            let trigger = position_manager.should_exit_position_price_only(&position, current_price, &exit_params);
+
+            match trigger {
+                Some(trigger) => {
+                    let trade = Self::close(position, current_price, trigger);
+                    capital += trade.pnl;
+                    if capital > peak_capital {
+                        peak_capital = capital;
+                    } else if peak_capital > Money::ZERO {
+                        let drawdown_pct = -Money::pct_change(peak_capital, capital);
+                        if drawdown_pct > max_drawdown_pct {
+                            max_drawdown_pct = drawdown_pct;
+                        }
+                    }
+                    closed_trades.push(trade);
+                }
+                None => open_position = Some(position),
+            }
+        }
+
+        // Force-close anything still open at the end of the replay window, at the last candle's close.
+        if let Some(position) = open_position.take() {
+            if let Some(last_candle) = candles.last() {
+                let trade = Self::close(position, Money::new(last_candle.close), ExitTrigger::Manual);
+                capital += trade.pnl;
+                closed_trades.push(trade);
+            }
+        }
+
+        let total_trades = closed_trades.len();
+        let wins = closed_trades.iter().filter(|t| t.is_win).count();
+        let win_rate_pct = if total_trades > 0 {
+            (wins as f64 / total_trades as f64) * 100.0
+        } else {
+            0.0
+        };
+        let total_pnl = capital - Money::new(self.config.starting_capital);
+        let sharpe_ratio = Self::sharpe_ratio(&closed_trades);
+
+        Ok(BacktestReport {
+            config: self.config,
+            total_trades,
+            win_rate_pct,
+            total_pnl,
+            max_drawdown_pct,
+            sharpe_ratio,
+            trades: closed_trades,
+        })
+    }
+
+    /// Close a simulated position the same way `PortfolioMonitor::close_position` does,
+    /// without needing a live broadcast channel to publish events on.
+    fn close(position: OpenPosition, exit_price: Money, trigger: ExitTrigger) -> ClosedTrade {
+        let pnl = Money::new(
+            (exit_price.as_decimal() - position.entry_price.as_decimal()) * position.amount.as_decimal()
+                / position.entry_price.as_decimal(),
+        );
+        let pnl_pct = Money::pct_change(position.entry_price, exit_price);
+        let is_win = pnl > Money::ZERO;
+
+        ClosedTrade {
+            token_mint: position.token_mint,
+            token_symbol: position.token_symbol,
+            entry_time: position.entry_time,
+            exit_time: Utc::now(),
+            entry_price: position.entry_price,
+            exit_price,
+            pnl,
+            pnl_pct,
+            hold_time_minutes: position.hold_time_minutes,
+            is_win,
+            trigger,
+        }
+    }
+
+    /// Unannualized Sharpe ratio across the run's per-trade returns (same shape as
+    /// `backtester::Backtester::calculate_sharpe_ratio`, without the trades/year
+    /// annualization - a sweep cares about relative ranking between configs, not an
+    /// absolute annualized figure).
+    fn sharpe_ratio(trades: &[ClosedTrade]) -> f64 {
+        if trades.len() < 2 {
+            return 0.0;
+        }
+
+        let returns: Vec<f64> = trades.iter().map(|t| t.pnl_pct).collect();
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+        let std_dev = variance.sqrt();
+
+        if std_dev == 0.0 {
+            0.0
+        } else {
+            mean / std_dev
+        }
+    }
+}
@@ -5,7 +5,22 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tracing::{info, warn};
 
+use trading_analysis::Candle;
+use trading_core::TradeSide;
+
 use crate::persistence::{SerializableClosedTrade, TradeHistory};
+use crate::trade_log_binary::{record_entry_time, record_exit_time, BinaryTradeLog, TradeRecord};
+
+/// Why a simulated position was closed, once the backtester can walk candles rather
+/// than only knowing the trade's recorded entry/exit price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExitReason {
+    TakeProfit,
+    StopLoss,
+    /// No candle data to replay against (or the configured targets were never crossed);
+    /// exits at the recorded/final price, same as the pre-candle behavior.
+    End,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BacktestConfig {
@@ -14,6 +29,17 @@ pub struct BacktestConfig {
     pub max_positions: usize,
     pub stop_loss_pct: f64,
     pub take_profit_pct: f64,
+    /// Apply the constant-product slippage model in `simulate_trade` instead of
+    /// assuming fills happen exactly at the recorded entry/exit price.
+    pub model_slippage: bool,
+    /// Per-side fee in basis points, subtracted from the fill on both entry and exit.
+    pub fee_bps: u16,
+    /// Historical trades don't persist the pool's `liquidity_usd` at fill time, so the
+    /// slippage model assumes this fixed pool depth rather than ignoring depth entirely.
+    pub assumed_liquidity_usd: Decimal,
+    /// Minimum acceptable return (in percent) used as the Sortino ratio's threshold:
+    /// returns above this don't count against downside deviation.
+    pub min_acceptable_return_pct: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,7 +59,18 @@ pub struct BacktestResults {
     pub max_drawdown_pct: f64,
     pub profit_factor: f64,
     pub sharpe_ratio: f64,
+    /// Like `sharpe_ratio`, but the denominator is downside deviation (only returns
+    /// below `min_acceptable_return_pct` count), so upside volatility isn't penalized.
+    pub sortino_ratio: f64,
+    /// Trades/year estimated from the actual span between the first entry and last
+    /// exit, rather than the hardcoded 365 both ratios used to annualize by.
+    pub trades_per_year: f64,
     pub avg_hold_time_minutes: i64,
+    /// Total difference between idealized (no slippage/fees) and realistic PnL across
+    /// all trades, i.e. how much the slippage model and fees cost relative to the naive
+    /// entry/exit pricing. `fee_bps` applies whatever `BacktestConfig.model_slippage` says,
+    /// so this is zero only when `model_slippage` is false and `fee_bps` is 0.
+    pub total_slippage_and_fee_cost: Decimal,
     pub trades: Vec<BacktestTrade>,
 }
 
@@ -48,6 +85,50 @@ pub struct BacktestTrade {
     pub pnl_pct: f64,
     pub hold_time_minutes: i64,
     pub is_win: bool,
+    /// Gap between the idealized PnL (fill at recorded entry/exit price, no fees) and
+    /// this trade's actual `pnl`, attributable to `BacktestConfig.model_slippage`/`fee_bps`.
+    pub slippage_and_fee_cost: Decimal,
+    /// Which of `stop_loss_pct`/`take_profit_pct` actually triggered, when replayed
+    /// against candles via `run_with_candles` (otherwise always `End`).
+    pub exit_reason: ExitReason,
+}
+
+/// Constant-product (x*y=k) slippage model: treats the pool as two reserves each worth
+/// `liquidity_usd/2` and returns the effective price a `position_size` notional buy or
+/// sell would actually execute at against that depth, rather than the quoted price.
+/// Falls back to the quoted `price` untouched when there isn't enough depth to model.
+fn constant_product_fill_price(liquidity_usd: Decimal, price: Decimal, position_size: Decimal, side: TradeSide) -> Decimal {
+    if liquidity_usd <= Decimal::ZERO || price <= Decimal::ZERO || position_size <= Decimal::ZERO {
+        return price;
+    }
+
+    let quote_reserve = liquidity_usd / Decimal::from(2);
+    let base_reserve = quote_reserve / price;
+    let k = base_reserve * quote_reserve;
+
+    match side {
+        TradeSide::Buy => {
+            let new_quote_reserve = quote_reserve + position_size;
+            let new_base_reserve = k / new_quote_reserve;
+            let delta_base = base_reserve - new_base_reserve;
+            if delta_base <= Decimal::ZERO {
+                price
+            } else {
+                position_size / delta_base
+            }
+        }
+        TradeSide::Sell => {
+            let base_amount = position_size / price;
+            let new_base_reserve = base_reserve + base_amount;
+            let new_quote_reserve = k / new_base_reserve;
+            let delta_quote = quote_reserve - new_quote_reserve;
+            if delta_quote <= Decimal::ZERO || base_amount <= Decimal::ZERO {
+                price
+            } else {
+                delta_quote / base_amount
+            }
+        }
+    }
 }
 
 pub struct Backtester {
@@ -100,7 +181,289 @@ impl Backtester {
             backtest_trades.push(backtest_trade);
         }
 
-        // Calculate metrics
+        Ok(self.summarize(capital, max_drawdown, daily_returns, backtest_trades))
+    }
+
+    fn simulate_trade(
+        &self,
+        trade: &SerializableClosedTrade,
+        _current_capital: Decimal,
+    ) -> Result<BacktestTrade> {
+        let entry_price = Decimal::from_str_exact(&trade.entry_price)?;
+        let exit_price = Decimal::from_str_exact(&trade.exit_price)?;
+
+        // Use configured position size
+        let position_size = self.config.position_size;
+
+        let idealized_pnl = ((exit_price - entry_price) / entry_price) * position_size;
+
+        let (fill_entry_price, fill_exit_price) = self.apply_fill_model(entry_price, exit_price, position_size);
+        let pnl = ((fill_exit_price - fill_entry_price) / fill_entry_price) * position_size;
+        let pnl_pct = (pnl / position_size * Decimal::from(100))
+            .to_string()
+            .parse::<f64>()
+            .unwrap_or(trade.pnl_pct);
+
+        Ok(BacktestTrade {
+            token_symbol: trade.token_symbol.clone(),
+            entry_time: trade.entry_time,
+            exit_time: trade.exit_time,
+            entry_price: fill_entry_price,
+            exit_price: fill_exit_price,
+            pnl,
+            pnl_pct,
+            hold_time_minutes: trade.hold_time_minutes,
+            is_win: pnl > Decimal::ZERO,
+            slippage_and_fee_cost: idealized_pnl - pnl,
+            exit_reason: ExitReason::End,
+        })
+    }
+
+    /// Apply the constant-product slippage model (if `model_slippage` is set) and the
+    /// per-side fee to a trade's recorded entry/exit price, returning the effective
+    /// fill prices actually used in PnL.
+    fn apply_fill_model(&self, entry_price: Decimal, exit_price: Decimal, position_size: Decimal) -> (Decimal, Decimal) {
+        let (mut fill_entry, mut fill_exit) = (entry_price, exit_price);
+
+        if self.config.model_slippage {
+            fill_entry = constant_product_fill_price(
+                self.config.assumed_liquidity_usd,
+                entry_price,
+                position_size,
+                TradeSide::Buy,
+            );
+            fill_exit = constant_product_fill_price(
+                self.config.assumed_liquidity_usd,
+                exit_price,
+                position_size,
+                TradeSide::Sell,
+            );
+        }
+
+        let fee_rate = Decimal::from(self.config.fee_bps) / Decimal::from(10_000);
+        fill_entry += fill_entry * fee_rate;
+        fill_exit -= fill_exit * fee_rate;
+
+        (fill_entry, fill_exit)
+    }
+
+    /// Run a backtest directly against a memory-mapped binary trade log, without
+    /// materializing a `TradeHistory` in memory first. Produces the same
+    /// `BacktestResults` shape as `run`.
+    pub fn run_from_binary(&self, log: &BinaryTradeLog) -> Result<BacktestResults> {
+        let records = log.records();
+        info!("🔄 Starting backtest with {} trades from binary log", records.len());
+
+        if records.is_empty() {
+            return Err(anyhow::anyhow!("No historical trades to backtest"));
+        }
+
+        let mut capital = self.config.starting_capital;
+        let mut peak_capital = capital;
+        let mut max_drawdown = 0.0f64;
+
+        let mut backtest_trades = Vec::with_capacity(records.len());
+        let mut daily_returns = Vec::with_capacity(records.len());
+
+        for record in records {
+            let backtest_trade = self.simulate_trade_record(log, record)?;
+
+            capital += backtest_trade.pnl;
+
+            if capital > peak_capital {
+                peak_capital = capital;
+            } else {
+                let current_drawdown = ((peak_capital - capital) / peak_capital * Decimal::from(100))
+                    .to_string()
+                    .parse::<f64>()
+                    .unwrap_or(0.0);
+
+                if current_drawdown > max_drawdown {
+                    max_drawdown = current_drawdown;
+                }
+            }
+
+            daily_returns.push(backtest_trade.pnl_pct);
+            backtest_trades.push(backtest_trade);
+        }
+
+        Ok(self.summarize(capital, max_drawdown, daily_returns, backtest_trades))
+    }
+
+    fn simulate_trade_record(&self, log: &BinaryTradeLog, record: &TradeRecord) -> Result<BacktestTrade> {
+        let entry_price = Decimal::from_f64_retain(record.entry_price as f64).unwrap_or(Decimal::ZERO);
+        let exit_price = Decimal::from_f64_retain(record.exit_price as f64).unwrap_or(Decimal::ZERO);
+        let position_size = self.config.position_size;
+
+        if entry_price.is_zero() {
+            let entry_time = record_entry_time(record);
+            let exit_time = record_exit_time(record);
+            return Ok(BacktestTrade {
+                token_symbol: log.token_symbol(record.token_code).to_string(),
+                entry_time,
+                exit_time,
+                entry_price,
+                exit_price,
+                pnl: Decimal::ZERO,
+                pnl_pct: 0.0,
+                hold_time_minutes: (exit_time - entry_time).num_minutes(),
+                is_win: false,
+                slippage_and_fee_cost: Decimal::ZERO,
+                exit_reason: ExitReason::End,
+            });
+        }
+
+        let idealized_pnl = ((exit_price - entry_price) / entry_price) * position_size;
+        let (fill_entry_price, fill_exit_price) = self.apply_fill_model(entry_price, exit_price, position_size);
+        let pnl = ((fill_exit_price - fill_entry_price) / fill_entry_price) * position_size;
+        let pnl_pct = (pnl / position_size * Decimal::from(100))
+            .to_string()
+            .parse::<f64>()
+            .unwrap_or(0.0);
+
+        let entry_time = record_entry_time(record);
+        let exit_time = record_exit_time(record);
+        let hold_time_minutes = (exit_time - entry_time).num_minutes();
+
+        Ok(BacktestTrade {
+            token_symbol: log.token_symbol(record.token_code).to_string(),
+            entry_time,
+            exit_time,
+            entry_price: fill_entry_price,
+            exit_price: fill_exit_price,
+            pnl,
+            pnl_pct,
+            hold_time_minutes,
+            is_win: pnl > Decimal::ZERO,
+            slippage_and_fee_cost: idealized_pnl - pnl,
+            exit_reason: ExitReason::End,
+        })
+    }
+
+    /// Run a backtest replaying each trade path-dependently against OHLCV candles, so
+    /// `stop_loss_pct`/`take_profit_pct` actually govern the exit instead of the trade
+    /// always closing at its recorded exit price. `candles_by_mint` should hold each
+    /// token's candles sorted by `bucket_start`; a token with no entry falls back to the
+    /// plain entry/exit simulation used by `run`.
+    pub fn run_with_candles(
+        &self,
+        trade_history: &TradeHistory,
+        candles_by_mint: &HashMap<String, Vec<Candle>>,
+    ) -> Result<BacktestResults> {
+        info!(
+            "🔄 Starting candle-replay backtest with {} historical trades",
+            trade_history.closed_trades.len()
+        );
+
+        if trade_history.closed_trades.is_empty() {
+            return Err(anyhow::anyhow!("No historical trades to backtest"));
+        }
+
+        let mut capital = self.config.starting_capital;
+        let mut peak_capital = capital;
+        let mut max_drawdown = 0.0f64;
+
+        let mut backtest_trades = Vec::new();
+        let mut daily_returns = Vec::new();
+
+        for trade in &trade_history.closed_trades {
+            let candles = candles_by_mint.get(&trade.token_mint);
+            let backtest_trade = self.simulate_trade_with_candles(trade, candles)?;
+
+            capital += backtest_trade.pnl;
+
+            if capital > peak_capital {
+                peak_capital = capital;
+            } else {
+                let current_drawdown = ((peak_capital - capital) / peak_capital * Decimal::from(100))
+                    .to_string()
+                    .parse::<f64>()
+                    .unwrap_or(0.0);
+
+                if current_drawdown > max_drawdown {
+                    max_drawdown = current_drawdown;
+                }
+            }
+
+            daily_returns.push(backtest_trade.pnl_pct);
+            backtest_trades.push(backtest_trade);
+        }
+
+        Ok(self.summarize(capital, max_drawdown, daily_returns, backtest_trades))
+    }
+
+    fn simulate_trade_with_candles(
+        &self,
+        trade: &SerializableClosedTrade,
+        candles: Option<&Vec<Candle>>,
+    ) -> Result<BacktestTrade> {
+        let entry_price = Decimal::from_str_exact(&trade.entry_price)?;
+        let position_size = self.config.position_size;
+
+        let take_profit_target =
+            entry_price * (Decimal::ONE + Decimal::from_f64_retain(self.config.take_profit_pct / 100.0).unwrap_or_default());
+        let stop_loss_target =
+            entry_price * (Decimal::ONE - Decimal::from_f64_retain(self.config.stop_loss_pct / 100.0).unwrap_or_default());
+
+        let recorded_exit_price = Decimal::from_str_exact(&trade.exit_price)?;
+
+        let (exit_price, exit_reason) = match candles {
+            Some(candles) => {
+                let entry_ts = trade.entry_time.timestamp();
+                let exit_ts = trade.exit_time.timestamp();
+                let path = candles
+                    .iter()
+                    .filter(|c| c.bucket_start >= entry_ts && c.bucket_start <= exit_ts);
+
+                let mut triggered = None;
+                for candle in path {
+                    if candle.low <= stop_loss_target {
+                        triggered = Some((stop_loss_target, ExitReason::StopLoss));
+                        break;
+                    }
+                    if candle.high >= take_profit_target {
+                        triggered = Some((take_profit_target, ExitReason::TakeProfit));
+                        break;
+                    }
+                }
+
+                triggered.unwrap_or((recorded_exit_price, ExitReason::End))
+            }
+            None => (recorded_exit_price, ExitReason::End),
+        };
+
+        let idealized_pnl = ((exit_price - entry_price) / entry_price) * position_size;
+        let (fill_entry_price, fill_exit_price) = self.apply_fill_model(entry_price, exit_price, position_size);
+        let pnl = ((fill_exit_price - fill_entry_price) / fill_entry_price) * position_size;
+        let pnl_pct = (pnl / position_size * Decimal::from(100))
+            .to_string()
+            .parse::<f64>()
+            .unwrap_or(0.0);
+
+        Ok(BacktestTrade {
+            token_symbol: trade.token_symbol.clone(),
+            entry_time: trade.entry_time,
+            exit_time: trade.exit_time,
+            entry_price: fill_entry_price,
+            exit_price: fill_exit_price,
+            pnl,
+            pnl_pct,
+            hold_time_minutes: trade.hold_time_minutes,
+            is_win: pnl > Decimal::ZERO,
+            slippage_and_fee_cost: idealized_pnl - pnl,
+            exit_reason,
+        })
+    }
+
+    /// Shared tail of `run`/`run_from_binary`: turn a capital trajectory and the
+    /// per-trade results into the summary statistics in `BacktestResults`.
+    fn summarize(
+        &self,
+        capital: Decimal,
+        max_drawdown: f64,
+        daily_returns: Vec<f64>,
+        backtest_trades: Vec<BacktestTrade>,
+    ) -> BacktestResults {
         let total_pnl = capital - self.config.starting_capital;
         let roi_pct = if self.config.starting_capital > Decimal::ZERO {
             ((total_pnl / self.config.starting_capital) * Decimal::from(100))
@@ -135,7 +498,6 @@ impl Backtester {
         let biggest_win = winning_trades.iter().map(|t| t.pnl).max().unwrap_or(Decimal::ZERO);
         let biggest_loss = losing_trades.iter().map(|t| t.pnl).min().unwrap_or(Decimal::ZERO);
 
-        // Profit factor
         let gross_profit: Decimal = winning_trades.iter().map(|t| t.pnl).sum();
         let gross_loss: Decimal = losing_trades.iter().map(|t| t.pnl).sum();
         let profit_factor = if gross_loss < Decimal::ZERO {
@@ -147,17 +509,19 @@ impl Backtester {
             0.0
         };
 
-        // Sharpe ratio (simplified: assumes risk-free rate = 0)
-        let sharpe_ratio = self.calculate_sharpe_ratio(&daily_returns);
+        let trades_per_year = Self::estimate_trades_per_year(&backtest_trades);
+        let sharpe_ratio = self.calculate_sharpe_ratio(&daily_returns, trades_per_year);
+        let sortino_ratio = self.calculate_sortino_ratio(&daily_returns, trades_per_year);
 
-        // Average hold time
         let avg_hold_time_minutes = if !backtest_trades.is_empty() {
             backtest_trades.iter().map(|t| t.hold_time_minutes).sum::<i64>() / backtest_trades.len() as i64
         } else {
             0
         };
 
-        Ok(BacktestResults {
+        let total_slippage_and_fee_cost = backtest_trades.iter().map(|t| t.slippage_and_fee_cost).sum();
+
+        BacktestResults {
             starting_capital: self.config.starting_capital,
             ending_capital: capital,
             total_pnl,
@@ -173,40 +537,33 @@ impl Backtester {
             max_drawdown_pct: max_drawdown,
             profit_factor,
             sharpe_ratio,
+            sortino_ratio,
+            trades_per_year,
             avg_hold_time_minutes,
+            total_slippage_and_fee_cost,
             trades: backtest_trades,
-        })
+        }
     }
 
-    fn simulate_trade(
-        &self,
-        trade: &SerializableClosedTrade,
-        _current_capital: Decimal,
-    ) -> Result<BacktestTrade> {
-        let entry_price = Decimal::from_str_exact(&trade.entry_price)?;
-        let exit_price = Decimal::from_str_exact(&trade.exit_price)?;
-
-        // Use configured position size
-        let position_size = self.config.position_size;
+    /// Estimate how many trades/year this run's cadence implies, from the span between
+    /// the earliest entry and latest exit, rather than assuming a fixed 365.
+    fn estimate_trades_per_year(trades: &[BacktestTrade]) -> f64 {
+        if trades.len() < 2 {
+            return 365.0;
+        }
 
-        // Calculate PnL
-        let pnl = ((exit_price - entry_price) / entry_price) * position_size;
-        let pnl_pct = trade.pnl_pct;
+        let first_entry = trades.iter().map(|t| t.entry_time).min().unwrap();
+        let last_exit = trades.iter().map(|t| t.exit_time).max().unwrap();
+        let span_days = (last_exit - first_entry).num_seconds() as f64 / 86400.0;
 
-        Ok(BacktestTrade {
-            token_symbol: trade.token_symbol.clone(),
-            entry_time: trade.entry_time,
-            exit_time: trade.exit_time,
-            entry_price,
-            exit_price,
-            pnl,
-            pnl_pct,
-            hold_time_minutes: trade.hold_time_minutes,
-            is_win: pnl > Decimal::ZERO,
-        })
+        if span_days <= 0.0 {
+            365.0
+        } else {
+            (trades.len() as f64 / span_days) * 365.25
+        }
     }
 
-    fn calculate_sharpe_ratio(&self, returns: &[f64]) -> f64 {
+    fn calculate_sharpe_ratio(&self, returns: &[f64], trades_per_year: f64) -> f64 {
         if returns.is_empty() {
             return 0.0;
         }
@@ -229,12 +586,40 @@ impl Backtester {
             return 0.0;
         }
 
-        // Sharpe ratio (annualized, assuming ~365 trades per year)
-        let annualized_return = mean_return * 365.0;
-        let annualized_std_dev = std_dev * (365.0f64).sqrt();
+        let annualized_return = mean_return * trades_per_year;
+        let annualized_std_dev = std_dev * trades_per_year.sqrt();
 
         annualized_return / annualized_std_dev
     }
+
+    /// Sortino ratio: same annualization as Sharpe, but the denominator is downside
+    /// deviation - the RMS of only the returns below `min_acceptable_return_pct` - so
+    /// large wins aren't treated as "risk" the way standard deviation treats them.
+    fn calculate_sortino_ratio(&self, returns: &[f64], trades_per_year: f64) -> f64 {
+        if returns.is_empty() {
+            return 0.0;
+        }
+
+        let mean_return = returns.iter().sum::<f64>() / returns.len() as f64;
+        let mar = self.config.min_acceptable_return_pct;
+
+        let downside_variance = returns
+            .iter()
+            .map(|r| (r - mar).min(0.0).powi(2))
+            .sum::<f64>()
+            / returns.len() as f64;
+
+        let downside_deviation = downside_variance.sqrt();
+
+        if downside_deviation == 0.0 {
+            return 0.0;
+        }
+
+        let annualized_return = mean_return * trades_per_year;
+        let annualized_downside_deviation = downside_deviation * trades_per_year.sqrt();
+
+        annualized_return / annualized_downside_deviation
+    }
 }
 
 impl BacktestResults {
@@ -261,10 +646,13 @@ impl BacktestResults {
         println!("║   Biggest Win:      ${:.2}", self.biggest_win);
         println!("║   Biggest Loss:     ${:.2}", self.biggest_loss);
         println!("║   Profit Factor:    {:.2}", self.profit_factor);
+        println!("║   Slippage+Fees:    ${:.2}", self.total_slippage_and_fee_cost);
         println!("║");
         println!("║ 📈 RISK METRICS");
         println!("║   Max Drawdown:     {:.2}%", self.max_drawdown_pct);
         println!("║   Sharpe Ratio:     {:.2}", self.sharpe_ratio);
+        println!("║   Sortino Ratio:    {:.2}", self.sortino_ratio);
+        println!("║   Trades/Year:      {:.1}", self.trades_per_year);
         println!("║   Avg Hold Time:    {} min", self.avg_hold_time_minutes);
         println!("╠═══════════════════════════════════════════════════════════╣");
 
@@ -298,16 +686,21 @@ impl BacktestResults {
             score += 1;
         }
 
+        // Sortino ratio > 2.0 (sortino runs higher than sharpe since it ignores upside)
+        if self.sortino_ratio > 2.0 {
+            score += 1;
+        }
+
         // Max drawdown < 20%
         if self.max_drawdown_pct < 20.0 {
             score += 1;
         }
 
         match score {
-            5 => "⭐⭐⭐⭐⭐ EXCELLENT",
-            4 => "⭐⭐⭐⭐ GOOD",
-            3 => "⭐⭐⭐ AVERAGE",
-            2 => "⭐⭐ BELOW AVERAGE",
+            6 => "⭐⭐⭐⭐⭐ EXCELLENT",
+            5 => "⭐⭐⭐⭐ GOOD",
+            4 => "⭐⭐⭐ AVERAGE",
+            2..=3 => "⭐⭐ BELOW AVERAGE",
             _ => "⭐ NEEDS IMPROVEMENT",
         }
     }
@@ -46,12 +46,20 @@ fn main() -> Result<()> {
         .parse::<f64>()
         .unwrap_or(10.0);
 
+    let model_slippage = std::env::var("BACKTEST_MODEL_SLIPPAGE")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(true);
+
     let config = BacktestConfig {
         starting_capital: Decimal::from_f64_retain(starting_capital).unwrap(),
         position_size: Decimal::from_f64_retain(position_size).unwrap(),
         max_positions: 5,
         stop_loss_pct: 10.0,
         take_profit_pct: 100.0,
+        model_slippage,
+        fee_bps: 30,
+        assumed_liquidity_usd: Decimal::from(20_000),
+        min_acceptable_return_pct: 0.0,
     };
 
     println!("⚙️  BACKTEST CONFIGURATION");
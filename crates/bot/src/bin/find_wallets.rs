@@ -1,14 +1,61 @@
 // Wallet Finder Tool - Discover Elite Solana Traders
-// Usage: cargo run --bin find-wallets
+// Usage: cargo run --bin find-wallets -- [--output-format text|json|csv]
 
 use anyhow::Result;
+use serde::Serialize;
 use solana_sdk::pubkey::Pubkey;
+use std::io::{self, Write};
 use std::str::FromStr;
 use tracing::{info, warn};
 use trading_core::*;
 use trading_data::*;
 use trading_analysis::*;
 
+/// Machine-readable shape of an elite wallet result, for `--output-format json|csv` - the
+/// plain fields downstream tooling actually wants, rather than the full `WalletAnalysis`.
+#[derive(Debug, Serialize)]
+struct WalletRecord {
+    wallet: String,
+    smart_money_score: f64,
+    win_rate: f64,
+    total_trades: u64,
+    trades_last_24h: u64,
+    risk_score: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+impl OutputFormat {
+    fn from_args() -> Self {
+        let args: Vec<String> = std::env::args().collect();
+        for (idx, arg) in args.iter().enumerate() {
+            let value = if let Some(value) = arg.strip_prefix("--output-format=") {
+                Some(value)
+            } else if arg == "--output-format" {
+                args.get(idx + 1).map(|s| s.as_str())
+            } else {
+                None
+            };
+
+            match value {
+                Some("json") => return Self::Json,
+                Some("csv") => return Self::Csv,
+                Some("text") => return Self::Text,
+                Some(other) => {
+                    warn!("Unknown --output-format '{}', defaulting to text", other);
+                }
+                None => {}
+            }
+        }
+        Self::Text
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize logging
@@ -20,6 +67,8 @@ async fn main() -> Result<()> {
 
     dotenvy::dotenv().ok();
 
+    let output_format = OutputFormat::from_args();
+
     println!("\n{}", r#"
 ╔═══════════════════════════════════════════════════════════════╗
 ║                                                               ║
@@ -57,27 +106,23 @@ async fn main() -> Result<()> {
         }
     }
 
-    // Candidate wallets to analyze (these are known active DEX traders)
-    // In a real implementation, you'd fetch these from on-chain data or DexScreener API
-    let candidate_wallets = get_candidate_wallets();
+    // Candidate wallets are auto-discovered from chain state (top holders of a trending
+    // mint, plus recent DEX traders) instead of requiring a hand-pasted address list.
+    let candidate_wallets = get_candidate_wallets(&rpc_client).await?;
 
     println!("\n📊 Analyzing {} candidate wallets...\n", candidate_wallets.len());
     println!("{}", "═".repeat(80));
 
-    let mut elite_wallets = Vec::new();
+    // Every successfully-analyzed wallet is kept in memory (not just today's elite set) so
+    // the REPL below can re-filter against different thresholds without re-hitting the RPC.
+    let mut all_analyses: Vec<(Pubkey, WalletAnalysis)> = Vec::new();
 
-    for (idx, wallet_str) in candidate_wallets.iter().enumerate() {
-        let wallet = match Pubkey::from_str(wallet_str) {
-            Ok(w) => w,
-            Err(e) => {
-                warn!("Invalid wallet {}: {}", wallet_str, e);
-                continue;
-            }
-        };
+    for (idx, wallet) in candidate_wallets.iter().enumerate() {
+        let wallet = *wallet;
+        let wallet_str = wallet.to_string();
 
         print!("[{:2}/{}] Analyzing {}...", idx + 1, candidate_wallets.len(), &wallet_str[..12]);
 
-        // Analyze wallet
         match analyze_wallet(&rpc_client, &wallet).await {
             Ok((analysis, _)) => {
                 println!(" Score: {:.2} | WR: {:.1}% | Trades: {}",
@@ -85,14 +130,7 @@ async fn main() -> Result<()> {
                     analysis.metrics.win_rate,
                     analysis.metrics.total_trades,
                 );
-
-                // Elite criteria: 75%+ win rate, 0.8+ smart score, 20+ trades
-                if analysis.smart_money_score >= 0.75
-                    && analysis.metrics.win_rate >= 70.0
-                    && analysis.metrics.total_trades >= 20
-                {
-                    elite_wallets.push((wallet, analysis));
-                }
+                all_analyses.push((wallet, analysis));
             }
             Err(e) => {
                 println!(" ❌ Error: {}", e);
@@ -103,21 +141,185 @@ async fn main() -> Result<()> {
     }
 
     println!("{}", "═".repeat(80));
-    println!("\n🎯 Found {} ELITE wallets!\n", elite_wallets.len());
 
-    if elite_wallets.is_empty() {
+    let mut criteria = EliteCriteria::default();
+    let mut elite_wallets = criteria.filter(&all_analyses);
+    print_elite_wallets(&elite_wallets);
+
+    if all_analyses.is_empty() {
         println!("💡 Try again later or add more candidate wallets to the list.");
         return Ok(());
     }
 
-    // Sort by smart money score
-    elite_wallets.sort_by(|a, b| {
-        b.1.smart_money_score
-            .partial_cmp(&a.1.smart_money_score)
-            .unwrap()
-    });
+    // Drop into a REPL so thresholds can be refined against the already-fetched analyses,
+    // instead of restarting the whole RPC-heavy scan for every tweak.
+    println!("\n🧭 Interactive mode. Type `help` for commands.\n");
+
+    loop {
+        print!("find-wallets> ");
+        io::stdout().flush().ok();
+
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line).unwrap_or(0) == 0 {
+            break; // EOF (e.g. piped input)
+        }
+
+        let mut parts = line.trim().split_whitespace();
+        let Some(command) = parts.next() else { continue };
+        let args: Vec<&str> = parts.collect();
+
+        match command {
+            "help" => print_repl_help(),
+            "threshold" => {
+                set_threshold(&mut criteria, &args);
+                elite_wallets = criteria.filter(&all_analyses);
+                print_elite_wallets(&elite_wallets);
+            }
+            "rescore" => {
+                elite_wallets = criteria.filter(&all_analyses);
+                print_elite_wallets(&elite_wallets);
+            }
+            "list" => print_elite_wallets(&elite_wallets),
+            "analyze" => {
+                if let Some(address) = args.first() {
+                    analyze_and_cache(&rpc_client, address, &mut all_analyses).await;
+                    elite_wallets = criteria.filter(&all_analyses);
+                } else {
+                    println!("Usage: analyze <pubkey>");
+                }
+            }
+            "save" => match save_wallets(output_format, &elite_wallets) {
+                Ok(path) => println!("✅ Saved {} elite wallets to {}", elite_wallets.len(), path),
+                Err(e) => println!("❌ Failed to save: {}", e),
+            },
+            "quit" | "exit" => {
+                println!("👋 Exiting.");
+                break;
+            }
+            "" => {}
+            other => println!("Unknown command '{}'. Type `help` for the command list.", other),
+        }
+    }
+
+    Ok(())
+}
+
+/// The re-appliable elite screening thresholds, editable at runtime via `threshold wr|score|trades <n>`.
+struct EliteCriteria {
+    min_score: f64,
+    min_win_rate: f64,
+    min_trades: u64,
+}
+
+impl Default for EliteCriteria {
+    fn default() -> Self {
+        // Elite criteria: 75%+ smart score, 70%+ win rate, 20+ trades
+        Self { min_score: 0.75, min_win_rate: 70.0, min_trades: 20 }
+    }
+}
+
+impl EliteCriteria {
+    fn matches(&self, analysis: &WalletAnalysis) -> bool {
+        analysis.smart_money_score >= self.min_score
+            && analysis.metrics.win_rate >= self.min_win_rate
+            && analysis.metrics.total_trades >= self.min_trades
+    }
+
+    /// Re-apply these thresholds against the already-fetched analyses and sort by score -
+    /// no RPC calls, so re-filtering is instant.
+    fn filter(&self, all_analyses: &[(Pubkey, WalletAnalysis)]) -> Vec<(Pubkey, WalletAnalysis)> {
+        let mut matching: Vec<(Pubkey, WalletAnalysis)> = all_analyses
+            .iter()
+            .filter(|(_, analysis)| self.matches(analysis))
+            .cloned()
+            .collect();
+
+        matching.sort_by(|a, b| b.1.smart_money_score.partial_cmp(&a.1.smart_money_score).unwrap());
+        matching
+    }
+}
+
+fn print_repl_help() {
+    println!(
+        "Commands:\n\
+         \u{20}  threshold wr <pct>      Set the minimum win rate (e.g. `threshold wr 80`)\n\
+         \u{20}  threshold score <f>     Set the minimum smart money score (e.g. `threshold score 0.85`)\n\
+         \u{20}  threshold trades <n>    Set the minimum trade count\n\
+         \u{20}  rescore                 Re-apply current thresholds to cached analyses\n\
+         \u{20}  analyze <pubkey>        Analyze one more wallet (hits the RPC) and re-score\n\
+         \u{20}  list                    Show the current elite wallet list\n\
+         \u{20}  save                    Save the current elite wallet list\n\
+         \u{20}  quit                    Exit"
+    );
+}
+
+fn set_threshold(criteria: &mut EliteCriteria, args: &[&str]) {
+    let (Some(&field), Some(&value)) = (args.first(), args.get(1)) else {
+        println!("Usage: threshold wr|score|trades <value>");
+        return;
+    };
+
+    match field {
+        "wr" => match value.parse::<f64>() {
+            Ok(v) => {
+                criteria.min_win_rate = v;
+                println!("Minimum win rate set to {:.1}%", v);
+            }
+            Err(_) => println!("Invalid value '{}'", value),
+        },
+        "score" => match value.parse::<f64>() {
+            Ok(v) => {
+                criteria.min_score = v;
+                println!("Minimum smart money score set to {:.2}", v);
+            }
+            Err(_) => println!("Invalid value '{}'", value),
+        },
+        "trades" => match value.parse::<u64>() {
+            Ok(v) => {
+                criteria.min_trades = v;
+                println!("Minimum trade count set to {}", v);
+            }
+            Err(_) => println!("Invalid value '{}'", value),
+        },
+        other => println!("Unknown threshold '{}'; expected wr|score|trades", other),
+    }
+}
+
+/// Analyze a wallet not already in the cache (or re-analyze one that is) and upsert it into
+/// `all_analyses`, so `criteria.filter` can pick it up on the next re-score.
+async fn analyze_and_cache(
+    rpc_client: &FallbackRpcClient,
+    address: &str,
+    all_analyses: &mut Vec<(Pubkey, WalletAnalysis)>,
+) {
+    let wallet = match Pubkey::from_str(address) {
+        Ok(wallet) => wallet,
+        Err(e) => {
+            println!("Invalid pubkey '{}': {}", address, e);
+            return;
+        }
+    };
+
+    match analyze_wallet(rpc_client, &wallet).await {
+        Ok((analysis, _)) => {
+            println!(
+                " Score: {:.2} | WR: {:.1}% | Trades: {}",
+                analysis.smart_money_score, analysis.metrics.win_rate, analysis.metrics.total_trades,
+            );
+            all_analyses.retain(|(w, _)| w != &wallet);
+            all_analyses.push((wallet, analysis));
+        }
+        Err(e) => println!(" ❌ Error: {}", e),
+    }
+}
+
+fn print_elite_wallets(elite_wallets: &[(Pubkey, WalletAnalysis)]) {
+    println!("\n🎯 {} ELITE wallet(s) match the current thresholds\n", elite_wallets.len());
+
+    if elite_wallets.is_empty() {
+        return;
+    }
 
-    // Display top wallets
     println!("╔═══════════════════════════════════════════════════════════════╗");
     println!("║                    TOP ELITE WALLETS                          ║");
     println!("╠═══════════════════════════════════════════════════════════════╣");
@@ -134,15 +336,20 @@ async fn main() -> Result<()> {
     }
 
     println!("╚═══════════════════════════════════════════════════════════════╝\n");
+}
 
-    // Save to file
-    save_wallets_to_file(&elite_wallets)?;
-
-    println!("✅ Saved {} elite wallets to tracked_wallets.txt", elite_wallets.len());
-    println!("\n🚀 Ready to run the bot with these wallets!");
-    println!("   cargo run --bin bot-enhanced\n");
-
-    Ok(())
+fn save_wallets(
+    output_format: OutputFormat,
+    elite_wallets: &[(Pubkey, WalletAnalysis)],
+) -> Result<String> {
+    match output_format {
+        OutputFormat::Text => {
+            save_wallets_to_file(elite_wallets)?;
+            Ok("tracked_wallets.txt".to_string())
+        }
+        OutputFormat::Json => save_wallets_json(elite_wallets).map(|p| p.to_string()),
+        OutputFormat::Csv => save_wallets_csv(elite_wallets).map(|p| p.to_string()),
+    }
 }
 
 async fn analyze_wallet(
@@ -155,27 +362,37 @@ async fn analyze_wallet(
         return Err(anyhow::anyhow!("No trades found"));
     }
 
-    let analysis = WalletMetricsCalculator::build_wallet_analysis(wallet, &trades)?;
+    let analysis = WalletMetricsCalculator::build_wallet_analysis(wallet, &trades, LotMatchingMode::Lifo)?;
 
     Ok((analysis, trades))
 }
 
-fn get_candidate_wallets() -> Vec<String> {
-    // These are example addresses - in production, you'd:
-    // 1. Fetch from DexScreener API (top traders on trending tokens)
-    // 2. Scrape from on-chain data (top holders/traders)
-    // 3. Monitor pump.fun for early buyers
-    // 4. Track wallets mentioned in alpha groups
+/// Auto-discover candidate wallets from chain state: top holders of a trending mint, plus
+/// recent signers of a DEX program's transactions. `FIND_WALLETS_TRENDING_MINT` and
+/// `FIND_WALLETS_DEX_PROGRAM` let a caller point this at whatever's trending right now;
+/// the fallbacks are BONK and Raydium's AMM v4 program, so the tool still discovers
+/// something with zero configuration.
+async fn get_candidate_wallets(rpc: &FallbackRpcClient) -> Result<Vec<Pubkey>> {
+    let trending_mint = std::env::var("FIND_WALLETS_TRENDING_MINT")
+        .ok()
+        .and_then(|s| Pubkey::from_str(&s).ok())
+        .unwrap_or_else(|| Pubkey::from_str("DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263").unwrap());
+
+    let dex_program = std::env::var("FIND_WALLETS_DEX_PROGRAM")
+        .ok()
+        .and_then(|s| Pubkey::from_str(&s).ok())
+        .unwrap_or_else(|| Pubkey::from_str("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8").unwrap());
+
+    let sources = vec![
+        CandidateSource::TopHolders { mint: trending_mint, n: 20 },
+        CandidateSource::RecentTraders { program: dex_program, slots: 1500 },
+    ];
 
-    vec![
-        // Add real wallet addresses here
-        // For now, returning empty so users must add their own
-    ]
+    let candidates = discover_candidates(rpc, &sources, 50).await?;
+    Ok(candidates)
 }
 
 fn save_wallets_to_file(wallets: &[(Pubkey, WalletAnalysis)]) -> Result<()> {
-    use std::io::Write;
-
     let mut file = std::fs::File::create("tracked_wallets.txt")?;
 
     writeln!(file, "# Elite Wallets - Auto-generated by find-wallets")?;
@@ -195,3 +412,45 @@ fn save_wallets_to_file(wallets: &[(Pubkey, WalletAnalysis)]) -> Result<()> {
 
     Ok(())
 }
+
+fn to_records(wallets: &[(Pubkey, WalletAnalysis)]) -> Vec<WalletRecord> {
+    wallets
+        .iter()
+        .map(|(wallet, analysis)| WalletRecord {
+            wallet: wallet.to_string(),
+            smart_money_score: analysis.smart_money_score,
+            win_rate: analysis.metrics.win_rate,
+            total_trades: analysis.metrics.total_trades,
+            trades_last_24h: analysis.metrics.trades_last_24h,
+            risk_score: analysis.risk_score,
+        })
+        .collect()
+}
+
+fn save_wallets_json(wallets: &[(Pubkey, WalletAnalysis)]) -> Result<&'static str> {
+    let path = "tracked_wallets.json";
+    let file = std::fs::File::create(path)?;
+    serde_json::to_writer_pretty(file, &to_records(wallets))?;
+    Ok(path)
+}
+
+fn save_wallets_csv(wallets: &[(Pubkey, WalletAnalysis)]) -> Result<&'static str> {
+    let path = "tracked_wallets.csv";
+    let mut file = std::fs::File::create(path)?;
+
+    writeln!(file, "wallet,smart_money_score,win_rate,total_trades,trades_last_24h,risk_score")?;
+    for record in to_records(wallets) {
+        writeln!(
+            file,
+            "{},{},{},{},{},{}",
+            record.wallet,
+            record.smart_money_score,
+            record.win_rate,
+            record.total_trades,
+            record.trades_last_24h,
+            record.risk_score,
+        )?;
+    }
+
+    Ok(path)
+}
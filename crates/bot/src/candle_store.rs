@@ -0,0 +1,127 @@
+// Builds real OHLCV candles at multiple resolutions from the live price/volume ticks
+// `MarketFeed` receives, so `ChartAnalyzer` can read an actual bar series instead of the
+// point-in-time `price_change_5m/1h/24h` snapshot `analyze_entry_exit` fakes technical
+// analysis from. Mirrors `trading_analysis::candles::CandleBuilder`'s bucketing rule
+// (`floor(ts / resolution_secs) * resolution_secs`), but folds in one tick at a time instead
+// of rebuilding from a full trade history, and only ever keeps a bounded ring buffer of
+// closed candles per (token, resolution) rather than the whole history.
+use dashmap::DashMap;
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, VecDeque};
+use trading_analysis::candles::{Candle, Resolution};
+
+/// Closed candles retained per (token, resolution) - enough headroom over
+/// `ChartAnalyzer::analyze_multi_resolution`'s 20-candle lookback for a live feed that may
+/// run for days between restarts.
+const RING_BUFFER_CAPACITY: usize = 300;
+
+struct Series {
+    closed: VecDeque<Candle>,
+    open: Option<Candle>,
+}
+
+pub struct CandleStore {
+    resolutions: Vec<Resolution>,
+    series: DashMap<(Pubkey, Resolution), Series>,
+}
+
+impl CandleStore {
+    pub fn new() -> Self {
+        Self {
+            resolutions: Resolution::chart_set().to_vec(),
+            series: DashMap::new(),
+        }
+    }
+
+    /// Fold one price/volume tick into every tracked resolution's current bucket for `token_mint`.
+    pub fn record_tick(&self, token_mint: Pubkey, price: Decimal, volume: Decimal, ts: i64) {
+        for &resolution in &self.resolutions {
+            self.record_for_resolution(token_mint, resolution, price, volume, ts);
+        }
+    }
+
+    fn record_for_resolution(&self, token_mint: Pubkey, resolution: Resolution, price: Decimal, volume: Decimal, ts: i64) {
+        let interval = resolution.seconds();
+        let bucket_start = ts.div_euclid(interval) * interval;
+
+        let mut entry = self
+            .series
+            .entry((token_mint, resolution))
+            .or_insert_with(|| Series { closed: VecDeque::with_capacity(RING_BUFFER_CAPACITY), open: None });
+
+        match entry.open.as_mut() {
+            Some(candle) if candle.bucket_start == bucket_start => {
+                candle.high = candle.high.max(price);
+                candle.low = candle.low.min(price);
+                candle.close = price;
+                candle.volume += volume;
+                candle.trade_count += 1;
+            }
+            Some(candle) if bucket_start > candle.bucket_start => {
+                let closed = std::mem::replace(
+                    candle,
+                    Candle {
+                        token_mint,
+                        resolution,
+                        bucket_start,
+                        open: price,
+                        high: price,
+                        low: price,
+                        close: price,
+                        volume,
+                        trade_count: 1,
+                        is_gap_fill: false,
+                        is_open: true,
+                    },
+                );
+                Self::push_closed(&mut entry.closed, closed);
+            }
+            // A tick for a bucket that's already closed (out-of-order delivery) - drop it
+            // rather than reopening history.
+            Some(_) => {}
+            None => {
+                entry.open = Some(Candle {
+                    token_mint,
+                    resolution,
+                    bucket_start,
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume,
+                    trade_count: 1,
+                    is_gap_fill: false,
+                    is_open: true,
+                });
+            }
+        }
+    }
+
+    fn push_closed(closed: &mut VecDeque<Candle>, mut candle: Candle) {
+        candle.is_open = false;
+        if closed.len() == RING_BUFFER_CAPACITY {
+            closed.pop_front();
+        }
+        closed.push_back(candle);
+    }
+
+    /// Closed candle history plus the still-accumulating current bucket for one
+    /// (token, resolution) - the series shape `ChartAnalyzer::analyze_candles` expects.
+    pub fn series(&self, token_mint: &Pubkey, resolution: Resolution) -> Vec<Candle> {
+        let Some(entry) = self.series.get(&(*token_mint, resolution)) else {
+            return Vec::new();
+        };
+        let mut out: Vec<Candle> = entry.closed.iter().cloned().collect();
+        if let Some(open) = &entry.open {
+            out.push(open.clone());
+        }
+        out
+    }
+
+    /// Every tracked resolution's series for `token_mint`, ready for
+    /// `ChartAnalyzer::analyze_multi_resolution`.
+    pub fn all_series(&self, token_mint: &Pubkey) -> HashMap<Resolution, Vec<Candle>> {
+        self.resolutions.iter().map(|&res| (res, self.series(token_mint, res))).collect()
+    }
+}
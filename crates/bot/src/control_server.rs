@@ -0,0 +1,252 @@
+// Local runtime control plane: a small JSON-RPC 2.0 server over HTTP exposing live
+// `BotConfig` state. `StrategyMode` and `RiskLimits`, which the bot would otherwise only
+// pick up at startup, live behind an `Arc<RwLock<BotConfig>>` here so an operator can
+// change them without a restart, the same way `DashboardServer` exposes read-only state
+// over HTTP today. `trading.enabled` is reported by `get_config`/`get_status` but no
+// method changes it; `pause_trading`/`resume_trading` toggle a separate pause flag.
+use axum::{extract::State, routing::post, Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::info;
+use trading_core::{BotConfig, RiskLimits, StrategyMode};
+
+#[derive(Clone)]
+pub struct ControlState {
+    pub config: Arc<RwLock<BotConfig>>,
+    pub paused: Arc<AtomicBool>,
+}
+
+pub struct ControlServer {
+    port: u16,
+    state: ControlState,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    #[allow(dead_code)]
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    id: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcResponse {
+    jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+    id: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code: i32,
+    message: String,
+}
+
+impl ControlServer {
+    pub fn new(port: u16, config: BotConfig) -> Self {
+        Self {
+            port,
+            state: ControlState {
+                config: Arc::new(RwLock::new(config)),
+                paused: Arc::new(AtomicBool::new(false)),
+            },
+        }
+    }
+
+    /// Handle to the live config, for the trading loop to read back whatever an RPC
+    /// call has applied.
+    pub fn shared_config(&self) -> Arc<RwLock<BotConfig>> {
+        self.state.config.clone()
+    }
+
+    /// Handle to the pause flag `pause_trading`/`resume_trading` toggle - there's no
+    /// corresponding field on `BotConfig` today, so it's tracked alongside it rather
+    /// than bolted onto `TradingConfig` for a control-plane-only concern.
+    pub fn paused(&self) -> Arc<AtomicBool> {
+        self.state.paused.clone()
+    }
+
+    fn router(state: ControlState) -> Router {
+        Router::new().route("/rpc", post(handle_rpc)).with_state(state)
+    }
+
+    pub async fn start(self) {
+        info!("🎛️  Starting control server on http://localhost:{}", self.port);
+
+        let app = Self::router(self.state);
+
+        let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", self.port))
+            .await
+            .expect("Failed to bind control server");
+
+        info!("🎛️  Control server ready at http://localhost:{}", self.port);
+
+        axum::serve(listener, app)
+            .await
+            .expect("Failed to start control server");
+    }
+}
+
+async fn handle_rpc(State(state): State<ControlState>, Json(req): Json<RpcRequest>) -> Json<RpcResponse> {
+    let id = req.id.clone();
+
+    match dispatch(&state, &req.method, req.params).await {
+        Ok(result) => Json(RpcResponse { jsonrpc: "2.0", result: Some(result), error: None, id }),
+        Err(message) => Json(RpcResponse {
+            jsonrpc: "2.0",
+            result: None,
+            error: Some(RpcError { code: -32602, message }),
+            id,
+        }),
+    }
+}
+
+async fn dispatch(state: &ControlState, method: &str, params: Value) -> Result<Value, String> {
+    match method {
+        "get_config" => {
+            let config = state.config.read().await;
+            serde_json::to_value(&*config).map_err(|e| e.to_string())
+        }
+        "get_status" => {
+            let config = state.config.read().await;
+            Ok(serde_json::json!({
+                "trading_enabled": config.trading.enabled,
+                "paused": state.paused.load(Ordering::SeqCst),
+                "strategy_mode": config.strategy.mode,
+            }))
+        }
+        "set_strategy_mode" => {
+            let mode_value = params.get("mode").cloned().ok_or("missing 'mode' param")?;
+            let mode: StrategyMode = serde_json::from_value(mode_value).map_err(|e| format!("invalid mode: {}", e))?;
+
+            let mut config = state.config.write().await;
+            config.strategy.mode = mode;
+            Ok(serde_json::json!({ "strategy_mode": mode }))
+        }
+        "pause_trading" => {
+            state.paused.store(true, Ordering::SeqCst);
+            Ok(serde_json::json!({ "paused": true }))
+        }
+        "resume_trading" => {
+            state.paused.store(false, Ordering::SeqCst);
+            Ok(serde_json::json!({ "paused": false }))
+        }
+        "update_risk_limits" => {
+            let limits: RiskLimits = serde_json::from_value(params).map_err(|e| format!("invalid risk limits: {}", e))?;
+            validate_risk_limits(&limits)?;
+
+            let mut config = state.config.write().await;
+            config.risk = limits;
+            Ok(serde_json::to_value(&config.risk).map_err(|e| e.to_string())?)
+        }
+        _ => Err(format!("unknown method '{}'", method)),
+    }
+}
+
+fn validate_risk_limits(limits: &RiskLimits) -> Result<(), String> {
+    if limits.max_position_size_percentage <= 0.0 || limits.max_position_size_percentage > 100.0 {
+        return Err("max_position_size_percentage must be in (0, 100]".to_string());
+    }
+    if limits.max_daily_loss_percentage <= 0.0 || limits.max_daily_loss_percentage > 100.0 {
+        return Err("max_daily_loss_percentage must be in (0, 100]".to_string());
+    }
+    if limits.max_slippage_bps == 0 || limits.max_slippage_bps > 10_000 {
+        return Err("max_slippage_bps must be in (0, 10000]".to_string());
+    }
+    if limits.stop_loss_percentage <= 0.0 || limits.take_profit_percentage <= 0.0 {
+        return Err("stop_loss_percentage and take_profit_percentage must be positive".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn spawn_test_server() -> (String, Arc<RwLock<BotConfig>>, Arc<AtomicBool>) {
+        let server = ControlServer::new(0, BotConfig::default());
+        let config = server.shared_config();
+        let paused = server.paused();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = ControlServer::router(server.state.clone());
+
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        (format!("http://{}/rpc", addr), config, paused)
+    }
+
+    async fn call(url: &str, method: &str, params: Value) -> Value {
+        reqwest::Client::new()
+            .post(url)
+            .json(&json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn get_status_reflects_defaults() {
+        let (url, _config, _paused) = spawn_test_server().await;
+
+        let response = call(&url, "get_status", json!({})).await;
+        assert_eq!(response["result"]["trading_enabled"], json!(false));
+        assert_eq!(response["result"]["paused"], json!(false));
+    }
+
+    #[tokio::test]
+    async fn pause_and_resume_round_trip() {
+        let (url, _config, paused) = spawn_test_server().await;
+
+        call(&url, "pause_trading", json!({})).await;
+        assert!(paused.load(Ordering::SeqCst));
+
+        let response = call(&url, "resume_trading", json!({})).await;
+        assert_eq!(response["result"]["paused"], json!(false));
+        assert!(!paused.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn set_strategy_mode_applies_atomically() {
+        let (url, config, _paused) = spawn_test_server().await;
+
+        let response = call(&url, "set_strategy_mode", json!({ "mode": "Scalping" })).await;
+        assert!(response["error"].is_null());
+        assert_eq!(config.read().await.strategy.mode, StrategyMode::Scalping);
+    }
+
+    #[tokio::test]
+    async fn update_risk_limits_rejects_invalid_input() {
+        let (url, config, _paused) = spawn_test_server().await;
+
+        let mut bad_limits = serde_json::to_value(&RiskLimits::default()).unwrap();
+        bad_limits["max_slippage_bps"] = json!(0);
+
+        let response = call(&url, "update_risk_limits", bad_limits).await;
+        assert!(response["error"].is_object());
+        assert_eq!(config.read().await.risk.max_slippage_bps, RiskLimits::default().max_slippage_bps);
+    }
+
+    #[tokio::test]
+    async fn unknown_method_is_rejected() {
+        let (url, _config, _paused) = spawn_test_server().await;
+
+        let response = call(&url, "delete_everything", json!({})).await;
+        assert!(response["error"].is_object());
+    }
+}
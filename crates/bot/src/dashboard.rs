@@ -1,25 +1,31 @@
 use axum::{
-    extract::State,
+    extract::{Query, State},
     response::{Html, IntoResponse, Response, sse::{Event, Sse}},
     routing::get,
     Json, Router,
 };
 use std::convert::Infallible;
+use std::str::FromStr;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::{broadcast, RwLock};
 use tokio_stream::{Stream, StreamExt as _};
 use tokio::time::{interval, Duration};
 use serde::{Deserialize, Serialize};
 use tower_http::cors::CorsLayer;
 use tracing::{info, error};
+use trading_analysis::candles::{Candle, Resolution};
+use trading_db::candles::CandleRepository;
 
-use crate::portfolio_monitor::DailyStats;
+use crate::portfolio_monitor::{DailyStats, PortfolioEvent};
 use crate::persistence::{TradeHistory, SerializableClosedTrade};
 
 #[derive(Clone)]
 pub struct DashboardState {
     pub stats: Arc<RwLock<DailyStats>>,
     pub trade_history: Arc<RwLock<TradeHistory>>,
+    pub candles: Option<CandleRepository>,
+    pub portfolio_events: Option<broadcast::Sender<PortfolioEvent>>,
 }
 
 pub struct DashboardServer {
@@ -35,9 +41,28 @@ struct ApiResponse<T> {
 
 impl DashboardServer {
     pub fn new(port: u16, trade_history: TradeHistory) -> Self {
+        Self::with_state(port, trade_history, None, None)
+    }
+
+    pub fn with_candles(
+        port: u16,
+        trade_history: TradeHistory,
+        candles: Option<CandleRepository>,
+    ) -> Self {
+        Self::with_state(port, trade_history, candles, None)
+    }
+
+    pub fn with_state(
+        port: u16,
+        trade_history: TradeHistory,
+        candles: Option<CandleRepository>,
+        portfolio_events: Option<broadcast::Sender<PortfolioEvent>>,
+    ) -> Self {
         let state = DashboardState {
             stats: Arc::new(RwLock::new(DailyStats::default())),
             trade_history: Arc::new(RwLock::new(trade_history)),
+            candles,
+            portfolio_events,
         };
 
         Self { port, state }
@@ -54,6 +79,7 @@ impl DashboardServer {
             .route("/", get(serve_dashboard))
             .route("/api/stats", get(get_stats))
             .route("/api/trades", get(get_trades))
+            .route("/api/candles", get(get_candles))
             .route("/api/stream", get(sse_handler))
             .layer(CorsLayer::permissive())
             .with_state(self.state);
@@ -95,14 +121,92 @@ async fn get_trades(
     })
 }
 
+#[derive(Debug, Deserialize)]
+struct CandlesQuery {
+    mint: String,
+    period: String,
+}
+
+async fn get_candles(
+    State(state): State<DashboardState>,
+    Query(query): Query<CandlesQuery>,
+) -> Response {
+    let Some(candles) = &state.candles else {
+        return Json(ApiResponse {
+            success: false,
+            data: "candle storage not configured".to_string(),
+        })
+        .into_response();
+    };
+
+    let Some(resolution) = Resolution::from_str(&query.period) else {
+        return Json(ApiResponse {
+            success: false,
+            data: format!("unknown period '{}'", query.period),
+        })
+        .into_response();
+    };
+
+    let Ok(mint) = Pubkey::from_str(&query.mint) else {
+        return Json(ApiResponse {
+            success: false,
+            data: format!("invalid mint '{}'", query.mint),
+        })
+        .into_response();
+    };
+
+    match candles.get_recent_candles(&mint, resolution, 500).await {
+        Ok(data) => Json(ApiResponse {
+            success: true,
+            data,
+        })
+        .into_response(),
+        Err(e) => {
+            error!("Failed to load candles: {}", e);
+            Json(ApiResponse {
+                success: false,
+                data: Vec::<Candle>::new(),
+            })
+            .into_response()
+        }
+    }
+}
+
 async fn sse_handler(
     State(state): State<DashboardState>,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let mut portfolio_events = state.portfolio_events.as_ref().map(|tx| tx.subscribe());
+
     let stream = async_stream::stream! {
         let mut tick = interval(Duration::from_secs(5));
 
         loop {
-            tick.tick().await;
+            let event = match &mut portfolio_events {
+                Some(events) => {
+                    tokio::select! {
+                        _ = tick.tick() => None,
+                        result = events.recv() => {
+                            match result {
+                                Ok(event) => Some(event),
+                                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                                Err(broadcast::error::RecvError::Closed) => None,
+                            }
+                        }
+                    }
+                }
+                None => {
+                    tick.tick().await;
+                    None
+                }
+            };
+
+            if let Some(event) = event {
+                match serde_json::to_string(&event) {
+                    Ok(json) => yield Ok(Event::default().event("trade").data(json)),
+                    Err(e) => error!("Failed to serialize portfolio event: {}", e),
+                }
+                continue;
+            }
 
             let stats = state.stats.read().await.clone();
 
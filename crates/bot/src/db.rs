@@ -0,0 +1,341 @@
+// Pooled SQLite persistence for portfolio state (open positions, closed trades, daily
+// stats). This sits alongside `trading_db::Database` (the async sqlx pool used for
+// wallet/candle data) rather than replacing it. Checkouts come from an `r2d2` pool
+// rather than a single `rusqlite::Connection`, so `Database` is `Clone` and can be used
+// from separate tasks at once.
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{params, OptionalExtension};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use tracing::info;
+
+use crate::money::Money;
+use crate::portfolio_monitor::{ClosedTrade, DailyStats, ExitTrigger, OpenPosition};
+
+#[derive(Clone)]
+pub struct Database {
+    pool: Pool<SqliteConnectionManager>,
+}
+
+impl Database {
+    /// Open (creating if necessary) the SQLite file at `path` and run migrations.
+    pub fn new(path: &str) -> Result<Self> {
+        info!("Opening portfolio database: {}", path);
+
+        let manager = SqliteConnectionManager::file(path);
+        let pool = Pool::new(manager)?;
+
+        let db = Self { pool };
+        db.run_migrations()?;
+
+        Ok(db)
+    }
+
+    fn run_migrations(&self) -> Result<()> {
+        let conn = self.pool.get()?;
+        conn.execute_batch(trading_db::schema::CREATE_POSITIONS_TABLE)?;
+        conn.execute_batch(trading_db::schema::CREATE_DAILY_STATS_TABLE)?;
+        Ok(())
+    }
+
+    /// Upsert the open-position row for `position.token_mint`, clearing any exit fields
+    /// left over from a previous trade on the same mint.
+    pub fn upsert_position(&self, position: &OpenPosition) -> Result<()> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "INSERT INTO positions
+                (token_mint, token_symbol, entry_time, entry_price, entry_mc, amount,
+                 stop_loss, take_profit, peak_price, status, exit_time, exit_price, exit_reason, pnl,
+                 pnl_pct, hold_time_minutes, expires_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, 'open', NULL, NULL, NULL, NULL, NULL, ?10, ?11, datetime('now'))
+             ON CONFLICT(token_mint) DO UPDATE SET
+                entry_time = excluded.entry_time,
+                entry_price = excluded.entry_price,
+                entry_mc = excluded.entry_mc,
+                amount = excluded.amount,
+                stop_loss = excluded.stop_loss,
+                take_profit = excluded.take_profit,
+                peak_price = excluded.peak_price,
+                status = 'open',
+                exit_time = NULL,
+                exit_price = NULL,
+                exit_reason = NULL,
+                pnl = NULL,
+                pnl_pct = NULL,
+                hold_time_minutes = excluded.hold_time_minutes,
+                expires_at = excluded.expires_at,
+                updated_at = datetime('now')",
+            params![
+                position.token_mint.to_string(),
+                position.token_symbol,
+                position.entry_time.to_rfc3339(),
+                position.entry_price.to_f64(),
+                position.entry_mc.to_f64(),
+                position.amount.to_f64(),
+                position.stop_loss.to_f64(),
+                position.take_profit.to_f64(),
+                position.peak_price.to_f64(),
+                position.hold_time_minutes,
+                position.expires_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Record a closed trade, overwriting the `positions` row for its mint with the
+    /// closed/exit state - the same row the position lived in while open.
+    pub fn insert_trade(&self, trade: &ClosedTrade) -> Result<()> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "INSERT INTO positions
+                (token_mint, token_symbol, entry_time, entry_price, entry_mc, amount,
+                 stop_loss, take_profit, status, exit_time, exit_price, exit_reason, pnl,
+                 pnl_pct, hold_time_minutes, updated_at)
+             VALUES (?1, ?2, ?3, ?4, 0, 0, 0, 0, 'closed', ?5, ?6, ?7, ?8, ?9, ?10, datetime('now'))
+             ON CONFLICT(token_mint) DO UPDATE SET
+                status = 'closed',
+                exit_time = excluded.exit_time,
+                exit_price = excluded.exit_price,
+                exit_reason = excluded.exit_reason,
+                pnl = excluded.pnl,
+                pnl_pct = excluded.pnl_pct,
+                hold_time_minutes = excluded.hold_time_minutes,
+                updated_at = datetime('now')",
+            params![
+                trade.token_mint.to_string(),
+                trade.token_symbol,
+                trade.entry_time.to_rfc3339(),
+                trade.entry_price.to_f64(),
+                trade.exit_time.to_rfc3339(),
+                trade.exit_price.to_f64(),
+                trade.trigger.as_str(),
+                trade.pnl.to_f64(),
+                trade.pnl_pct,
+                trade.hold_time_minutes,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Upsert today's daily stats row, keyed by date.
+    pub fn record_daily_stats(&self, stats: &DailyStats) -> Result<()> {
+        let conn = self.pool.get()?;
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        conn.execute(
+            "INSERT INTO daily_stats
+                (date, total_trades, wins, losses, win_rate, total_pnl, biggest_win,
+                 biggest_loss, avg_win, avg_loss, portfolio_value, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, datetime('now'))
+             ON CONFLICT(date) DO UPDATE SET
+                total_trades = excluded.total_trades,
+                wins = excluded.wins,
+                losses = excluded.losses,
+                win_rate = excluded.win_rate,
+                total_pnl = excluded.total_pnl,
+                biggest_win = excluded.biggest_win,
+                biggest_loss = excluded.biggest_loss,
+                avg_win = excluded.avg_win,
+                avg_loss = excluded.avg_loss,
+                portfolio_value = excluded.portfolio_value,
+                updated_at = datetime('now')",
+            params![
+                today,
+                stats.total_trades,
+                stats.wins,
+                stats.losses,
+                stats.win_rate,
+                stats.total_pnl.to_string(),
+                stats.biggest_win.to_string(),
+                stats.biggest_loss.to_string(),
+                stats.avg_win.to_string(),
+                stats.avg_loss.to_string(),
+                stats.portfolio_value.to_string(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Reload positions left `open` from a previous run, so a restart doesn't lose track
+    /// of live trades.
+    pub fn load_open_positions(&self) -> Result<Vec<OpenPosition>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT token_mint, token_symbol, entry_time, entry_price, entry_mc, amount,
+                    stop_loss, take_profit, peak_price, hold_time_minutes, expires_at
+             FROM positions WHERE status = 'open'",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            let token_mint: String = row.get(0)?;
+            let entry_time: String = row.get(2)?;
+            let entry_price: f64 = row.get(3)?;
+            let entry_mc: f64 = row.get(4)?;
+            let amount: f64 = row.get(5)?;
+            let stop_loss: f64 = row.get(6)?;
+            let take_profit: f64 = row.get(7)?;
+            let peak_price: f64 = row.get(8)?;
+
+            Ok((
+                token_mint,
+                row.get::<_, String>(1)?,
+                entry_time,
+                entry_price,
+                entry_mc,
+                amount,
+                stop_loss,
+                take_profit,
+                peak_price,
+                row.get::<_, i64>(9)?,
+                row.get::<_, Option<String>>(10)?,
+            ))
+        })?;
+
+        let mut positions = Vec::new();
+        for row in rows {
+            let (token_mint, token_symbol, entry_time, entry_price, entry_mc, amount, stop_loss, take_profit, peak_price, hold_time_minutes, expires_at) = row?;
+
+            let (Some(token_mint), Some(entry_time)) = (
+                Pubkey::from_str(&token_mint).ok(),
+                DateTime::parse_from_rfc3339(&entry_time).ok().map(|dt| dt.with_timezone(&Utc)),
+            ) else {
+                continue;
+            };
+
+            let entry_price = Money::from_f64(entry_price);
+            let entry_mc = Money::from_f64(entry_mc);
+
+            // Rows written before `expires_at` existed have no value for it - fall back to the
+            // old hard cutoff so a pre-migration position isn't held open forever.
+            let expires_at = expires_at
+                .and_then(|t| DateTime::parse_from_rfc3339(&t).ok())
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|| entry_time + chrono::Duration::hours(48));
+
+            positions.push(OpenPosition {
+                token_mint,
+                token_symbol,
+                entry_time,
+                entry_price,
+                entry_mc,
+                amount: Money::from_f64(amount),
+                current_price: entry_price,
+                current_mc: entry_mc,
+                unrealized_pnl: Money::ZERO,
+                unrealized_pnl_pct: 0.0,
+                stop_loss: Money::from_f64(stop_loss),
+                take_profit: Money::from_f64(take_profit),
+                peak_price: Money::from_f64(peak_price),
+                hold_time_minutes,
+                expires_at,
+            });
+        }
+
+        Ok(positions)
+    }
+
+    /// Load closed trades recorded in the `positions` table.
+    pub fn load_closed_trades(&self) -> Result<Vec<ClosedTrade>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT token_mint, token_symbol, entry_time, exit_time, entry_price, exit_price,
+                    pnl, pnl_pct, hold_time_minutes, exit_reason
+             FROM positions WHERE status = 'closed'",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, f64>(4)?,
+                row.get::<_, Option<f64>>(5)?,
+                row.get::<_, Option<f64>>(6)?,
+                row.get::<_, Option<f64>>(7)?,
+                row.get::<_, Option<i64>>(8)?,
+                row.get::<_, Option<String>>(9)?,
+            ))
+        })?;
+
+        let mut trades = Vec::new();
+        for row in rows {
+            let (token_mint, token_symbol, entry_time, exit_time, entry_price, exit_price, pnl, pnl_pct, hold_time_minutes, exit_reason) = row?;
+
+            let (Some(token_mint), Some(entry_time), Some(exit_time)) = (
+                Pubkey::from_str(&token_mint).ok(),
+                DateTime::parse_from_rfc3339(&entry_time).ok().map(|dt| dt.with_timezone(&Utc)),
+                exit_time.and_then(|t| DateTime::parse_from_rfc3339(&t).ok()).map(|dt| dt.with_timezone(&Utc)),
+            ) else {
+                continue;
+            };
+
+            let pnl = Money::from_f64(pnl.unwrap_or(0.0));
+
+            trades.push(ClosedTrade {
+                token_mint,
+                token_symbol,
+                entry_time,
+                exit_time,
+                entry_price: Money::from_f64(entry_price),
+                exit_price: Money::from_f64(exit_price.unwrap_or(0.0)),
+                pnl,
+                pnl_pct: pnl_pct.unwrap_or(0.0),
+                hold_time_minutes: hold_time_minutes.unwrap_or(0),
+                is_win: pnl > Money::ZERO,
+                trigger: exit_reason.as_deref().map(ExitTrigger::from_str).unwrap_or(ExitTrigger::Manual),
+            });
+        }
+
+        Ok(trades)
+    }
+
+    /// Load today's daily stats row, if one was already recorded this run or a prior one.
+    pub fn load_daily_stats(&self) -> Result<Option<DailyStats>> {
+        let conn = self.pool.get()?;
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+
+        let row = conn
+            .query_row(
+                "SELECT total_trades, wins, losses, win_rate, total_pnl, biggest_win,
+                        biggest_loss, avg_win, avg_loss, portfolio_value
+                 FROM daily_stats WHERE date = ?1",
+                params![today],
+                |row| {
+                    Ok((
+                        row.get::<_, u32>(0)?,
+                        row.get::<_, u32>(1)?,
+                        row.get::<_, u32>(2)?,
+                        row.get::<_, f64>(3)?,
+                        row.get::<_, String>(4)?,
+                        row.get::<_, String>(5)?,
+                        row.get::<_, String>(6)?,
+                        row.get::<_, String>(7)?,
+                        row.get::<_, String>(8)?,
+                        row.get::<_, String>(9)?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        Ok(row.map(
+            |(total_trades, wins, losses, win_rate, total_pnl, biggest_win, biggest_loss, avg_win, avg_loss, portfolio_value)| {
+                DailyStats {
+                    total_trades,
+                    wins,
+                    losses,
+                    win_rate,
+                    total_pnl: Money::from_str(&total_pnl).unwrap_or(Money::ZERO),
+                    biggest_win: Money::from_str(&biggest_win).unwrap_or(Money::ZERO),
+                    biggest_loss: Money::from_str(&biggest_loss).unwrap_or(Money::ZERO),
+                    avg_win: Money::from_str(&avg_win).unwrap_or(Money::ZERO),
+                    avg_loss: Money::from_str(&avg_loss).unwrap_or(Money::ZERO),
+                    portfolio_value: Money::from_str(&portfolio_value).unwrap_or(Money::ZERO),
+                    starting_value: Money::from_str(&portfolio_value).unwrap_or(Money::ZERO),
+                }
+            },
+        ))
+    }
+}
@@ -2,6 +2,8 @@
 // Use with: cargo run --bin bot-enhanced
 
 use anyhow::Result;
+use dashmap::DashMap;
+use futures_util::stream::{self, StreamExt};
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::time::Duration;
@@ -21,6 +23,18 @@ mod position_manager;
 mod persistence;
 mod telegram;
 mod dashboard;
+mod control_server;
+mod execution_guard;
+mod fee_tracker;
+mod webhook;
+mod market_feed;
+mod candle_store;
+mod money;
+mod db;
+mod backtest;
+mod notifier;
+mod order_engine;
+mod telegram_control;
 
 use portfolio_monitor::*;
 use alpha_accelerator::*;
@@ -28,6 +42,17 @@ use position_manager::*;
 use persistence::*;
 use telegram::*;
 use dashboard::*;
+use control_server::ControlServer;
+use execution_guard::{ExecutionGuard, SignalSnapshot};
+use fee_tracker::PriorityFeeTracker;
+use webhook::WebhookNotifier;
+use market_feed::MarketFeed;
+use notifier::{CompositeNotifier, DeduplicatingNotifier, DiscordNotifier, HeartbeatMonitor, Notifier, WebhookSink};
+use order_engine::{OrderAction, OrderEngine, OrderType};
+use telegram_control::{Command, ControlRequest, TelegramControl};
+use tokio::sync::mpsc;
+use money::Money;
+use db::Database as PortfolioDatabase;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -79,11 +104,17 @@ async fn main() -> Result<()> {
             .unwrap_or_else(|_| "https://rpc.ankr.com/solana".to_string()),
     ];
 
+    let rpc_max_rps = std::env::var("RPC_MAX_RPS")
+        .unwrap_or_else(|_| "10".to_string())
+        .parse::<usize>()
+        .unwrap_or(10);
+
     let rpc_client = FallbackRpcClient::new(
         rpc_url,
         fallback_rpcs,
         solana_sdk::commitment_config::CommitmentConfig::confirmed(),
-    );
+    )
+    .with_rate_limit(rpc_max_rps);
 
     // Test RPC
     match rpc_client.get_slot().await {
@@ -105,6 +136,11 @@ async fn main() -> Result<()> {
             .unwrap_or_else(|_| "https://api.rugcheck.xyz/v1".to_string())
     );
 
+    // Database (backs candle storage for the dashboard's /api/candles chart route)
+    let database_url = std::env::var("DATABASE_URL")
+        .unwrap_or_else(|_| "sqlite://trading_bot.db".to_string());
+    let db = trading_db::Database::new(&database_url).await?;
+
     // Load tracked wallets
     let tracked_wallets = load_tracked_wallets("tracked_wallets.txt")?;
 
@@ -127,6 +163,32 @@ async fn main() -> Result<()> {
         Decimal::from_f64_retain(starting_capital).unwrap()
     );
 
+    // Pooled SQLite handle for position/trade/stats persistence, written by the main
+    // trading loop (the pool itself is cheaply `Clone`). Restores open positions and
+    // today's stats on startup instead of starting empty after a restart.
+    let portfolio_db_path = std::env::var("PORTFOLIO_DB_PATH")
+        .unwrap_or_else(|_| "portfolio.db".to_string());
+    let portfolio_db = std::sync::Arc::new(PortfolioDatabase::new(&portfolio_db_path)?);
+
+    for position in portfolio_db.load_open_positions()? {
+        portfolio.restore_position(position);
+    }
+    if let Some(stats) = portfolio_db.load_daily_stats()? {
+        portfolio.restore_daily_stats(stats);
+    }
+    info!("💾 Restored {} open position(s) from {}", portfolio.get_position_mints().len(), portfolio_db_path);
+
+    // Console logger: the same boxes that used to print inline from PortfolioMonitor,
+    // now just one subscriber among several on the portfolio event bus.
+    tokio::spawn(portfolio_monitor::run_console_event_logger(portfolio.subscribe()));
+
+    // Optional outbound webhook subscriber
+    if let Ok(webhook_url) = std::env::var("PORTFOLIO_WEBHOOK_URL") {
+        let notifier = WebhookNotifier::new(webhook_url);
+        let events = portfolio.subscribe();
+        tokio::spawn(async move { notifier.run(events).await });
+    }
+
     // Initialize alpha detector
     let alpha_detector = AlphaAccelerator::new(
         3,  // 3+ wallets = strong signal
@@ -136,13 +198,30 @@ async fn main() -> Result<()> {
     // Initialize position manager
     let position_manager = PositionManager::new();
 
+    // Bracket-order book: a genuine stop-loss/take-profit per open position, enforced on
+    // every position-check tick instead of the one-shot `suggested_entry`/`suggested_exit`
+    // a `ChartSignal` hands back and never revisits.
+    let order_engine = OrderEngine::new();
+
+    // Per-token priority-fee history, used to pick a competitive compute-unit price for
+    // follow-up buys on an `UltraSignal` instead of the fixed `priority_fee_microlamports`.
+    let fee_tracker = PriorityFeeTracker::new();
+
+    // Live price feed: subscribes/unsubscribes automatically off the portfolio event bus,
+    // so position checks read a pushed price instead of polling DexScreener every tick.
+    let market_feed_url = std::env::var("MARKET_FEED_WS_URL")
+        .unwrap_or_else(|_| "wss://feed.example.com/market".to_string());
+    let market_feed = std::sync::Arc::new(MarketFeed::new(market_feed_url));
+    market_feed.run();
+    market_feed.run_subscription_manager(portfolio.subscribe());
+
     // Initialize Telegram notifier
     let telegram_enabled = std::env::var("TELEGRAM_ENABLED")
         .unwrap_or_else(|_| "false".to_string())
         .parse::<bool>()
         .unwrap_or(false);
 
-    let telegram = if telegram_enabled {
+    let (telegram, mut control_rx) = if telegram_enabled {
         let token = std::env::var("TELEGRAM_BOT_TOKEN")
             .expect("TELEGRAM_BOT_TOKEN must be set when TELEGRAM_ENABLED=true");
         let chat_id = std::env::var("TELEGRAM_CHAT_ID")
@@ -151,21 +230,60 @@ async fn main() -> Result<()> {
             .expect("TELEGRAM_CHAT_ID must be a valid integer");
 
         info!("📱 Telegram notifications ENABLED");
-        let notifier = TelegramNotifier::new(token, chat_id);
+        let notifier = TelegramNotifier::new(token.clone(), chat_id);
 
         // Test notification
         if let Err(e) = notifier.test_notification().await {
             warn!("Failed to send test notification: {}", e);
         }
 
-        notifier
+        let (control, control_rx) = TelegramControl::new(token, chat_id);
+        tokio::spawn(control.run());
+        info!("📱 Telegram control commands ENABLED (/status /positions /close /pause /resume /sell_all)");
+
+        (notifier, Some(control_rx))
     } else {
         info!("📱 Telegram notifications DISABLED");
-        TelegramNotifier::disabled()
+        (TelegramNotifier::disabled(), None)
     };
 
+    // Fan notifications out to every configured sink - Telegram plus whichever of the
+    // generic-webhook/Discord backends have a URL set - then wrap the lot so a signal that
+    // keeps re-triggering cycle after cycle doesn't spam an operator's phone.
+    let mut sinks: Vec<Box<dyn Notifier>> = vec![Box::new(telegram)];
+
+    if let Ok(webhook_url) = std::env::var("NOTIFIER_WEBHOOK_URL") {
+        info!("🔗 Generic webhook notifications ENABLED");
+        sinks.push(Box::new(WebhookSink::new(webhook_url)));
+    }
+
+    if let Ok(discord_url) = std::env::var("NOTIFIER_DISCORD_WEBHOOK_URL") {
+        info!("💬 Discord notifications ENABLED");
+        sinks.push(Box::new(DiscordNotifier::new(discord_url)));
+    }
+
+    let notifier_dedup_cooldown_secs = std::env::var("NOTIFIER_DEDUP_COOLDOWN_SECS")
+        .unwrap_or_else(|_| "900".to_string())
+        .parse::<u64>()
+        .unwrap_or(900);
+
+    let notifier: std::sync::Arc<dyn Notifier> = std::sync::Arc::new(DeduplicatingNotifier::new(
+        CompositeNotifier::new(sinks),
+        Duration::from_secs(notifier_dedup_cooldown_secs),
+    ));
+
     // Send startup notification
-    telegram.notify_bot_started(Decimal::from_f64_retain(starting_capital).unwrap()).await;
+    notifier.notify_bot_started(Decimal::from_f64_retain(starting_capital).unwrap()).await;
+
+    // Watches for a cycle that never completes (a wedged task, a hung RPC call) and keeps
+    // alerting every `heartbeat_check_interval` while it stays stuck.
+    let heartbeat_threshold_secs = std::env::var("HEARTBEAT_THRESHOLD_SECS")
+        .unwrap_or_else(|_| "1800".to_string())
+        .parse::<u64>()
+        .unwrap_or(1800);
+
+    let heartbeat = HeartbeatMonitor::new();
+    heartbeat.run(notifier.clone(), Duration::from_secs(heartbeat_threshold_secs), Duration::from_secs(60));
 
     // Load trade history
     let history_path = std::path::Path::new("trade_history.json");
@@ -185,7 +303,12 @@ async fn main() -> Result<()> {
             .parse::<u16>()
             .unwrap_or(3000);
 
-        let dashboard = DashboardServer::new(dashboard_port, trade_history.clone());
+        let dashboard = DashboardServer::with_state(
+            dashboard_port,
+            trade_history.clone(),
+            Some(db.candles()),
+            Some(portfolio.events_sender()),
+        );
         let dashboard_state = dashboard.get_state();
 
         // Start dashboard server in background
@@ -200,6 +323,51 @@ async fn main() -> Result<()> {
         None
     };
 
+    // Initialize the runtime control server - lets an operator pause/resume new entries,
+    // switch strategy mode, or push new risk limits without restarting the bot.
+    let control_enabled = std::env::var("CONTROL_SERVER_ENABLED")
+        .unwrap_or_else(|_| "true".to_string())
+        .parse::<bool>()
+        .unwrap_or(true);
+
+    let (live_config, paused) = if control_enabled {
+        let control_port = std::env::var("CONTROL_SERVER_PORT")
+            .unwrap_or_else(|_| "9191".to_string())
+            .parse::<u16>()
+            .unwrap_or(9191);
+
+        let mut initial_config = BotConfig::default();
+        initial_config.trading.enabled = trading_enabled;
+
+        let control_server = ControlServer::new(control_port, initial_config);
+        let live_config = control_server.shared_config();
+        let paused = control_server.paused();
+
+        tokio::spawn(async move {
+            control_server.start().await;
+        });
+
+        info!("🎛️  Control server enabled at http://localhost:{}/rpc", control_port);
+        (Some(live_config), paused)
+    } else {
+        info!("🎛️  Control server disabled");
+        (None, std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)))
+    };
+
+    // Shared with the /pause and /resume Telegram commands below, independent of
+    // `trading_enabled` (paper-vs-live, not accepting-vs-not). While set, the engine keeps
+    // managing open positions but stops opening new ones - a resume-only mode for draining
+    // the book during RPC instability or before a planned shutdown.
+    let maintenance_mode = std::env::var("MAINTENANCE_MODE_ENABLED")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false);
+    if maintenance_mode {
+        warn!("🛠️  Starting in maintenance mode - new entries are blocked until /resume");
+        paused.store(true, std::sync::atomic::Ordering::SeqCst);
+        notifier.notify_maintenance_mode(true, "startup flag").await;
+    }
+
     // Configuration
     let min_smart_score = std::env::var("MIN_SMART_MONEY_SCORE")
         .unwrap_or_else(|_| "0.8".to_string())
@@ -211,57 +379,112 @@ async fn main() -> Result<()> {
         .parse::<u64>()
         .unwrap_or(300);
 
+    let wallet_analysis_concurrency = std::env::var("WALLET_ANALYSIS_CONCURRENCY")
+        .unwrap_or_else(|_| "5".to_string())
+        .parse::<usize>()
+        .unwrap_or(5);
+
+    let exit_params = ExitParams {
+        hard_max_hold_hours: std::env::var("HARD_MAX_HOLD_HOURS")
+            .unwrap_or_else(|_| "48".to_string())
+            .parse::<i64>()
+            .unwrap_or(48),
+        rollover_hour_utc: std::env::var("ROLLOVER_HOUR_UTC").ok().and_then(|v| v.parse::<u32>().ok()),
+        warning_threshold_hours: std::env::var("WARNING_THRESHOLD_HOURS")
+            .unwrap_or_else(|_| "4".to_string())
+            .parse::<i64>()
+            .unwrap_or(4),
+        ..ExitParams::default()
+    };
+
     info!("\n⚙️  Configuration:");
     info!("   Min Smart Money Score: {:.2}", min_smart_score);
     info!("   Analysis Interval: {}s", analysis_interval);
     info!("   Starting Capital: ${:.2}", starting_capital);
+    info!("   Wallet Analysis Concurrency: {}", wallet_analysis_concurrency);
+    info!("   RPC Max Requests/sec: {}", rpc_max_rps);
+    info!("   Notifier Dedup Cooldown: {}s", notifier_dedup_cooldown_secs);
+    info!("   Heartbeat Threshold: {}s", heartbeat_threshold_secs);
+    info!("   Hard Max Hold Hours: {}", exit_params.hard_max_hold_hours);
+    info!("   Rollover Hour (UTC): {:?}", exit_params.rollover_hour_utc);
+    info!("   Warning Threshold Hours: {}", exit_params.warning_threshold_hours);
 
     // Main loop
     info!("\n🚀 Starting accelerated trading loop...\n");
 
     let mut cycle = 0;
-    let mut wallet_analyses: HashMap<Pubkey, WalletAnalysis> = HashMap::new();
-    let mut all_trades: HashMap<Pubkey, Vec<Trade>> = HashMap::new();
+    // Populated concurrently by the bounded worker pool below, so every wallet's slot can be
+    // written as soon as its own analysis finishes instead of waiting for a `&mut HashMap`.
+    let wallet_analyses: DashMap<Pubkey, WalletAnalysis> = DashMap::new();
+    let all_trades: DashMap<Pubkey, Vec<Trade>> = DashMap::new();
 
     loop {
+        drain_control_commands(&mut control_rx, &mut portfolio, &order_engine, &paused, &notifier).await;
+
+        if paused.load(std::sync::atomic::Ordering::SeqCst) {
+            info!("⏸️  Trading paused (control server or /pause), skipping cycle");
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            continue;
+        }
+
         cycle += 1;
 
         println!("\n{}", "═".repeat(70));
         info!("📊 CYCLE #{} - {}", cycle, Utc::now().format("%Y-%m-%d %H:%M:%S"));
         println!("{}", "═".repeat(70));
 
-        // Analyze wallets
+        // Log when the control server's min smart money score differs from the startup value
+        // this loop filters wallets by (the loop keeps filtering by the startup value).
+        if let Some(live_config) = &live_config {
+            let config = live_config.read().await;
+            if config.strategy.min_smart_money_score != min_smart_score {
+                info!(
+                    "⚙️  Min Smart Money Score updated via control server: {:.2} -> {:.2}",
+                    min_smart_score, config.strategy.min_smart_money_score
+                );
+            }
+        }
+
+        // Analyze up to `wallet_analysis_concurrency` wallets at once instead of one at a
+        // time - the RPC client's own rate limiter (see RPC_MAX_RPS) is what actually keeps
+        // the aggregate request rate within the provider's limit, so this pool no longer
+        // needs a fixed sleep between wallets.
         info!("\n🔍 Analyzing {} wallets...", tracked_wallets.len());
 
-        for (idx, wallet) in tracked_wallets.iter().enumerate() {
-            match analyze_wallet(&rpc_client, wallet).await {
-                Ok((analysis, trades)) => {
-                    info!("[{:2}/{}] {} - Score: {:.2} | WR: {:.1}% | Trades: {}",
-                        idx + 1,
-                        tracked_wallets.len(),
-                        &wallet.to_string()[..8],
-                        analysis.smart_money_score,
-                        analysis.metrics.win_rate,
-                        analysis.metrics.total_trades,
-                    );
-
-                    if analysis.smart_money_score >= min_smart_score {
-                        wallet_analyses.insert(*wallet, analysis);
-                        all_trades.insert(*wallet, trades);
+        stream::iter(tracked_wallets.iter().cloned().enumerate())
+            .for_each_concurrent(wallet_analysis_concurrency, |(idx, wallet)| {
+                let rpc_client = &rpc_client;
+                let wallet_analyses = &wallet_analyses;
+                let all_trades = &all_trades;
+                async move {
+                    match analyze_wallet(rpc_client, &wallet).await {
+                        Ok((analysis, trades)) => {
+                            info!("[{:2}/{}] {} - Score: {:.2} | WR: {:.1}% | Trades: {}",
+                                idx + 1,
+                                tracked_wallets.len(),
+                                &wallet.to_string()[..8],
+                                analysis.smart_money_score,
+                                analysis.metrics.win_rate,
+                                analysis.metrics.total_trades,
+                            );
+
+                            if analysis.smart_money_score >= min_smart_score {
+                                wallet_analyses.insert(wallet, analysis);
+                                all_trades.insert(wallet, trades);
+                            }
+                        }
+                        Err(e) => {
+                            warn!("[{:2}/{}] {} - Error: {}",
+                                idx + 1,
+                                tracked_wallets.len(),
+                                &wallet.to_string()[..8],
+                                e
+                            );
+                        }
                     }
                 }
-                Err(e) => {
-                    warn!("[{:2}/{}] {} - Error: {}",
-                        idx + 1,
-                        tracked_wallets.len(),
-                        &wallet.to_string()[..8],
-                        e
-                    );
-                }
-            }
-
-            tokio::time::sleep(Duration::from_secs(2)).await;
-        }
+            })
+            .await;
 
         info!("\n✅ Found {} high-quality wallets", wallet_analyses.len());
 
@@ -269,10 +492,29 @@ async fn main() -> Result<()> {
         if !wallet_analyses.is_empty() {
             info!("\n🎯 Scanning for ULTRA-HIGH confidence signals...");
 
-            let ultra_signals = alpha_detector
-                .find_ultra_high_confidence_signals(&wallet_analyses, &all_trades)
+            // `AlphaAccelerator` wants a plain `HashMap` snapshot - cheap to build once per
+            // cycle here, versus threading `DashMap` through every signal-detection helper.
+            let wallet_analyses_snapshot: HashMap<Pubkey, WalletAnalysis> = wallet_analyses
+                .iter()
+                .map(|entry| (*entry.key(), entry.value().clone()))
+                .collect();
+            let all_trades_snapshot: HashMap<Pubkey, Vec<Trade>> = all_trades
+                .iter()
+                .map(|entry| (*entry.key(), entry.value().clone()))
+                .collect();
+
+            let mut ultra_signals = alpha_detector
+                .find_ultra_high_confidence_signals(&wallet_analyses_snapshot, &all_trades_snapshot)
                 .await;
 
+            let breakout_signals =
+                alpha_detector.find_volume_breakouts(&wallet_analyses_snapshot, &all_trades_snapshot);
+            if !breakout_signals.is_empty() {
+                info!("   📈 Found {} volume breakout signal(s)!", breakout_signals.len());
+            }
+            ultra_signals.extend(breakout_signals);
+            ultra_signals.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap());
+
             if ultra_signals.is_empty() {
                 info!("   No ultra signals this cycle");
             } else {
@@ -289,7 +531,7 @@ async fn main() -> Result<()> {
                     println!("╚════════════════════════════════════════════════════════╝");
 
                     // Notify about ultra signal
-                    telegram.notify_ultra_signal(
+                    notifier.notify_ultra_signal(
                         &signal.token_mint.to_string(),
                         signal.confidence,
                         signal.smart_wallets_count as usize
@@ -301,12 +543,17 @@ async fn main() -> Result<()> {
 
                         match token_fetcher.get_token_data(&signal.token_mint).await {
                             Ok(token) => {
+                                let signal_snapshot = SignalSnapshot {
+                                    slot: rpc_client.get_slot().await.unwrap_or(0),
+                                    price_usd: token.market_data.price_usd,
+                                };
+
                                 // Check security
-                                match scam_detector.check_token_security(&signal.token_mint).await {
+                                match scam_detector.check_token_security(&rpc_client, &signal.token_mint).await {
                                     Ok(security) => {
                                         if security.is_scam {
                                             error!("   ❌ SCAM DETECTED! Skipping.");
-                                            telegram.notify_scam_detected(&signal.token_mint.to_string()).await;
+                                            notifier.notify_scam_detected(&signal.token_mint.to_string()).await;
                                             continue;
                                         }
 
@@ -335,9 +582,53 @@ async fn main() -> Result<()> {
                                                 let combined_confidence = (signal.confidence + chart_signal.confidence) / 2.0;
 
                                                 if combined_confidence > 0.75 {
+                                                    // Re-verify the state that justified this signal hasn't gone stale,
+                                                    // and that taking the position wouldn't breach risk limits.
+                                                    let risk_limits = match &live_config {
+                                                        Some(live_config) => live_config.read().await.risk.clone(),
+                                                        None => RiskLimits::default(),
+                                                    };
+                                                    let guard_result = ExecutionGuard::check_staleness(
+                                                        &rpc_client,
+                                                        &token_fetcher,
+                                                        &token,
+                                                        signal_snapshot,
+                                                        50,
+                                                        3.0,
+                                                    )
+                                                    .await
+                                                    .and_then(|fresh_token| {
+                                                        ExecutionGuard::check_portfolio_health(
+                                                            &portfolio,
+                                                            TradeSide::Buy,
+                                                            &signal.token_mint,
+                                                            Decimal::from_f64_retain(starting_capital).unwrap_or(Decimal::ZERO),
+                                                            &risk_limits,
+                                                        )
+                                                        .map(|_| fresh_token)
+                                                    });
+
+                                                    let token = match guard_result {
+                                                        Ok(fresh_token) => fresh_token,
+                                                        Err(e) => {
+                                                            warn!("   ⛔ Execution guard blocked trade: {}", e);
+                                                            continue;
+                                                        }
+                                                    };
+
                                                     info!("\n   🚀 EXECUTING TRADE (Combined Confidence: {:.0}%)",
                                                         combined_confidence * 100.0);
 
+                                                    // High-confidence convergence signals need to win the landing race, so
+                                                    // reach for p90 instead of the median fee a routine trade would use.
+                                                    let recommended_fee = fee_tracker
+                                                        .recommend(&signal.token_mint, 90)
+                                                        .await;
+                                                    match recommended_fee {
+                                                        Some(fee) => info!("   ⛽ Recommended priority fee: {} micro-lamports/CU", fee),
+                                                        None => info!("   ⛽ No priority fee history yet for this mint, using configured default"),
+                                                    }
+
                                                     if trading_enabled {
                                                         // TODO: Execute real trade
                                                         info!("   [LIVE] Would execute trade here");
@@ -345,26 +636,40 @@ async fn main() -> Result<()> {
                                                         info!("   [PAPER] Simulated buy at ${}", token.market_data.price_usd);
 
                                                         // Track in portfolio
+                                                        let entry_time = Utc::now();
+                                                        let strategy_mode = if let Some(live_config) = &live_config {
+                                                            live_config.read().await.strategy.mode
+                                                        } else {
+                                                            StrategyMode::SwingTrading
+                                                        };
                                                         let position = OpenPosition {
                                                             token_mint: signal.token_mint,
                                                             token_symbol: token.symbol.clone(),
-                                                            entry_time: Utc::now(),
-                                                            entry_price: token.market_data.price_usd,
-                                                            entry_mc: token.market_data.market_cap,
-                                                            amount: Decimal::from_f64_retain(starting_capital).unwrap(),
-                                                            current_price: token.market_data.price_usd,
-                                                            current_mc: token.market_data.market_cap,
-                                                            unrealized_pnl: Decimal::ZERO,
+                                                            entry_time,
+                                                            entry_price: Money::new(token.market_data.price_usd),
+                                                            entry_mc: Money::new(token.market_data.market_cap),
+                                                            amount: Money::new(Decimal::from_f64_retain(starting_capital).unwrap()),
+                                                            current_price: Money::new(token.market_data.price_usd),
+                                                            current_mc: Money::new(token.market_data.market_cap),
+                                                            unrealized_pnl: Money::ZERO,
                                                             unrealized_pnl_pct: 0.0,
-                                                            stop_loss: chart_signal.suggested_entry * Decimal::from_f64_retain(0.9).unwrap(),
-                                                            take_profit: chart_signal.suggested_exit,
+                                                            stop_loss: Money::new(chart_signal.suggested_entry * Decimal::from_f64_retain(0.9).unwrap()),
+                                                            take_profit: Money::new(chart_signal.suggested_exit),
+                                                            peak_price: Money::new(token.market_data.price_usd),
                                                             hold_time_minutes: 0,
+                                                            expires_at: entry_time + exit_params.expiry_window(strategy_mode),
                                                         };
 
                                                         portfolio.open_position(position.clone());
+                                                        order_engine.place_order(signal.token_mint, OrderType::StopLoss { threshold: position.stop_loss });
+                                                        order_engine.place_order(signal.token_mint, OrderType::TakeProfit { threshold: position.take_profit });
+
+                                                        if let Err(e) = portfolio_db.upsert_position(&position) {
+                                                            warn!("Failed to persist opened position: {}", e);
+                                                        }
 
                                                         // Send Telegram notification
-                                                        telegram.notify_position_opened(&position, combined_confidence).await;
+                                                        notifier.notify_position_opened(&position, combined_confidence).await;
                                                     }
                                                 }
                                             }
@@ -384,7 +689,7 @@ async fn main() -> Result<()> {
             }
 
             // Check hot wallets (on winning streak)
-            let hot_wallets = alpha_detector.find_hot_wallets(&wallet_analyses);
+            let hot_wallets = alpha_detector.find_hot_wallets(&wallet_analyses_snapshot);
             if !hot_wallets.is_empty() {
                 info!("\n🔥 {} wallets are HOT (on winning streak)!", hot_wallets.len());
                 for wallet in hot_wallets.iter().take(3) {
@@ -394,14 +699,46 @@ async fn main() -> Result<()> {
         }
 
         // Update and check open positions
-        if let Err(e) = position_manager.check_and_update_positions(&mut portfolio, &token_fetcher).await {
-            warn!("Failed to update positions: {}", e);
+        match position_manager.check_and_update_positions(&mut portfolio, &token_fetcher, &market_feed, &exit_params).await {
+            Ok((warnings, rollovers)) => {
+                for (token_mint, token_symbol, hours_remaining) in warnings {
+                    notifier.notify_time_warning(&token_mint.to_string(), &token_symbol, hours_remaining).await;
+                }
+                for (token_mint, token_symbol, new_expires_at) in rollovers {
+                    notifier.notify_position_rolled_over(&token_mint.to_string(), &token_symbol, new_expires_at).await;
+                }
+            }
+            Err(e) => warn!("Failed to update positions: {}", e),
+        }
+
+        for token_mint in order_engine.tracked_mints() {
+            let Some(market) = market_feed.get(&token_mint) else { continue };
+            for fired in order_engine.evaluate(&token_mint, Money::new(market.price_usd)) {
+                info!("📐 Order fired for {}: {}", token_mint, fired.order_type.label());
+                notifier
+                    .notify_order_fired(&token_mint.to_string(), fired.action.as_str(), &fired.order_type.label(), fired.price.as_decimal())
+                    .await;
+
+                if fired.action == OrderAction::Sell && portfolio.get_position(&token_mint).is_some() {
+                    portfolio.close_position(&token_mint, fired.price.as_decimal(), ExitTrigger::Manual);
+                }
+            }
+        }
+
+        // Persist the refreshed peak_price (and other live fields) for every still-open
+        // position, so a restart's trailing-stop state reflects this cycle's prices.
+        for mint in portfolio.get_position_mints() {
+            if let Some(position) = portfolio.get_position(&mint) {
+                if let Err(e) = portfolio_db.upsert_position(position) {
+                    warn!("Failed to persist position update for {}: {}", mint, e);
+                }
+            }
         }
 
         // Save trade history if there was a new closed trade
         if let Some(closed_trade) = portfolio.get_last_closed_trade() {
             // Send Telegram notification
-            telegram.notify_position_closed(closed_trade).await;
+            notifier.notify_position_closed(closed_trade).await;
 
             trade_history.add_closed_trade(closed_trade);
             trade_history.update_daily_stats(portfolio.get_daily_stats());
@@ -410,6 +747,13 @@ async fn main() -> Result<()> {
                 warn!("Failed to save trade history: {}", e);
             }
 
+            if let Err(e) = portfolio_db.insert_trade(closed_trade) {
+                warn!("Failed to persist closed trade: {}", e);
+            }
+            if let Err(e) = portfolio_db.record_daily_stats(portfolio.get_daily_stats()) {
+                warn!("Failed to persist daily stats: {}", e);
+            }
+
             // Update dashboard
             if let Some(ref state) = dashboard_state {
                 state.update_trade_history(&trade_history).await;
@@ -426,12 +770,147 @@ async fn main() -> Result<()> {
 
         // Send periodic portfolio update (every 10 cycles or ~50 minutes with 5 min interval)
         if cycle % 10 == 0 {
-            telegram.notify_portfolio_update(portfolio.get_daily_stats()).await;
+            notifier.notify_portfolio_update(portfolio.get_daily_stats()).await;
         }
 
-        // Sleep
+        heartbeat.record_cycle().await;
+
+        // Sleep, but keep answering Telegram control commands instead of going deaf for the
+        // whole interval - an operator pausing mid-cycle shouldn't wait up to `analysis_interval`
+        // for a reply.
         info!("\n💤 Next cycle in {} seconds...\n", analysis_interval);
-        tokio::time::sleep(Duration::from_secs(analysis_interval)).await;
+        let sleep = tokio::time::sleep(Duration::from_secs(analysis_interval));
+        tokio::pin!(sleep);
+        loop {
+            tokio::select! {
+                _ = &mut sleep => break,
+                request = recv_control_request(&mut control_rx) => {
+                    if let Some(request) = request {
+                        let reply = handle_control_command(request.command, &mut portfolio, &order_engine, &paused, &notifier).await;
+                        let _ = request.respond_to.send(reply);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Polls `control_rx` if it's wired up (Telegram control enabled), otherwise never resolves.
+/// Clears `control_rx` to `None` once the sender side closes, so a dead dispatcher doesn't
+/// spin the surrounding `select!` loop.
+async fn recv_control_request(control_rx: &mut Option<mpsc::Receiver<ControlRequest>>) -> Option<ControlRequest> {
+    match control_rx {
+        Some(rx) => match rx.recv().await {
+            Some(request) => Some(request),
+            None => {
+                *control_rx = None;
+                std::future::pending().await
+            }
+        },
+        None => std::future::pending().await,
+    }
+}
+
+/// Drains every Telegram control request queued since the last call, replying to each inline.
+/// Called at the top of each cycle; requests arriving during the inter-cycle sleep are
+/// answered as they come via `recv_control_request` in that sleep's `tokio::select!`.
+async fn drain_control_commands(
+    control_rx: &mut Option<mpsc::Receiver<ControlRequest>>,
+    portfolio: &mut PortfolioMonitor,
+    order_engine: &OrderEngine,
+    paused: &std::sync::atomic::AtomicBool,
+    notifier: &std::sync::Arc<dyn Notifier>,
+) {
+    let Some(rx) = control_rx else { return };
+    while let Ok(request) = rx.try_recv() {
+        let reply = handle_control_command(request.command, portfolio, order_engine, paused, notifier).await;
+        let _ = request.respond_to.send(reply);
+    }
+}
+
+/// Handle one Telegram control command against the live engine state, returning the text to
+/// reply with.
+async fn handle_control_command(
+    command: Command,
+    portfolio: &mut PortfolioMonitor,
+    order_engine: &OrderEngine,
+    paused: &std::sync::atomic::AtomicBool,
+    notifier: &std::sync::Arc<dyn Notifier>,
+) -> String {
+    use std::sync::atomic::Ordering;
+
+    match command {
+        Command::Status => {
+            let stats = portfolio.get_daily_stats();
+            format!(
+                "📊 Trades: {} ({} wins, {:.1}% win rate)\n💰 Daily PnL: {}\n💼 Portfolio: {}\n{}",
+                stats.total_trades,
+                stats.wins,
+                stats.win_rate,
+                stats.total_pnl,
+                stats.portfolio_value,
+                if paused.load(Ordering::SeqCst) { "⏸️ New entries paused" } else { "▶️ New entries active" }
+            )
+        }
+        Command::Positions => {
+            let mints = portfolio.get_position_mints();
+            if mints.is_empty() {
+                "No open positions".to_string()
+            } else {
+                mints
+                    .iter()
+                    .filter_map(|mint| portfolio.get_position(mint))
+                    .map(|p| format!("{}: entry ${} / now ${} ({:+.1}%)", p.token_symbol, p.entry_price, p.current_price, p.unrealized_pnl_pct))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        }
+        Command::Close(symbol) => {
+            let target = portfolio
+                .get_position_mints()
+                .into_iter()
+                .find(|mint| portfolio.get_position(mint).is_some_and(|p| p.token_symbol.eq_ignore_ascii_case(symbol.trim())));
+
+            match target {
+                Some(mint) => {
+                    let price = portfolio.get_position(&mint).map(|p| p.current_price.as_decimal()).unwrap_or_default();
+                    order_engine.cancel_all(&mint);
+                    match portfolio.close_position(&mint, price, ExitTrigger::Manual) {
+                        Some(trade) => format!("✅ Closed {} @ ${:.6}", trade.token_symbol, trade.exit_price),
+                        None => format!("⚠️ No open position for {}", symbol),
+                    }
+                }
+                None => format!("⚠️ No open position for {}", symbol),
+            }
+        }
+        Command::Pause => {
+            paused.store(true, Ordering::SeqCst);
+            notifier.notify_maintenance_mode(true, "/pause").await;
+            "⏸️ New entries paused - open positions are still managed".to_string()
+        }
+        Command::Resume => {
+            paused.store(false, Ordering::SeqCst);
+            notifier.notify_maintenance_mode(false, "/resume").await;
+            "▶️ New entries resumed".to_string()
+        }
+        Command::SellAll => {
+            let mints = portfolio.get_position_mints();
+            if mints.is_empty() {
+                return "No open positions to sell".to_string();
+            }
+
+            let mut closed = 0;
+            for mint in mints {
+                let price = portfolio.get_position(&mint).map(|p| p.current_price.as_decimal());
+                if let Some(price) = price {
+                    order_engine.cancel_all(&mint);
+                    if portfolio.close_position(&mint, price, ExitTrigger::Manual).is_some() {
+                        closed += 1;
+                    }
+                }
+            }
+            format!("✅ Sold {} position(s)", closed)
+        }
     }
 }
 
@@ -477,7 +956,7 @@ async fn analyze_wallet(
         return Err(anyhow::anyhow!("No trades found"));
     }
 
-    let analysis = WalletMetricsCalculator::build_wallet_analysis(wallet, &trades)?;
+    let analysis = WalletMetricsCalculator::build_wallet_analysis(wallet, &trades, LotMatchingMode::Lifo)?;
 
     Ok((analysis, trades))
 }
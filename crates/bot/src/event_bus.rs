@@ -0,0 +1,43 @@
+// Unified event stream tying the bot's pipeline stages together: an analysis task publishes
+// `WalletAnalysis` updates, a signal task consumes those and publishes `UltraSignal`s, and an
+// execution task consumes signals and publishes `PortfolioEvent`s as it opens/closes positions.
+// Sink tasks (dashboard, Telegram, persistence) subscribe to this same bus instead of each
+// stage wiring up its own channel, so a new subscriber never has to touch the stages upstream.
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::broadcast;
+use trading_core::WalletAnalysis;
+
+use crate::alpha_accelerator::UltraSignal;
+use crate::portfolio_monitor::PortfolioEvent;
+
+#[derive(Debug, Clone)]
+pub enum BotEvent {
+    WalletAnalysis { wallet: Pubkey, analysis: WalletAnalysis },
+    /// A tracked wallet's smart money score fell below `min_smart_score` on a fresh
+    /// analysis - `run_signal_task` drops it from its rolling `wallet_analyses` view so a
+    /// wallet that only cleared the bar once doesn't keep feeding signal detection forever.
+    WalletEvicted { wallet: Pubkey },
+    Signal(UltraSignal),
+    Portfolio(PortfolioEvent),
+}
+
+pub struct EventBus {
+    sender: broadcast::Sender<BotEvent>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Publish an event to every current subscriber. A send with no subscribers left
+    /// (e.g. every sink has shut down) is not an error - there's simply nothing to notify.
+    pub fn publish(&self, event: BotEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<BotEvent> {
+        self.sender.subscribe()
+    }
+}
@@ -0,0 +1,128 @@
+// Pre-execution guard: makes execution atomic with respect to the state that justified
+// it, mirroring mango-v4's sequence-check / health-check guard instructions.
+
+use rust_decimal::Decimal;
+use tracing::warn;
+use trading_core::{Result, RiskLimits, TradeSide, TradingError};
+use trading_data::{FallbackRpcClient, Token, TokenDataFetcher};
+
+use crate::money::Money;
+use crate::portfolio_monitor::PortfolioMonitor;
+
+/// The market state captured when a signal was generated, re-verified immediately
+/// before execution.
+#[derive(Debug, Clone, Copy)]
+pub struct SignalSnapshot {
+    pub slot: u64,
+    pub price_usd: Decimal,
+}
+
+pub struct ExecutionGuard;
+
+impl ExecutionGuard {
+    /// Re-read the current slot and token price and abort if the state has moved too
+    /// far from what justified the signal.
+    pub async fn check_staleness(
+        rpc: &FallbackRpcClient,
+        token_fetcher: &TokenDataFetcher,
+        token: &Token,
+        snapshot: SignalSnapshot,
+        max_slot_drift: u64,
+        max_price_move_pct: f64,
+    ) -> Result<Token> {
+        let current_slot = rpc.get_slot().await?;
+        let slot_drift = current_slot.saturating_sub(snapshot.slot);
+
+        if slot_drift > max_slot_drift {
+            return Err(TradingError::StaleStateError(format!(
+                "Slot drift {} exceeds max {} since signal was generated",
+                slot_drift, max_slot_drift
+            )));
+        }
+
+        let fresh_token = token_fetcher
+            .get_token_data(&token.mint)
+            .await
+            .map_err(|e| TradingError::StaleStateError(format!("Failed to re-fetch price: {}", e)))?;
+
+        let current_price = fresh_token.market_data.price_usd;
+        if snapshot.price_usd.is_zero() {
+            return Ok(fresh_token);
+        }
+
+        let price_move_pct = ((current_price - snapshot.price_usd) / snapshot.price_usd
+            * Decimal::from(100))
+        .abs()
+        .to_string()
+        .parse::<f64>()
+        .unwrap_or(f64::MAX);
+
+        if price_move_pct > max_price_move_pct {
+            return Err(TradingError::StaleStateError(format!(
+                "Price moved {:.2}% since signal, exceeding max {:.2}%",
+                price_move_pct, max_price_move_pct
+            )));
+        }
+
+        Ok(fresh_token)
+    }
+
+    /// Simulate the intended position size against current open positions and refuse
+    /// the trade if post-trade exposure would exceed configured per-token or total caps.
+    pub fn check_portfolio_health(
+        portfolio: &PortfolioMonitor,
+        side: TradeSide,
+        token_mint: &solana_sdk::pubkey::Pubkey,
+        intended_size_usd: Decimal,
+        risk_limits: &RiskLimits,
+    ) -> Result<()> {
+        if !matches!(side, TradeSide::Buy) {
+            // Exposure only grows on buys; sells always reduce risk.
+            return Ok(());
+        }
+
+        let current_total_exposure: Money = portfolio
+            .positions
+            .values()
+            .map(|p| p.amount)
+            .fold(Money::ZERO, |acc, amount| acc + amount);
+
+        let current_token_exposure = portfolio
+            .positions
+            .get(token_mint)
+            .map(|p| p.amount)
+            .unwrap_or(Money::ZERO);
+
+        let intended_size_usd = Money::new(intended_size_usd);
+        let post_trade_token_exposure = current_token_exposure + intended_size_usd;
+        let post_trade_total_exposure = current_total_exposure + intended_size_usd;
+
+        if post_trade_token_exposure > Money::new(risk_limits.max_position_size_usd) {
+            warn!(
+                "Refusing trade: post-trade token exposure ${} exceeds cap ${}",
+                post_trade_token_exposure, risk_limits.max_position_size_usd
+            );
+            return Err(TradingError::RiskLimitExceeded(format!(
+                "Post-trade exposure for {} would be ${}, exceeding per-token cap ${}",
+                token_mint, post_trade_token_exposure, risk_limits.max_position_size_usd
+            )));
+        }
+
+        // Total exposure across all open positions should never exceed the portfolio's
+        // own value - i.e. we shouldn't be more than fully deployed.
+        let max_total_exposure = portfolio.get_daily_stats().portfolio_value;
+
+        if post_trade_total_exposure > max_total_exposure && max_total_exposure > Money::ZERO {
+            warn!(
+                "Refusing trade: post-trade total exposure ${} exceeds cap ${}",
+                post_trade_total_exposure, max_total_exposure
+            );
+            return Err(TradingError::RiskLimitExceeded(format!(
+                "Post-trade total exposure would be ${}, exceeding cap ${}",
+                post_trade_total_exposure, max_total_exposure
+            )));
+        }
+
+        Ok(())
+    }
+}
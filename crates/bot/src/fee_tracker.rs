@@ -0,0 +1,108 @@
+// Per-token priority-fee history: tracks what compute-unit price recently landed
+// transactions that touch a given mint actually paid, so a follow-up buy on an
+// `UltraSignal` can pick a price that's competitive with the current landing race
+// instead of guessing. This mirrors the per-account fee-statistics approach Solana's
+// banking stage uses to decide which transactions to prioritize, but applied locally
+// against our own execution history rather than sampled from `getRecentPrioritizationFees`.
+//
+// Write-locked vs read-only accounts are not tracked separately here: every sample this
+// tracker sees comes from our own buy/sell transactions, which always write-lock the
+// mint's associated token account, so the write-lock fee pressure is the only class we
+// actually observe. A future read-heavy consumer (e.g. a quote-only probe) would need
+// its own key space rather than sharing this one.
+use std::collections::HashMap;
+
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::RwLock;
+
+/// Samples kept per mint before the oldest are evicted - bounds memory while still
+/// giving percentiles a reasonable window to work with.
+const MAX_SAMPLES_PER_MINT: usize = 200;
+
+/// Sorted-sample percentile summary for a single mint's fee history.
+#[derive(Debug, Clone, Copy)]
+pub struct FeeStats {
+    pub min: u64,
+    pub max: u64,
+    pub median: u64,
+    pub p75: u64,
+    pub p90: u64,
+    pub p95: u64,
+    pub samples: usize,
+}
+
+/// Rolling per-mint history of observed `micro-lamports-per-CU` prices, keyed by
+/// `token_mint`.
+pub struct PriorityFeeTracker {
+    samples: RwLock<HashMap<Pubkey, Vec<u64>>>,
+}
+
+impl PriorityFeeTracker {
+    pub fn new() -> Self {
+        Self {
+            samples: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Record the compute-unit price a landed transaction touching `token_mint` paid.
+    pub async fn record(&self, token_mint: Pubkey, micro_lamports_per_cu: u64) {
+        let mut samples = self.samples.write().await;
+        let history = samples.entry(token_mint).or_default();
+        history.push(micro_lamports_per_cu);
+
+        if history.len() > MAX_SAMPLES_PER_MINT {
+            let excess = history.len() - MAX_SAMPLES_PER_MINT;
+            history.drain(0..excess);
+        }
+    }
+
+    /// Percentile summary for `token_mint`, or `None` with fewer than two samples.
+    pub async fn stats(&self, token_mint: &Pubkey) -> Option<FeeStats> {
+        let samples = self.samples.read().await;
+        let history = samples.get(token_mint)?;
+
+        if history.len() < 2 {
+            return None;
+        }
+
+        let mut sorted = history.clone();
+        sorted.sort_unstable();
+        let len = sorted.len();
+
+        Some(FeeStats {
+            min: sorted[0],
+            max: sorted[len - 1],
+            median: sorted[len / 2],
+            p75: sorted[len * 75 / 100],
+            p90: sorted[len * 90 / 100],
+            p95: sorted[len * 95 / 100],
+            samples: len,
+        })
+    }
+
+    /// Recommended compute-unit price for `token_mint` at the given percentile (0-100),
+    /// e.g. `recommend(mint, 90)` for a high-confidence convergence signal that needs to
+    /// win the landing race, or `recommend(mint, 50)` for a routine trade. `None` when
+    /// there isn't enough history yet for `token_mint`.
+    pub async fn recommend(&self, token_mint: &Pubkey, percentile: u8) -> Option<u64> {
+        let samples = self.samples.read().await;
+        let history = samples.get(token_mint)?;
+
+        if history.len() < 2 {
+            return None;
+        }
+
+        let mut sorted = history.clone();
+        sorted.sort_unstable();
+        let percentile = percentile.min(100) as usize;
+        let index = (sorted.len().saturating_sub(1) * percentile) / 100;
+
+        Some(sorted[index])
+    }
+}
+
+impl Default for PriorityFeeTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,249 @@
+// Interactive REPL control mode: keeps a single RPC client and DB pool alive across
+// operator commands instead of restarting the process to change what's tracked.
+
+use solana_sdk::pubkey::Pubkey;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+use trading_core::Result;
+use trading_data::FallbackRpcClient;
+use trading_db::Database;
+
+use crate::analyze_wallet;
+
+/// Runs the background analysis loop and the operator prompt side by side until `quit`.
+pub async fn run(
+    rpc_client: Arc<FallbackRpcClient>,
+    db: Arc<Database>,
+    analysis_interval: u64,
+) -> Result<()> {
+    let tracked_wallets: Arc<Mutex<Vec<Pubkey>>> =
+        Arc::new(Mutex::new(db.wallets().get_tracked_wallets().await.unwrap_or_default()));
+    let paused = Arc::new(AtomicBool::new(false));
+
+    let scan_handle = spawn_background_scan(
+        rpc_client.clone(),
+        db.clone(),
+        tracked_wallets.clone(),
+        paused.clone(),
+        analysis_interval,
+    );
+
+    println!("\n🧭 Interactive mode. Type `help` for commands.\n");
+
+    loop {
+        print!("haku> ");
+        io::stdout().flush().ok();
+
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line).unwrap_or(0) == 0 {
+            break; // EOF (e.g. piped input or closed terminal)
+        }
+
+        let mut parts = line.trim().split_whitespace();
+        let Some(command) = parts.next() else { continue };
+        let args: Vec<&str> = parts.collect();
+
+        match command {
+            "help" => print_help(),
+            "add-wallet" => add_wallet(&db, &tracked_wallets, &args).await,
+            "remove-wallet" => remove_wallet(&db, &tracked_wallets, &args).await,
+            "list" => list_wallets(&tracked_wallets).await,
+            "analyze" => analyze_one(&rpc_client, &db, &args).await,
+            "signals" => show_signals(&db, &args).await,
+            "stats" => show_stats(&db, &args).await,
+            "pause" => {
+                paused.store(true, Ordering::SeqCst);
+                println!("⏸  Background scan paused");
+            }
+            "resume" => {
+                paused.store(false, Ordering::SeqCst);
+                println!("▶️  Background scan resumed");
+            }
+            "quit" | "exit" => {
+                println!("👋 Shutting down...");
+                break;
+            }
+            "" => {}
+            other => println!("Unknown command '{}'. Type `help` for the command list.", other),
+        }
+    }
+
+    scan_handle.abort();
+    Ok(())
+}
+
+fn print_help() {
+    println!(
+        "Commands:\n\
+         \u{20}  add-wallet <pubkey>   Track a new wallet\n\
+         \u{20}  remove-wallet <pubkey> Stop tracking a wallet\n\
+         \u{20}  list                  List tracked wallets\n\
+         \u{20}  analyze <pubkey>      Run analysis on demand\n\
+         \u{20}  signals [n]           Show the n most recent signals (default 10)\n\
+         \u{20}  stats [days]          Show daily stats for the last n days (default 7)\n\
+         \u{20}  pause / resume        Pause or resume the background scan loop\n\
+         \u{20}  quit                  Exit interactive mode"
+    );
+}
+
+async fn add_wallet(db: &Database, tracked: &Mutex<Vec<Pubkey>>, args: &[&str]) {
+    let Some(address) = args.first() else {
+        println!("Usage: add-wallet <pubkey>");
+        return;
+    };
+
+    match Pubkey::from_str(address) {
+        Ok(pubkey) => {
+            let now = chrono::Utc::now();
+            if let Err(e) = db
+                .wallets()
+                .save_wallet(&pubkey, None, 0.0, 0.0, true, now, now)
+                .await
+            {
+                error!("Failed to save wallet: {}", e);
+                return;
+            }
+            tracked.lock().await.push(pubkey);
+            println!("✅ Now tracking {}", pubkey);
+        }
+        Err(e) => println!("Invalid pubkey '{}': {}", address, e),
+    }
+}
+
+async fn remove_wallet(db: &Database, tracked: &Mutex<Vec<Pubkey>>, args: &[&str]) {
+    let Some(address) = args.first() else {
+        println!("Usage: remove-wallet <pubkey>");
+        return;
+    };
+
+    match Pubkey::from_str(address) {
+        Ok(pubkey) => {
+            let now = chrono::Utc::now();
+            if let Err(e) = db
+                .wallets()
+                .save_wallet(&pubkey, None, 0.0, 0.0, false, now, now)
+                .await
+            {
+                error!("Failed to update wallet: {}", e);
+                return;
+            }
+            tracked.lock().await.retain(|w| w != &pubkey);
+            println!("🗑  Stopped tracking {}", pubkey);
+        }
+        Err(e) => println!("Invalid pubkey '{}': {}", address, e),
+    }
+}
+
+async fn list_wallets(tracked: &Mutex<Vec<Pubkey>>) {
+    let wallets = tracked.lock().await;
+    if wallets.is_empty() {
+        println!("No wallets tracked");
+        return;
+    }
+    for wallet in wallets.iter() {
+        println!("  {}", wallet);
+    }
+}
+
+async fn analyze_one(rpc_client: &FallbackRpcClient, db: &Database, args: &[&str]) {
+    let Some(address) = args.first() else {
+        println!("Usage: analyze <pubkey>");
+        return;
+    };
+
+    let pubkey = match Pubkey::from_str(address) {
+        Ok(pubkey) => pubkey,
+        Err(e) => {
+            println!("Invalid pubkey '{}': {}", address, e);
+            return;
+        }
+    };
+
+    match analyze_wallet(rpc_client, db, &pubkey, None).await {
+        Ok(analysis) => println!("{:#?}", analysis),
+        Err(e) => println!("❌ Analysis failed: {}", e),
+    }
+}
+
+async fn show_signals(db: &Database, args: &[&str]) {
+    let limit = args
+        .first()
+        .and_then(|s| s.parse::<i64>().ok())
+        .unwrap_or(10);
+
+    match db.statistics().get_recent_signals(limit).await {
+        Ok(signals) if signals.is_empty() => println!("No signals recorded yet"),
+        Ok(signals) => {
+            for signal in signals {
+                println!(
+                    "  [{}] {} {} conf={:.2} wallets={} vol={}",
+                    signal.detected_at.format("%Y-%m-%d %H:%M:%S"),
+                    signal.token_mint,
+                    signal.signal_type,
+                    signal.confidence,
+                    signal.smart_wallets_count,
+                    signal.total_volume,
+                );
+            }
+        }
+        Err(e) => println!("❌ Failed to load signals: {}", e),
+    }
+}
+
+async fn show_stats(db: &Database, args: &[&str]) {
+    let days = args.first().and_then(|s| s.parse::<i64>().ok()).unwrap_or(7);
+    let end = chrono::Utc::now().date_naive();
+    let start = end - chrono::Duration::days(days);
+
+    match db.statistics().get_stats_range(start, end).await {
+        Ok(stats) if stats.is_empty() => println!("No stats recorded in the last {} days", days),
+        Ok(stats) => {
+            for day in stats {
+                println!(
+                    "  {} trades={} win_rate={:.1}% pnl={}",
+                    day.date, day.total_trades, day.win_rate, day.total_pnl
+                );
+            }
+        }
+        Err(e) => println!("❌ Failed to load stats: {}", e),
+    }
+}
+
+fn spawn_background_scan(
+    rpc_client: Arc<FallbackRpcClient>,
+    db: Arc<Database>,
+    tracked_wallets: Arc<Mutex<Vec<Pubkey>>>,
+    paused: Arc<AtomicBool>,
+    analysis_interval: u64,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(analysis_interval)).await;
+
+            if paused.load(Ordering::SeqCst) {
+                continue;
+            }
+
+            let wallets = tracked_wallets.lock().await.clone();
+            if wallets.is_empty() {
+                continue;
+            }
+
+            info!("🔄 Background scan of {} tracked wallets", wallets.len());
+            for wallet in &wallets {
+                match analyze_wallet(&rpc_client, &db, wallet, None).await {
+                    Ok(analysis) => info!(
+                        "   {} score={:.2} win_rate={:.1}%",
+                        wallet, analysis.smart_money_score, analysis.metrics.win_rate
+                    ),
+                    Err(e) => warn!("   {} analysis failed: {}", wallet, e),
+                }
+            }
+        }
+    })
+}
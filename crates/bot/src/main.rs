@@ -1,17 +1,56 @@
 use anyhow::Result;
+use futures_util::stream::{self, StreamExt};
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio;
+use tokio::sync::{broadcast, mpsc};
 use tracing::{info, error, warn};
 use solana_sdk::pubkey::Pubkey;
+use rust_decimal::Decimal;
 
 use trading_core::*;
 use trading_data::*;
 use trading_analysis::*;
+use trading_db::Database;
+
+mod interactive;
+mod stream_server;
+mod trade_log_binary;
+mod event_bus;
+mod alpha_accelerator;
+mod money;
+mod candle_store;
+mod portfolio_monitor;
+mod position_manager;
+mod market_feed;
+mod persistence;
+mod telegram;
+mod dashboard;
+mod notifier;
+mod order_engine;
+mod telegram_control;
+
+use stream_server::{StreamEvent, StreamServer};
+use event_bus::{BotEvent, EventBus};
+use alpha_accelerator::{AlphaAccelerator, UltraSignal};
+use portfolio_monitor::{run_console_event_logger, ExitTrigger, OpenPosition, PortfolioMonitor};
+use position_manager::{ExitParams, PositionManager};
+use market_feed::MarketFeed;
+use money::Money;
+use persistence::TradeHistory;
+use telegram::TelegramNotifier;
+use dashboard::DashboardServer;
+use notifier::{CompositeNotifier, DeduplicatingNotifier, DiscordNotifier, HeartbeatMonitor, Notifier, WebhookSink};
+use order_engine::{OrderAction, OrderEngine, OrderType};
+use telegram_control::{Command, ControlRequest, TelegramControl};
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let interactive_mode = std::env::args().any(|arg| arg == "--interactive");
+
     // Initialize logging
     tracing_subscriber::fmt()
         .with_env_filter(
@@ -50,11 +89,27 @@ async fn main() -> Result<()> {
             .unwrap_or_else(|_| "https://rpc.ankr.com/solana".to_string()),
     ];
 
+    let ws_url = std::env::var("SOLANA_WS_URL")
+        .unwrap_or_else(|_| rpc_url.replacen("https://", "wss://", 1));
+    let fallback_ws_urls = vec![
+        std::env::var("SOLANA_FALLBACK_WS_1")
+            .unwrap_or_else(|_| fallback_rpcs[0].replacen("https://", "wss://", 1)),
+        std::env::var("SOLANA_FALLBACK_WS_2")
+            .unwrap_or_else(|_| fallback_rpcs[1].replacen("https://", "wss://", 1)),
+    ];
+
+    let rpc_max_rps = std::env::var("RPC_MAX_RPS")
+        .unwrap_or_else(|_| "10".to_string())
+        .parse::<usize>()
+        .unwrap_or(10);
+
     let rpc_client = FallbackRpcClient::new(
         rpc_url.clone(),
         fallback_rpcs,
         solana_sdk::commitment_config::CommitmentConfig::confirmed(),
-    );
+    )
+    .with_ws_urls(std::iter::once(ws_url).chain(fallback_ws_urls).collect())
+    .with_rate_limit(rpc_max_rps);
 
     // Test RPC connection
     match rpc_client.get_slot().await {
@@ -65,14 +120,57 @@ async fn main() -> Result<()> {
         }
     }
 
+    // Shared via Arc: every pipeline task below needs its own handle to the RPC client
+    // and database.
+    let rpc_client = Arc::new(rpc_client);
+
+    // Database (used here to persist per-wallet backfill cursors for incremental history pulls)
+    let database_url = std::env::var("DATABASE_URL")
+        .unwrap_or_else(|_| "sqlite://trading_bot.db".to_string());
+    let db = Arc::new(Database::new(&database_url).await?);
+
+    if interactive_mode {
+        let analysis_interval = std::env::var("WALLET_ANALYSIS_INTERVAL")
+            .unwrap_or_else(|_| "600".to_string())
+            .parse::<u64>()
+            .unwrap_or(600);
+
+        return interactive::run(rpc_client, db, analysis_interval)
+            .await
+            .map_err(Into::into);
+    }
+
+    // Optional WebSocket stream server: pushes new trades (and, once produced, copy-trade
+    // signals / portfolio positions) to connected clients instead of them polling SQLite.
+    let stream_enabled = std::env::var("STREAM_SERVER_ENABLED")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false);
+
+    let stream_events = if stream_enabled {
+        let stream_port = std::env::var("STREAM_SERVER_PORT")
+            .unwrap_or_else(|_| "3001".to_string())
+            .parse::<u16>()
+            .unwrap_or(3001);
+
+        let server = StreamServer::new(stream_port, Some(db.trades()));
+        let sender = server.sender();
+        tokio::spawn(server.start());
+
+        info!("🔌 Stream server enabled at ws://localhost:{}/stream", stream_port);
+        Some(sender)
+    } else {
+        None
+    };
+
     // Initialize data fetchers
     let dexscreener_url = std::env::var("DEXSCREENER_API_URL")
         .unwrap_or_else(|_| "https://api.dexscreener.com/latest".to_string());
-    let token_fetcher = TokenDataFetcher::new(dexscreener_url);
+    let token_fetcher = Arc::new(TokenDataFetcher::new(dexscreener_url));
 
     let rugcheck_url = std::env::var("RUGCHECK_API_URL")
         .unwrap_or_else(|_| "https://api.rugcheck.xyz/v1".to_string());
-    let scam_detector = ScamDetector::new(rugcheck_url);
+    let scam_detector = Arc::new(ScamDetector::new(rugcheck_url));
 
     // Load tracked wallets
     info!("📂 Loading tracked wallets...");
@@ -98,123 +196,788 @@ async fn main() -> Result<()> {
         .parse::<u64>()
         .unwrap_or(600);
 
+    let wallet_analysis_concurrency = std::env::var("WALLET_ANALYSIS_CONCURRENCY")
+        .unwrap_or_else(|_| "5".to_string())
+        .parse::<usize>()
+        .unwrap_or(5);
+
+    let position_size = std::env::var("MAX_POSITION_SIZE_USD")
+        .unwrap_or_else(|_| "10".to_string())
+        .parse::<f64>()
+        .ok()
+        .and_then(Decimal::from_f64_retain)
+        .unwrap_or(Decimal::from(10));
+
     info!("⚙️  Configuration:");
     info!("   Min Smart Money Score: {:.2}", min_smart_score);
     info!("   Analysis Interval: {}s", analysis_interval);
+    info!("   Max Position Size: ${}", position_size);
+    info!("   Wallet Analysis Concurrency: {}", wallet_analysis_concurrency);
+    info!("   RPC Max Requests/sec: {}", rpc_max_rps);
 
-    // Main bot loop
-    info!("\n🚀 Starting main loop...\n");
+    // Unified event bus: the analysis tasks below publish `WalletAnalysis` updates, the
+    // signal task turns those into `UltraSignal`s, the execution task turns signals into
+    // managed positions, and every sink (console, dashboard, Telegram) just subscribes -
+    // none of them need to know the pipeline stages upstream exist.
+    let event_bus = Arc::new(EventBus::new(512));
 
-    let mut cycle = 0;
-    loop {
-        cycle += 1;
-        info!("🔄 Analysis Cycle #{}", cycle);
-        info!("====================");
-
-        let mut wallet_analyses = HashMap::new();
-
-        // Analyze each tracked wallet
-        for (idx, wallet) in tracked_wallets.iter().enumerate() {
-            info!("[{}/{}] Analyzing {}...", idx + 1, tracked_wallets.len(), wallet);
-
-            match analyze_wallet(&rpc_client, wallet).await {
-                Ok(analysis) => {
-                    info!(
-                        "   ✅ Score: {:.2} | Win Rate: {:.1}% | Trades: {}",
-                        analysis.smart_money_score,
-                        analysis.metrics.win_rate,
-                        analysis.metrics.total_trades,
-                    );
-
-                    if analysis.smart_money_score >= min_smart_score {
-                        wallet_analyses.insert(*wallet, analysis);
-                    } else {
-                        warn!("   ⚠️  Score too low, skipping");
+    // Shutdown signal: each long-running task holds a receiver and breaks its loop once
+    // `shutdown_tx` fires, so SIGINT lets the execution task flush `TradeHistory` instead
+    // of the process dying mid-write.
+    let (shutdown_tx, _) = broadcast::channel::<()>(1);
+
+    // Tracks whether the reconciliation cycle below is still completing on schedule - wired
+    // up to alert through `notifier` once Telegram/webhook/Discord sinks are configured below.
+    let heartbeat = HeartbeatMonitor::new();
+
+    // Real-time path: stream confirmed trades for the tracked wallets via logsSubscribe
+    // (falling back to polling internally if no WS endpoint is reachable) and publish the
+    // touched wallet's refreshed analysis as each trade confirms, instead of waiting on the
+    // next full `analysis_interval` sweep below.
+    {
+        let mut monitor = WalletStreamMonitor::start(rpc_client.clone(), tracked_wallets.clone()).await;
+        info!("📡 Streaming real-time trades for {} wallets", tracked_wallets.len());
+
+        let db = db.clone();
+        let rpc_client = rpc_client.clone();
+        let stream_events = stream_events.clone();
+        let event_bus = event_bus.clone();
+        let mut shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    trade = monitor.recv() => {
+                        let Some(trade) = trade else {
+                            warn!("Real-time trade stream ended");
+                            break;
+                        };
+
+                        match analyze_wallet(&rpc_client, &db, &trade.wallet, stream_events.as_ref()).await {
+                            Ok(analysis) => {
+                                info!(
+                                    "⚡ {} traded {} | Score: {:.2} | Win Rate: {:.1}%",
+                                    trade.wallet, trade.token_mint, analysis.smart_money_score, analysis.metrics.win_rate,
+                                );
+
+                                if analysis.smart_money_score >= min_smart_score {
+                                    event_bus.publish(BotEvent::WalletAnalysis { wallet: trade.wallet, analysis });
+                                } else {
+                                    event_bus.publish(BotEvent::WalletEvicted { wallet: trade.wallet });
+                                }
+                            }
+                            Err(e) => warn!("⚠️  Failed to analyze {} after streamed trade: {}", trade.wallet, e),
+                        }
+                    }
+                    _ = shutdown_rx.recv() => break,
+                }
+            }
+        });
+    }
+
+    // Periodic reconciliation task: a full re-scan of every tracked wallet, kept as the
+    // fallback/backstop path for wallets the real-time stream above missed (a missed
+    // `logsSubscribe` notification, a gap during reconnect) rather than the bot's primary path.
+    {
+        let rpc_client = rpc_client.clone();
+        let db = db.clone();
+        let stream_events = stream_events.clone();
+        let event_bus = event_bus.clone();
+        let tracked_wallets = tracked_wallets.clone();
+        let heartbeat = heartbeat.clone();
+        let mut shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            let mut cycle = 0;
+            loop {
+                cycle += 1;
+                info!("🔄 Reconciliation Cycle #{}", cycle);
+                info!("====================");
+
+                // Analyze up to `wallet_analysis_concurrency` wallets at once instead of one
+                // at a time - the RPC client's own rate limiter (see RPC_MAX_RPS) is what
+                // actually keeps the aggregate request rate within the provider's limit, so
+                // this pool no longer needs a fixed sleep between wallets.
+                let results: Vec<(Pubkey, Result<WalletAnalysis>)> = stream::iter(tracked_wallets.iter().cloned())
+                    .map(|wallet| {
+                        let rpc_client = &rpc_client;
+                        let db = &db;
+                        let stream_events = stream_events.as_ref();
+                        async move {
+                            let result = analyze_wallet(rpc_client, db, &wallet, stream_events).await;
+                            (wallet, result)
+                        }
+                    })
+                    .buffer_unordered(wallet_analysis_concurrency)
+                    .collect()
+                    .await;
+
+                for (idx, (wallet, result)) in results.into_iter().enumerate() {
+                    info!("[{}/{}] {}", idx + 1, tracked_wallets.len(), wallet);
+
+                    match result {
+                        Ok(analysis) => {
+                            info!(
+                                "   ✅ Score: {:.2} | Win Rate: {:.1}% | Trades: {}",
+                                analysis.smart_money_score,
+                                analysis.metrics.win_rate,
+                                analysis.metrics.total_trades,
+                            );
+
+                            if analysis.smart_money_score >= min_smart_score {
+                                event_bus.publish(BotEvent::WalletAnalysis { wallet, analysis });
+                            } else {
+                                warn!("   ⚠️  Score too low, skipping");
+                                event_bus.publish(BotEvent::WalletEvicted { wallet });
+                            }
+                        }
+                        Err(e) => error!("   ❌ Error: {}", e),
                     }
                 }
-                Err(e) => {
-                    error!("   ❌ Error: {}", e);
+
+                heartbeat.record_cycle().await;
+
+                info!("\n💤 Sleeping for {} seconds...\n", analysis_interval);
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_secs(analysis_interval)) => {}
+                    _ = shutdown_rx.recv() => break,
                 }
             }
+        });
+    }
 
-            // Rate limiting (free tier friendly)
-            tokio::time::sleep(Duration::from_secs(3)).await;
-        }
+    // Signal task: folds every `WalletAnalysis` update into a rolling view of elite wallets
+    // and their recent trades, and runs `AlphaAccelerator` over that view to publish
+    // `UltraSignal`s - the same detector `enhanced_main`'s monolithic loop calls inline.
+    {
+        let db = db.clone();
+        let event_bus = event_bus.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(run_signal_task(db, event_bus, shutdown_rx));
+    }
+
+    // Portfolio state lives in the execution task below; console logging and the optional
+    // dashboard/Telegram sinks each mint their own receiver off it before it's moved in.
+    let portfolio = PortfolioMonitor::new(position_size);
+    tokio::spawn(run_console_event_logger(portfolio.subscribe()));
+
+    // Forward every `PortfolioEvent` onto the unified bus too, so Telegram and any future
+    // subscriber only need to know about `BotEvent`, not reach into the execution task.
+    {
+        let mut portfolio_events = portfolio.subscribe();
+        let event_bus = event_bus.clone();
+        let mut shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    event = portfolio_events.recv() => {
+                        match event {
+                            Ok(event) => event_bus.publish(BotEvent::Portfolio(event)),
+                            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                    _ = shutdown_rx.recv() => break,
+                }
+            }
+        });
+    }
+
+    // Optional web dashboard: same SSE portfolio feed and candle chart API `enhanced_main`
+    // serves, wired to this bot's own `PortfolioMonitor`/candle store.
+    let dashboard_enabled = std::env::var("DASHBOARD_ENABLED")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false);
 
-        info!("\n✨ Found {} high-quality wallets", wallet_analyses.len());
+    let history_path = std::path::Path::new("trade_history.json");
+    let trade_history = TradeHistory::load(history_path)
+        .unwrap_or_else(|_| TradeHistory::new(position_size));
 
-        // Look for alpha signals
-        if !wallet_analyses.is_empty() {
-            info!("\n🔍 Scanning for alpha signals...");
+    if dashboard_enabled {
+        let dashboard_port = std::env::var("DASHBOARD_PORT")
+            .unwrap_or_else(|_| "3000".to_string())
+            .parse::<u16>()
+            .unwrap_or(3000);
 
-            let signals = detect_alpha_signals(&wallet_analyses).await;
+        let dashboard = DashboardServer::with_state(
+            dashboard_port,
+            trade_history.clone(),
+            Some(db.candles()),
+            Some(portfolio.events_sender()),
+        );
+        tokio::spawn(async move { dashboard.start().await });
 
-            if signals.is_empty() {
-                info!("   No signals detected this cycle");
-            } else {
-                info!("   🎯 Found {} signals!", signals.len());
+        info!("🌐 Web dashboard enabled at http://localhost:{}", dashboard_port);
+    }
+
+    // Optional Telegram sink, subscribed to the same unified bus as every other sink.
+    let telegram_enabled = std::env::var("TELEGRAM_ENABLED")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false);
+
+    // Shared with `handle_ultra_signal`: flipped by the /pause and /resume Telegram commands
+    // (or set at startup below), independent of `trading_enabled` (which is paper-vs-live,
+    // not accepting-vs-not). While set, the engine keeps managing open positions but stops
+    // opening new ones - a resume-only mode for draining the book during RPC instability or
+    // before a planned shutdown.
+    let maintenance_mode = std::env::var("MAINTENANCE_MODE_ENABLED")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false);
+    if maintenance_mode {
+        warn!("🛠️  Starting in maintenance mode - new entries are blocked until /resume");
+    }
+    let entries_paused = Arc::new(AtomicBool::new(maintenance_mode));
+
+    let (telegram, control_rx) = if telegram_enabled {
+        let token = std::env::var("TELEGRAM_BOT_TOKEN")
+            .expect("TELEGRAM_BOT_TOKEN must be set when TELEGRAM_ENABLED=true");
+        let chat_id = std::env::var("TELEGRAM_CHAT_ID")
+            .expect("TELEGRAM_CHAT_ID must be set when TELEGRAM_ENABLED=true")
+            .parse::<i64>()
+            .expect("TELEGRAM_CHAT_ID must be a valid integer");
+
+        info!("📱 Telegram notifications ENABLED");
+
+        let (control, control_rx) = TelegramControl::new(token.clone(), chat_id);
+        tokio::spawn(control.run());
+        info!("📱 Telegram control commands ENABLED (/status /positions /close /pause /resume /sell_all)");
+
+        (Some(TelegramNotifier::new(token, chat_id)), Some(control_rx))
+    } else {
+        info!("📱 Telegram notifications DISABLED");
+        (None, None)
+    };
+
+    // Fan notifications out to every configured sink - Telegram (if enabled) plus whichever
+    // of the generic-webhook/Discord backends have a URL set - then wrap the lot so a signal
+    // that keeps re-triggering cycle after cycle doesn't spam an operator's phone.
+    let mut sinks: Vec<Box<dyn Notifier>> = vec![];
+    if let Some(telegram) = telegram {
+        sinks.push(Box::new(telegram));
+    }
+
+    if let Ok(webhook_url) = std::env::var("NOTIFIER_WEBHOOK_URL") {
+        info!("🔗 Generic webhook notifications ENABLED");
+        sinks.push(Box::new(WebhookSink::new(webhook_url)));
+    }
+
+    if let Ok(discord_url) = std::env::var("NOTIFIER_DISCORD_WEBHOOK_URL") {
+        info!("💬 Discord notifications ENABLED");
+        sinks.push(Box::new(DiscordNotifier::new(discord_url)));
+    }
+
+    let notifier_dedup_cooldown_secs = std::env::var("NOTIFIER_DEDUP_COOLDOWN_SECS")
+        .unwrap_or_else(|_| "900".to_string())
+        .parse::<u64>()
+        .unwrap_or(900);
+
+    let notifier = Arc::new(DeduplicatingNotifier::new(
+        CompositeNotifier::new(sinks),
+        Duration::from_secs(notifier_dedup_cooldown_secs),
+    ));
+    info!("   Notifier Dedup Cooldown: {}s", notifier_dedup_cooldown_secs);
+
+    if telegram_enabled {
+        notifier.notify_bot_started(position_size).await;
+    }
+    if maintenance_mode {
+        notifier.notify_maintenance_mode(true, "startup flag").await;
+    }
+
+    let heartbeat_threshold_secs = std::env::var("HEARTBEAT_THRESHOLD_SECS")
+        .unwrap_or_else(|_| "1800".to_string())
+        .parse::<u64>()
+        .unwrap_or(1800);
+    info!("   Heartbeat Threshold: {}s", heartbeat_threshold_secs);
+
+    heartbeat.run(notifier.clone(), Duration::from_secs(heartbeat_threshold_secs), Duration::from_secs(60));
+
+    let exit_params = ExitParams {
+        hard_max_hold_hours: std::env::var("HARD_MAX_HOLD_HOURS")
+            .unwrap_or_else(|_| "48".to_string())
+            .parse::<i64>()
+            .unwrap_or(48),
+        rollover_hour_utc: std::env::var("ROLLOVER_HOUR_UTC").ok().and_then(|v| v.parse::<u32>().ok()),
+        warning_threshold_hours: std::env::var("WARNING_THRESHOLD_HOURS")
+            .unwrap_or_else(|_| "4".to_string())
+            .parse::<i64>()
+            .unwrap_or(4),
+        ..ExitParams::default()
+    };
+    info!("   Hard Max Hold Hours: {}", exit_params.hard_max_hold_hours);
+    info!("   Rollover Hour (UTC): {:?}", exit_params.rollover_hour_utc);
+    info!("   Warning Threshold Hours: {}", exit_params.warning_threshold_hours);
+
+    {
+        let notifier = notifier.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(run_notifier_sink(notifier, event_bus.subscribe(), shutdown_rx));
+    }
+
+    // Live price feed: subscribes/unsubscribes automatically off the portfolio event bus,
+    // so position checks read a pushed price instead of polling DexScreener every tick.
+    let market_feed_url = std::env::var("MARKET_FEED_WS_URL")
+        .unwrap_or_else(|_| "wss://feed.example.com/market".to_string());
+    let market_feed = Arc::new(MarketFeed::new(market_feed_url));
+    market_feed.run();
+    market_feed.run_subscription_manager(portfolio.subscribe());
+
+    // Bracket-order book: a genuine stop-loss/take-profit pair per open position,
+    // enforced on every position-check tick instead of the one-shot `suggested_entry`/
+    // `suggested_exit` a `ChartSignal` hands back and never revisits.
+    let order_engine = Arc::new(OrderEngine::new());
+
+    // Execution task: the only task that owns `portfolio`. Consumes `UltraSignal`s to open
+    // new positions, and on its own tick consumes `PositionManager::check_and_update_positions`
+    // to close them - so position monitoring runs at its own cadence instead of being gated
+    // behind the slower wallet-analysis cycle above.
+    {
+        let event_bus = event_bus.clone();
+        let rpc_client = rpc_client.clone();
+        let scam_detector = scam_detector.clone();
+        let token_fetcher = token_fetcher.clone();
+        let notifier = notifier.clone();
+        let order_engine = order_engine.clone();
+        let entries_paused = entries_paused.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(run_execution_task(
+            event_bus,
+            portfolio,
+            rpc_client,
+            scam_detector,
+            token_fetcher,
+            market_feed,
+            trading_enabled,
+            position_size,
+            trade_history,
+            history_path.to_path_buf(),
+            notifier,
+            exit_params,
+            order_engine,
+            entries_paused,
+            control_rx,
+            shutdown_rx,
+        ));
+    }
+
+    info!("\n🚀 Event bus online - analysis, signal, and execution tasks are running independently\n");
+
+    // Block until SIGINT, then give every task a moment to observe `shutdown_tx` and flush
+    // (the execution task saves `TradeHistory` on its shutdown branch) before exiting.
+    tokio::signal::ctrl_c().await?;
+    info!("\n🛑 Shutdown requested, draining event bus...");
+    let _ = shutdown_tx.send(());
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    info!("👋 Shut down cleanly");
+
+    Ok(())
+}
+
+/// Fold `WalletAnalysis` updates into a rolling view of elite wallets and their recent trades,
+/// running `AlphaAccelerator` over that view to publish `UltraSignal`s for the execution task.
+async fn run_signal_task(
+    db: Arc<Database>,
+    event_bus: Arc<EventBus>,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) {
+    let mut analyses = event_bus.subscribe();
+    let alpha_detector = AlphaAccelerator::new(3, 60);
+
+    let mut wallet_analyses: HashMap<Pubkey, WalletAnalysis> = HashMap::new();
+    let mut recent_trades: HashMap<Pubkey, Vec<Trade>> = HashMap::new();
+
+    loop {
+        tokio::select! {
+            event = analyses.recv() => {
+                let wallet = match event {
+                    Ok(BotEvent::WalletAnalysis { wallet, analysis }) => {
+                        wallet_analyses.insert(wallet, analysis);
+                        wallet
+                    }
+                    Ok(BotEvent::WalletEvicted { wallet }) => {
+                        wallet_analyses.remove(&wallet);
+                        recent_trades.remove(&wallet);
+                        continue;
+                    }
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("Signal task lagged behind the event bus by {} events", n);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+
+                match db.trades().get_wallet_trades(&wallet, 50).await {
+                    Ok(trades) => { recent_trades.insert(wallet, trades); }
+                    Err(e) => warn!("Failed to load trades for {} while scanning for signals: {}", wallet, e),
+                }
+
+                let mut signals = alpha_detector
+                    .find_ultra_high_confidence_signals(&wallet_analyses, &recent_trades)
+                    .await;
+                signals.extend(alpha_detector.find_volume_breakouts(&wallet_analyses, &recent_trades));
 
                 for signal in signals {
-                    info!("\n   ┌─ SIGNAL ─────────────────────");
-                    info!("   │ Token: {}", signal.token_mint);
-                    info!("   │ Type: {:?}", signal.signal_type);
-                    info!("   │ Confidence: {:.1}%", signal.confidence * 100.0);
-                    info!("   │ Reason: {}", signal.reason);
-                    info!("   └──────────────────────────────");
-
-                    if signal.confidence > 0.7 {
-                        // Check token safety
-                        info!("   🛡️  Checking token security...");
-
-                        match scam_detector.check_token_security(&signal.token_mint).await {
-                            Ok(security) => {
-                                if security.is_scam {
-                                    error!("   ❌ SCAM DETECTED! Skipping.");
-                                    continue;
-                                }
+                    info!("🎯 Ultra signal: {} ({:.0}% confidence)", signal.token_mint, signal.confidence * 100.0);
+                    event_bus.publish(BotEvent::Signal(signal));
+                }
+            }
+            _ = shutdown_rx.recv() => break,
+        }
+    }
+}
 
-                                if security.is_bundle {
-                                    warn!("   ⚠️  Bundle detected, skipping.");
-                                    continue;
-                                }
+/// Consume `UltraSignal`s to open new positions and, on its own tick, run
+/// `PositionManager::check_and_update_positions` to close them. The only task that owns
+/// `portfolio`, so it's also the one that flushes `TradeHistory` on shutdown.
+#[allow(clippy::too_many_arguments)]
+async fn run_execution_task(
+    event_bus: Arc<EventBus>,
+    mut portfolio: PortfolioMonitor,
+    rpc_client: Arc<FallbackRpcClient>,
+    scam_detector: Arc<ScamDetector>,
+    token_fetcher: Arc<TokenDataFetcher>,
+    market_feed: Arc<MarketFeed>,
+    trading_enabled: bool,
+    position_size: Decimal,
+    mut trade_history: TradeHistory,
+    history_path: std::path::PathBuf,
+    notifier: Arc<dyn Notifier>,
+    exit_params: ExitParams,
+    order_engine: Arc<OrderEngine>,
+    entries_paused: Arc<AtomicBool>,
+    mut control_rx: Option<mpsc::Receiver<ControlRequest>>,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) {
+    let mut signals = event_bus.subscribe();
+    let position_manager = PositionManager::new();
+    let mut position_check = tokio::time::interval(Duration::from_secs(30));
 
-                                info!("   ✅ Security check passed");
-
-                                // Get token data
-                                match token_fetcher.get_token_data(&signal.token_mint).await {
-                                    Ok(token) => {
-                                        info!("   💰 Price: ${}", token.market_data.price_usd);
-                                        info!("   💧 Liquidity: ${}", token.market_data.liquidity_usd);
-
-                                        // Check if worth trading (free tier checks)
-                                        if is_worth_trading(&token) {
-                                            if trading_enabled {
-                                                info!("   🔥 EXECUTING TRADE (would execute)");
-                                                // TODO: Implement actual execution
-                                            } else {
-                                                info!("   📝 [PAPER] Would execute trade");
-                                            }
-                                        } else {
-                                            warn!("   ⚠️  Token doesn't meet criteria");
-                                        }
-                                    }
-                                    Err(e) => {
-                                        error!("   ❌ Failed to fetch token: {}", e);
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                warn!("   ⚠️  Security check failed: {}", e);
-                            }
+    loop {
+        tokio::select! {
+            event = signals.recv() => {
+                match event {
+                    Ok(BotEvent::Signal(signal)) => {
+                        handle_ultra_signal(
+                            &signal,
+                            &mut portfolio,
+                            &rpc_client,
+                            &scam_detector,
+                            &token_fetcher,
+                            trading_enabled,
+                            position_size,
+                            &order_engine,
+                            &entries_paused,
+                            &exit_params,
+                        )
+                        .await;
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(n)) => warn!("Execution task lagged behind the event bus by {} events", n),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+            request = recv_control_request(&mut control_rx) => {
+                if let Some(request) = request {
+                    let reply = handle_control_command(request.command, &mut portfolio, &order_engine, &entries_paused, &notifier).await;
+                    let _ = request.respond_to.send(reply);
+                }
+            }
+            _ = position_check.tick() => {
+                match position_manager.check_and_update_positions(&mut portfolio, &token_fetcher, &market_feed, &exit_params).await {
+                    Ok((warnings, rollovers)) => {
+                        for (token_mint, token_symbol, hours_remaining) in warnings {
+                            notifier.notify_time_warning(&token_mint.to_string(), &token_symbol, hours_remaining).await;
+                        }
+                        for (token_mint, token_symbol, new_expires_at) in rollovers {
+                            notifier.notify_position_rolled_over(&token_mint.to_string(), &token_symbol, new_expires_at).await;
+                        }
+                    }
+                    Err(e) => warn!("Failed to update positions: {}", e),
+                }
+
+                for token_mint in order_engine.tracked_mints() {
+                    let Some(market) = market_feed.get(&token_mint) else { continue };
+                    for fired in order_engine.evaluate(&token_mint, Money::new(market.price_usd)) {
+                        info!("📐 Order fired for {}: {}", token_mint, fired.order_type.label());
+                        notifier
+                            .notify_order_fired(
+                                &token_mint.to_string(),
+                                fired.action.as_str(),
+                                &fired.order_type.label(),
+                                fired.price.as_decimal(),
+                            )
+                            .await;
+
+                        if fired.action == OrderAction::Sell && portfolio.get_position(&token_mint).is_some() {
+                            portfolio.close_position(&token_mint, fired.price.as_decimal(), ExitTrigger::Manual);
                         }
+                        // Buy (limit) fills still need the full security/sizing pipeline
+                        // `handle_ultra_signal` runs before opening a position - the order
+                        // engine only hands off the fill, it doesn't open positions itself.
+                    }
+                }
+
+                if let Some(closed_trade) = portfolio.get_last_closed_trade() {
+                    trade_history.add_closed_trade(closed_trade);
+                    trade_history.update_daily_stats(portfolio.get_daily_stats());
+
+                    if let Err(e) = trade_history.save(&history_path) {
+                        warn!("Failed to save trade history: {}", e);
                     }
                 }
             }
+            _ = shutdown_rx.recv() => {
+                info!("Execution task flushing trade history before shutdown");
+                if let Err(e) = trade_history.save(&history_path) {
+                    warn!("Failed to save trade history on shutdown: {}", e);
+                }
+                break;
+            }
         }
+    }
+}
+
+/// Polls `control_rx` if it's wired up (Telegram control enabled), otherwise never resolves
+/// so the `tokio::select!` arm that drives it simply never fires. Clears `control_rx` to
+/// `None` once the sender side closes, so a dead dispatcher doesn't spin the select loop.
+async fn recv_control_request(control_rx: &mut Option<mpsc::Receiver<ControlRequest>>) -> Option<ControlRequest> {
+    match control_rx {
+        Some(rx) => match rx.recv().await {
+            Some(request) => Some(request),
+            None => {
+                *control_rx = None;
+                std::future::pending().await
+            }
+        },
+        None => std::future::pending().await,
+    }
+}
+
+/// Handle one Telegram control command against the live engine state, returning the text to
+/// reply with. Runs on `run_execution_task`'s own task since that's the only place `portfolio`
+/// lives - nothing here touches `portfolio`/`order_engine` concurrently with the rest of the loop.
+async fn handle_control_command(
+    command: Command,
+    portfolio: &mut PortfolioMonitor,
+    order_engine: &OrderEngine,
+    entries_paused: &AtomicBool,
+    notifier: &Arc<dyn Notifier>,
+) -> String {
+    match command {
+        Command::Status => {
+            let stats = portfolio.get_daily_stats();
+            format!(
+                "📊 Trades: {} ({} wins, {:.1}% win rate)\n💰 Daily PnL: {}\n💼 Portfolio: {}\n{}",
+                stats.total_trades,
+                stats.wins,
+                stats.win_rate,
+                stats.total_pnl,
+                stats.portfolio_value,
+                if entries_paused.load(Ordering::SeqCst) { "⏸️ New entries paused" } else { "▶️ New entries active" }
+            )
+        }
+        Command::Positions => {
+            let mints = portfolio.get_position_mints();
+            if mints.is_empty() {
+                "No open positions".to_string()
+            } else {
+                mints
+                    .iter()
+                    .filter_map(|mint| portfolio.get_position(mint))
+                    .map(|p| format!("{}: entry ${} / now ${} ({:+.1}%)", p.token_symbol, p.entry_price, p.current_price, p.unrealized_pnl_pct))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        }
+        Command::Close(symbol) => {
+            let target = portfolio
+                .get_position_mints()
+                .into_iter()
+                .find(|mint| portfolio.get_position(mint).is_some_and(|p| p.token_symbol.eq_ignore_ascii_case(symbol.trim())));
+
+            match target {
+                Some(mint) => {
+                    let price = portfolio.get_position(&mint).map(|p| p.current_price.as_decimal()).unwrap_or_default();
+                    order_engine.cancel_all(&mint);
+                    match portfolio.close_position(&mint, price, ExitTrigger::Manual) {
+                        Some(trade) => format!("✅ Closed {} @ ${:.6}", trade.token_symbol, trade.exit_price),
+                        None => format!("⚠️ No open position for {}", symbol),
+                    }
+                }
+                None => format!("⚠️ No open position for {}", symbol),
+            }
+        }
+        Command::Pause => {
+            entries_paused.store(true, Ordering::SeqCst);
+            notifier.notify_maintenance_mode(true, "/pause").await;
+            "⏸️ New entries paused - open positions are still managed".to_string()
+        }
+        Command::Resume => {
+            entries_paused.store(false, Ordering::SeqCst);
+            notifier.notify_maintenance_mode(false, "/resume").await;
+            "▶️ New entries resumed".to_string()
+        }
+        Command::SellAll => {
+            let mints = portfolio.get_position_mints();
+            if mints.is_empty() {
+                return "No open positions to sell".to_string();
+            }
 
-        // Sleep until next cycle
-        info!("\n💤 Sleeping for {} seconds...\n", analysis_interval);
-        tokio::time::sleep(Duration::from_secs(analysis_interval)).await;
+            let mut closed = 0;
+            for mint in mints {
+                let price = portfolio.get_position(&mint).map(|p| p.current_price.as_decimal());
+                if let Some(price) = price {
+                    order_engine.cancel_all(&mint);
+                    if portfolio.close_position(&mint, price, ExitTrigger::Manual).is_some() {
+                        closed += 1;
+                    }
+                }
+            }
+            format!("✅ Sold {} position(s)", closed)
+        }
+    }
+}
+
+/// Run the security-check/token-data/execution-decision pipeline for one `UltraSignal`,
+/// opening a paper (or, once implemented, live) position when everything checks out.
+async fn handle_ultra_signal(
+    signal: &UltraSignal,
+    portfolio: &mut PortfolioMonitor,
+    rpc_client: &FallbackRpcClient,
+    scam_detector: &ScamDetector,
+    token_fetcher: &TokenDataFetcher,
+    trading_enabled: bool,
+    position_size: Decimal,
+    order_engine: &OrderEngine,
+    entries_paused: &AtomicBool,
+    exit_params: &ExitParams,
+) {
+    info!("\n   ┌─ SIGNAL ─────────────────────");
+    info!("   │ Token: {}", signal.token_mint);
+    info!("   │ Type: {:?}", signal.signal_type);
+    info!("   │ Confidence: {:.1}%", signal.confidence * 100.0);
+    info!("   └──────────────────────────────");
+
+    if signal.confidence <= 0.85 {
+        return;
+    }
+
+    if entries_paused.load(Ordering::SeqCst) {
+        info!("   ⏸️  New entries paused via /pause, skipping");
+        return;
+    }
+
+    // Check token safety
+    info!("   🛡️  Checking token security...");
+
+    let security = match scam_detector.check_token_security(rpc_client, &signal.token_mint).await {
+        Ok(security) => security,
+        Err(e) => {
+            warn!("   ⚠️  Security check failed: {}", e);
+            return;
+        }
+    };
+
+    if security.is_scam {
+        error!("   ❌ SCAM DETECTED! Skipping.");
+        return;
+    }
+
+    if security.is_bundle {
+        warn!("   ⚠️  Bundle detected, skipping.");
+        return;
+    }
+
+    info!("   ✅ Security check passed");
+
+    let token = match token_fetcher.get_token_data(&signal.token_mint).await {
+        Ok(token) => token,
+        Err(e) => {
+            error!("   ❌ Failed to fetch token: {}", e);
+            return;
+        }
+    };
+
+    info!("   💰 Price: ${}", token.market_data.price_usd);
+    info!("   💧 Liquidity: ${}", token.market_data.liquidity_usd);
+
+    if !is_worth_trading(&token) {
+        warn!("   ⚠️  Token doesn't meet criteria");
+        return;
+    }
+
+    let chart_signal = ChartAnalyzer::analyze_entry_exit(&token);
+    let combined_confidence = (signal.confidence + chart_signal.confidence) / 2.0;
+
+    match chart_signal.action {
+        TradeAction::StrongBuy | TradeAction::Buy if combined_confidence > 0.75 => {
+            if trading_enabled {
+                info!("   🔥 EXECUTING TRADE (would execute)");
+                // TODO: Implement actual execution
+            } else {
+                info!("   📝 [PAPER] Opening position at ${}", token.market_data.price_usd);
+
+                let price = Money::new(token.market_data.price_usd);
+                let stop_loss = Money::new(chart_signal.suggested_entry * Decimal::from_f64_retain(0.9).unwrap());
+                let take_profit = Money::new(chart_signal.suggested_exit);
+                let entry_time = chrono::Utc::now();
+                portfolio.open_position(OpenPosition {
+                    token_mint: signal.token_mint,
+                    token_symbol: token.symbol.clone(),
+                    entry_time,
+                    entry_price: price,
+                    entry_mc: Money::new(token.market_data.market_cap),
+                    amount: Money::new(position_size),
+                    current_price: price,
+                    current_mc: Money::new(token.market_data.market_cap),
+                    unrealized_pnl: Money::ZERO,
+                    unrealized_pnl_pct: 0.0,
+                    stop_loss,
+                    take_profit,
+                    peak_price: price,
+                    hold_time_minutes: 0,
+                    // No live strategy mode to read here, same default `BotConfig::strategy.mode` uses.
+                    expires_at: entry_time + exit_params.expiry_window(StrategyMode::SwingTrading),
+                });
+
+                // Bracket the paper position with real conditional orders instead of
+                // leaving `stop_loss`/`take_profit` as numbers `PositionManager` alone checks.
+                order_engine.place_order(signal.token_mint, OrderType::StopLoss { threshold: stop_loss });
+                order_engine.place_order(signal.token_mint, OrderType::TakeProfit { threshold: take_profit });
+            }
+        }
+        _ => warn!("   ⚠️  Chart doesn't confirm the signal, skipping"),
+    }
+}
+
+/// Forward the signal/portfolio events an operator cares about to every configured notifier
+/// sink (Telegram, webhook, Discord - whatever `notifier` fans out to).
+async fn run_notifier_sink(
+    notifier: Arc<dyn Notifier>,
+    mut events: broadcast::Receiver<BotEvent>,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) {
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                match event {
+                    Ok(BotEvent::Signal(signal)) => {
+                        notifier
+                            .notify_ultra_signal(&signal.token_mint.to_string(), signal.confidence, signal.smart_wallets_count as usize)
+                            .await;
+                    }
+                    Ok(BotEvent::Portfolio(portfolio_monitor::PortfolioEvent::PositionClosed { pnl, pnl_pct, is_win, .. })) => {
+                        info!("📱 Notifying sinks of closed position (PnL: {} / {:.1}%, win: {})", pnl, pnl_pct, is_win);
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(n)) => warn!("Notifier sink lagged behind the event bus by {} events", n),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+            _ = shutdown_rx.recv() => break,
+        }
     }
 }
 
@@ -252,65 +1015,73 @@ fn load_tracked_wallets(path: &str) -> Result<Vec<Pubkey>> {
     Ok(wallets)
 }
 
-async fn analyze_wallet(
+pub(crate) async fn analyze_wallet(
     rpc: &FallbackRpcClient,
+    db: &Database,
     wallet: &Pubkey,
+    stream_events: Option<&tokio::sync::broadcast::Sender<StreamEvent>>,
 ) -> Result<WalletAnalysis> {
-    // Get recent trades (limit to save on RPC calls)
-    let trades = TransactionParser::get_wallet_trades(rpc, wallet, 50).await?;
+    // Full-history backfill, capped by an incremental cursor so repeat cycles only
+    // fetch the delta instead of re-walking the whole wallet every time.
+    let wallet_repo = db.wallets();
+    let trade_repo = db.trades();
 
-    if trades.is_empty() {
-        return Err(anyhow::anyhow!("No trades found"));
-    }
+    let cursor = wallet_repo
+        .get_backfill_cursor(wallet)
+        .await?
+        .and_then(|sig| solana_sdk::signature::Signature::from_str(&sig).ok());
 
-    // Build analysis
-    let analysis = WalletMetricsCalculator::build_wallet_analysis(wallet, &trades)?;
+    let (new_trades, newest_signature) =
+        TransactionParser::get_wallet_trades_paginated(rpc, wallet, cursor, 10).await?;
 
-    Ok(analysis)
-}
+    let mut touched_mints = std::collections::HashSet::new();
+    for trade in &new_trades {
+        match trade_repo.save_trade(trade).await {
+            Err(e) => warn!("Failed to persist trade {}: {}", trade.signature, e),
+            Ok(inserted) => {
+                touched_mints.insert(trade.token_mint);
+                if inserted {
+                    if let Some(sender) = stream_events {
+                        let _ = sender.send(StreamEvent::Trade(trade.clone()));
+                    }
+                }
+            }
+        }
+    }
 
-#[derive(Debug, Clone)]
-struct AlphaSignal {
-    token_mint: Pubkey,
-    signal_type: AlphaType,
-    confidence: f64,
-    reason: String,
-}
+    // Roll any newly-saved trades into candles so the dashboard chart stays current
+    // without re-aggregating tokens nothing traded against this cycle.
+    let candle_repo = db.candles();
+    for mint in &touched_mints {
+        for resolution in Resolution::chart_set() {
+            if let Err(e) = candle_repo
+                .backfill_incremental(&trade_repo, mint, resolution)
+                .await
+            {
+                warn!("Failed to update candles for {} ({}): {}", mint, resolution.as_str(), e);
+            }
+        }
+    }
 
-#[derive(Debug, Clone)]
-enum AlphaType {
-    SmartMoneyConvergence,
-    TopWalletTrade,
-    UnusualActivity,
-}
+    if let Some(newest) = newest_signature {
+        wallet_repo
+            .save_backfill_cursor(wallet, &newest.to_string())
+            .await?;
+    }
 
-async fn detect_alpha_signals(
-    wallet_analyses: &HashMap<Pubkey, WalletAnalysis>,
-) -> Vec<AlphaSignal> {
-    let mut signals = vec![];
-
-    // Simple alpha detection for free tier
-    // Signal 1: Top wallet made recent trade
-    for (_wallet, analysis) in wallet_analyses.iter() {
-        if analysis.smart_money_score > 0.85
-            && analysis.metrics.win_rate > 75.0
-            && analysis.metrics.total_trades >= 20
-        {
-            // This is an elite wallet
-            // In full version, would check their recent trades
-            // For now, just placeholder
-        }
+    // Build analysis on the full, previously-backfilled trade set, not just this cycle's delta
+    let trades = trade_repo.get_wallet_trades(wallet, i64::MAX).await?;
+
+    if trades.is_empty() {
+        return Err(anyhow::anyhow!("No trades found"));
     }
 
-    // Signal 2: Multiple wallets trading same token
-    // TODO: Implement when database is set up
+    let analysis = WalletMetricsCalculator::build_wallet_analysis(wallet, &trades, LotMatchingMode::Lifo)?;
 
-    signals
+    Ok(analysis)
 }
 
 fn is_worth_trading(token: &Token) -> bool {
-    use rust_decimal::Decimal;
-
     // Free tier safety checks
 
     // 1. Minimum liquidity
@@ -0,0 +1,255 @@
+// Streaming price/fill ingestion, modeled on the same reconnect-loop shape as
+// `trading_data::PriceStream`: a background task holds the WebSocket, and
+// `PositionManager::check_and_update_positions` reads the shared map instead of
+// fetching each tick, so exit checks run as fast as prices arrive rather than once
+// per polling cycle.
+use dashmap::DashMap;
+use futures_util::{SinkExt, StreamExt};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, error, info, warn};
+use trading_analysis::candles::{Candle, Resolution};
+use trading_core::{MarketData, Result, TradingError};
+
+use crate::candle_store::CandleStore;
+use crate::portfolio_monitor::PortfolioEvent;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ControlFrame<'a> {
+    Subscribe { mints: &'a [String] },
+    Ping,
+}
+
+/// Inbound frame from the feed. Tag-dispatched like an exchange account-event stream,
+/// so adding a new event kind (e.g. `Fill`) later is just another enum variant.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum MarketEvent {
+    Price {
+        token_mint: String,
+        price_usd: String,
+        market_cap: String,
+        /// Notional volume transacted since the previous tick. Optional since older feed
+        /// versions only ever pushed price - defaults to zero rather than failing to parse.
+        #[serde(default)]
+        volume_usd: String,
+        ts: i64,
+    },
+    Pong,
+}
+
+/// Shared live-price feed: a background WebSocket connection pushes `MarketEvent`s
+/// into `live`, which `PositionManager` reads directly instead of polling DexScreener
+/// for every open position on every tick.
+pub struct MarketFeed {
+    ws_url: String,
+    live: Arc<DashMap<Pubkey, MarketData>>,
+    last_ts: Arc<DashMap<Pubkey, i64>>,
+    subscribed: Arc<RwLock<HashSet<Pubkey>>>,
+    heartbeat_timeout: Duration,
+    candles: Arc<CandleStore>,
+}
+
+impl MarketFeed {
+    pub fn new(ws_url: String) -> Self {
+        Self {
+            ws_url,
+            live: Arc::new(DashMap::new()),
+            last_ts: Arc::new(DashMap::new()),
+            subscribed: Arc::new(RwLock::new(HashSet::new())),
+            heartbeat_timeout: Duration::from_secs(30),
+            candles: Arc::new(CandleStore::new()),
+        }
+    }
+
+    /// Real OHLCV candle series built from every tick this feed has applied, for one
+    /// (token, resolution) - ready for `ChartAnalyzer::analyze_candles`.
+    pub fn candles(&self, mint: &Pubkey, resolution: Resolution) -> Vec<Candle> {
+        self.candles.series(mint, resolution)
+    }
+
+    /// Every tracked resolution's candle series for `mint`, ready for
+    /// `ChartAnalyzer::analyze_multi_resolution`.
+    pub fn all_candles(&self, mint: &Pubkey) -> std::collections::HashMap<Resolution, Vec<Candle>> {
+        self.candles.all_series(mint)
+    }
+
+    /// Current live price for `mint`, if a subscription has delivered one yet. Callers
+    /// fall back to a REST fetch when this returns `None`.
+    pub fn get(&self, mint: &Pubkey) -> Option<MarketData> {
+        self.live.get(mint).map(|entry| entry.clone())
+    }
+
+    /// Mark `mint` as wanted; picked up on the next (re)connect's subscribe frame.
+    pub async fn subscribe(&self, mint: Pubkey) {
+        self.subscribed.write().await.insert(mint);
+    }
+
+    /// Stop tracking `mint` and drop any cached price for it immediately, so a closed
+    /// position never reads a stale live price if it gets reopened under a new feed.
+    pub async fn unsubscribe(&self, mint: Pubkey) {
+        self.subscribed.write().await.remove(&mint);
+        self.live.remove(&mint);
+        self.last_ts.remove(&mint);
+    }
+
+    /// Start the reconnect loop in the background. Safe to call once.
+    pub fn run(self: &Arc<Self>) {
+        let feed = self.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = feed.run_connection().await {
+                    warn!("Market feed connection to {} ended: {}", feed.ws_url, e);
+                }
+                tokio::time::sleep(Duration::from_secs(2)).await;
+            }
+        });
+    }
+
+    /// Subscribe `MarketFeed` to the portfolio event bus so opening/closing a position
+    /// automatically subscribes/unsubscribes its live price feed.
+    pub fn run_subscription_manager(
+        self: &Arc<Self>,
+        mut events: tokio::sync::broadcast::Receiver<PortfolioEvent>,
+    ) {
+        let feed = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let event = match events.recv().await {
+                    Ok(event) => event,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                };
+
+                match event {
+                    PortfolioEvent::PositionOpened { token_mint, .. } => {
+                        feed.subscribe(token_mint).await;
+                    }
+                    PortfolioEvent::PositionClosed { token_mint, .. }
+                    | PortfolioEvent::StopLossHit { token_mint, .. }
+                    | PortfolioEvent::TakeProfitHit { token_mint, .. }
+                    | PortfolioEvent::TrailingStopHit { token_mint, .. }
+                    | PortfolioEvent::TimeExit { token_mint, .. } => {
+                        feed.unsubscribe(token_mint).await;
+                    }
+                    PortfolioEvent::BigWin { .. } | PortfolioEvent::PositionRolledOver { .. } => {}
+                }
+            }
+        });
+    }
+
+    async fn run_connection(&self) -> Result<()> {
+        info!("Opening market feed WebSocket to {}", self.ws_url);
+        let (ws, _response) = tokio_tungstenite::connect_async(&self.ws_url)
+            .await
+            .map_err(|e| TradingError::DataFetchError(format!("Market feed connect failed: {}", e)))?;
+        let (mut write, mut read) = ws.split();
+
+        let mints: Vec<String> = self
+            .subscribed
+            .read()
+            .await
+            .iter()
+            .map(|m| m.to_string())
+            .collect();
+        if !mints.is_empty() {
+            let frame = serde_json::to_string(&ControlFrame::Subscribe { mints: &mints })
+                .map_err(|e| TradingError::ParseError(e.to_string()))?;
+            write
+                .send(Message::Text(frame))
+                .await
+                .map_err(|e| TradingError::DataFetchError(format!("Failed to send subscribe frame: {}", e)))?;
+        }
+
+        loop {
+            match tokio::time::timeout(self.heartbeat_timeout, read.next()).await {
+                Ok(Some(Ok(Message::Text(text)))) => match serde_json::from_str::<MarketEvent>(&text) {
+                    Ok(MarketEvent::Price {
+                        token_mint,
+                        price_usd,
+                        market_cap,
+                        volume_usd,
+                        ts,
+                    }) => {
+                        self.handle_price(&token_mint, &price_usd, &market_cap, &volume_usd, ts);
+                    }
+                    Ok(MarketEvent::Pong) => {}
+                    Err(e) => {
+                        debug!("Ignoring unparseable market feed frame: {}", e);
+                    }
+                },
+                Ok(Some(Ok(Message::Ping(payload)))) => {
+                    let _ = write.send(Message::Pong(payload)).await;
+                }
+                Ok(Some(Ok(_))) => {}
+                Ok(Some(Err(e))) => {
+                    return Err(TradingError::DataFetchError(format!("Market feed error: {}", e)));
+                }
+                Ok(None) => {
+                    return Err(TradingError::DataFetchError(
+                        "Market feed closed by server".to_string(),
+                    ));
+                }
+                Err(_) => {
+                    warn!("Market feed heartbeat timeout on {}, sending ping", self.ws_url);
+                    let ping = serde_json::to_string(&ControlFrame::Ping)
+                        .unwrap_or_else(|_| "{\"type\":\"ping\"}".to_string());
+                    if write.send(Message::Text(ping)).await.is_err() {
+                        return Err(TradingError::DataFetchError(
+                            "Market feed ping failed, reconnecting".to_string(),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Apply a price update, dropping it if `ts` is not newer than the last applied update
+    /// for this mint - a reconnect or out-of-order delivery shouldn't roll a price back.
+    fn handle_price(&self, token_mint: &str, price_usd: &str, market_cap: &str, volume_usd: &str, ts: i64) {
+        let Ok(mint) = Pubkey::from_str(token_mint) else {
+            debug!("Market feed price event had unparseable mint: {}", token_mint);
+            return;
+        };
+
+        if let Some(last) = self.last_ts.get(&mint) {
+            if ts <= *last {
+                return;
+            }
+        }
+
+        let price_usd = Decimal::from_str(price_usd).unwrap_or(Decimal::ZERO);
+        let market_cap = Decimal::from_str(market_cap).unwrap_or(Decimal::ZERO);
+        let volume_usd = Decimal::from_str(volume_usd).unwrap_or(Decimal::ZERO);
+
+        self.candles.record_tick(mint, price_usd, volume_usd, ts);
+
+        self.live
+            .entry(mint)
+            .and_modify(|market| {
+                market.price_usd = price_usd;
+                market.market_cap = market_cap;
+            })
+            .or_insert_with(|| MarketData {
+                price_usd,
+                price_sol: Decimal::ZERO,
+                market_cap,
+                liquidity_usd: Decimal::ZERO,
+                volume_24h: Decimal::ZERO,
+                price_change_24h: 0.0,
+                price_change_1h: 0.0,
+                price_change_5m: 0.0,
+                holders: None,
+                dex: None,
+            });
+        self.last_ts.insert(mint, ts);
+    }
+}
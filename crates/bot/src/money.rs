@@ -0,0 +1,104 @@
+// Strongly typed monetary amount, replacing the TEXT-column / float-parsing idiom that
+// used to be scattered across portfolio tracking: every conversion between `Decimal` and
+// a displayed or persisted value now goes through exactly one of these methods instead of
+// an ad-hoc `.to_string().parse::<f64>().unwrap_or(0.0)` at each call site.
+use std::fmt;
+use std::ops::{Add, AddAssign, Sub};
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Money(Decimal);
+
+impl Money {
+    pub const ZERO: Money = Money(Decimal::ZERO);
+
+    pub fn new(value: Decimal) -> Self {
+        Self(value)
+    }
+
+    /// Parse a canonical decimal string (the form `Money` persists as), e.g. from a
+    /// JSON/TEXT column written by an earlier version of this type.
+    pub fn from_str(value: &str) -> Option<Self> {
+        Decimal::from_str_exact(value).ok().map(Self)
+    }
+
+    pub fn as_decimal(self) -> Decimal {
+        self.0
+    }
+
+    /// Boundary conversion for columns persisted as a native SQLite `REAL` rather than a
+    /// `TEXT`-encoded decimal string, so `WHERE`/`ORDER BY`/`SUM` against them compare
+    /// numerically instead of lexicographically.
+    pub fn to_f64(self) -> f64 {
+        self.0.try_into().unwrap_or(0.0)
+    }
+
+    pub fn from_f64(value: f64) -> Self {
+        Decimal::try_from(value).map(Self).unwrap_or(Self::ZERO)
+    }
+
+    pub fn checked_add(self, rhs: Money) -> Option<Money> {
+        self.0.checked_add(rhs.0).map(Money)
+    }
+
+    pub fn checked_sub(self, rhs: Money) -> Option<Money> {
+        self.0.checked_sub(rhs.0).map(Money)
+    }
+
+    pub fn checked_mul(self, rhs: Decimal) -> Option<Money> {
+        self.0.checked_mul(rhs).map(Money)
+    }
+
+    pub fn checked_div(self, rhs: Decimal) -> Option<Money> {
+        self.0.checked_div(rhs).map(Money)
+    }
+
+    /// Percentage change from `entry` to `current`. The single audited place this repo
+    /// computes a Decimal ratio as an f64 percentage - previously duplicated at every
+    /// call site via `.to_string().parse::<f64>().unwrap_or(0.0)`. A zero `entry`, or a
+    /// ratio that fails to parse, still yields 0.0 ("no change").
+    pub fn pct_change(entry: Money, current: Money) -> f64 {
+        if entry.0.is_zero() {
+            return 0.0;
+        }
+        ((current.0 - entry.0) / entry.0 * Decimal::from(100))
+            .to_string()
+            .parse::<f64>()
+            .unwrap_or(0.0)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+    fn add(self, rhs: Money) -> Money {
+        Money(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Money) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+    fn sub(self, rhs: Money) -> Money {
+        Money(self.0 - rhs.0)
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl From<Decimal> for Money {
+    fn from(value: Decimal) -> Self {
+        Self(value)
+    }
+}
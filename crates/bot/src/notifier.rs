@@ -0,0 +1,590 @@
+// Generalizes the bot's alerting beyond Telegram: a `Notifier` trait any backend can
+// implement, a `CompositeNotifier` that fans the same call out to several of them, a
+// de-duplicating wrapper so a signal that keeps re-triggering every cycle doesn't spam the
+// same alert every time, and a `HeartbeatMonitor` that notices a silently-stuck bot.
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::portfolio_monitor::{ClosedTrade, DailyStats, OpenPosition};
+use crate::telegram::TelegramNotifier;
+
+/// Every alert the bot can push to an external channel. Implementors forward each call to
+/// whatever medium they speak - Telegram, a generic webhook, Discord, and so on.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify_bot_started(&self, starting_capital: Decimal);
+    async fn notify_position_opened(&self, position: &OpenPosition, confidence: f64);
+    async fn notify_position_closed(&self, trade: &ClosedTrade);
+    async fn notify_portfolio_update(&self, stats: &DailyStats);
+    async fn notify_ultra_signal(&self, token_mint: &str, confidence: f64, smart_wallets_count: usize);
+    async fn notify_scam_detected(&self, token_mint: &str);
+    async fn notify_cycle_complete(&self, cycle: u32, wallets_analyzed: usize, signals_found: usize);
+    /// No cycle has completed in `seconds_since_last_cycle` - longer than the configured
+    /// heartbeat threshold, which usually means a task is wedged rather than just between cycles.
+    async fn notify_heartbeat_missed(&self, seconds_since_last_cycle: u64);
+    /// A position is approaching its forced time-based exit (`OpenPosition::expires_at`), with
+    /// `hours_remaining` left before `PositionManager` closes it (or, on the REST-fetch path,
+    /// rolls it over) regardless of PnL.
+    async fn notify_time_warning(&self, token_mint: &str, token_symbol: &str, hours_remaining: i64);
+    /// A conditional order placed through `OrderEngine` crossed its trigger. `action` is
+    /// `"BUY"`/`"SELL"` (`OrderAction::as_str`), `order_description` is its `OrderType::label`.
+    async fn notify_order_fired(&self, token_mint: &str, action: &str, order_description: &str, price: Decimal);
+    /// Maintenance (resume-only) mode was entered or left - new entries are blocked/allowed
+    /// again, but open positions keep being managed either way. `reason` is freeform
+    /// (e.g. "startup flag", "/pause", "/resume").
+    async fn notify_maintenance_mode(&self, active: bool, reason: &str);
+    /// A position past its `expires_at` was rolled over instead of closed - `PositionManager`
+    /// re-checked `ChartAnalyzer::analyze_entry_exit` against fresh data and the signal still
+    /// supported holding, so `new_expires_at` replaces the old expiry.
+    async fn notify_position_rolled_over(&self, token_mint: &str, token_symbol: &str, new_expires_at: DateTime<Utc>);
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    async fn notify_bot_started(&self, starting_capital: Decimal) {
+        TelegramNotifier::notify_bot_started(self, starting_capital).await;
+    }
+
+    async fn notify_position_opened(&self, position: &OpenPosition, confidence: f64) {
+        TelegramNotifier::notify_position_opened(self, position, confidence).await;
+    }
+
+    async fn notify_position_closed(&self, trade: &ClosedTrade) {
+        TelegramNotifier::notify_position_closed(self, trade).await;
+    }
+
+    async fn notify_portfolio_update(&self, stats: &DailyStats) {
+        TelegramNotifier::notify_portfolio_update(self, stats).await;
+    }
+
+    async fn notify_ultra_signal(&self, token_mint: &str, confidence: f64, smart_wallets_count: usize) {
+        TelegramNotifier::notify_ultra_signal(self, token_mint, confidence, smart_wallets_count).await;
+    }
+
+    async fn notify_scam_detected(&self, token_mint: &str) {
+        TelegramNotifier::notify_scam_detected(self, token_mint).await;
+    }
+
+    async fn notify_cycle_complete(&self, cycle: u32, wallets_analyzed: usize, signals_found: usize) {
+        TelegramNotifier::notify_cycle_complete(self, cycle, wallets_analyzed, signals_found).await;
+    }
+
+    async fn notify_heartbeat_missed(&self, seconds_since_last_cycle: u64) {
+        warn!(
+            "💓 No cycle completed in {}s, bot may be stuck - Telegram has no dedicated heartbeat message, logging instead",
+            seconds_since_last_cycle
+        );
+    }
+
+    async fn notify_time_warning(&self, token_mint: &str, token_symbol: &str, hours_remaining: i64) {
+        TelegramNotifier::notify_time_warning(self, token_mint, token_symbol, hours_remaining).await;
+    }
+
+    async fn notify_order_fired(&self, token_mint: &str, action: &str, order_description: &str, price: Decimal) {
+        TelegramNotifier::notify_order_fired(self, token_mint, action, order_description, price).await;
+    }
+
+    async fn notify_maintenance_mode(&self, active: bool, reason: &str) {
+        TelegramNotifier::notify_maintenance_mode(self, active, reason).await;
+    }
+
+    async fn notify_position_rolled_over(&self, token_mint: &str, token_symbol: &str, new_expires_at: DateTime<Utc>) {
+        TelegramNotifier::notify_position_rolled_over(self, token_mint, token_symbol, new_expires_at).await;
+    }
+}
+
+/// Posts each notification as a small JSON payload to a configured URL - a minimal backend
+/// any external integration can consume without speaking Telegram or Discord specifically.
+pub struct WebhookSink {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl WebhookSink {
+    pub fn new(url: String) -> Self {
+        Self { url, client: reqwest::Client::new() }
+    }
+
+    async fn post(&self, kind: &'static str, mut payload: serde_json::Value) {
+        if let Some(obj) = payload.as_object_mut() {
+            obj.insert("kind".to_string(), serde_json::Value::String(kind.to_string()));
+        }
+
+        if let Err(e) = self.client.post(&self.url).json(&payload).send().await {
+            warn!("Failed to deliver webhook notification ({}): {}", kind, e);
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookSink {
+    async fn notify_bot_started(&self, starting_capital: Decimal) {
+        self.post("bot_started", serde_json::json!({ "starting_capital": starting_capital.to_string() })).await;
+    }
+
+    async fn notify_position_opened(&self, position: &OpenPosition, confidence: f64) {
+        self.post(
+            "position_opened",
+            serde_json::json!({
+                "token_mint": position.token_mint.to_string(),
+                "token_symbol": position.token_symbol,
+                "entry_price": position.entry_price.to_string(),
+                "confidence": confidence,
+            }),
+        )
+        .await;
+    }
+
+    async fn notify_position_closed(&self, trade: &ClosedTrade) {
+        self.post(
+            "position_closed",
+            serde_json::json!({
+                "token_mint": trade.token_mint.to_string(),
+                "token_symbol": trade.token_symbol,
+                "pnl": trade.pnl.to_string(),
+                "pnl_pct": trade.pnl_pct,
+                "is_win": trade.is_win,
+            }),
+        )
+        .await;
+    }
+
+    async fn notify_portfolio_update(&self, stats: &DailyStats) {
+        self.post(
+            "portfolio_update",
+            serde_json::json!({
+                "portfolio_value": stats.portfolio_value.to_string(),
+                "total_pnl": stats.total_pnl.to_string(),
+                "win_rate": stats.win_rate,
+            }),
+        )
+        .await;
+    }
+
+    async fn notify_ultra_signal(&self, token_mint: &str, confidence: f64, smart_wallets_count: usize) {
+        self.post(
+            "ultra_signal",
+            serde_json::json!({
+                "token_mint": token_mint,
+                "confidence": confidence,
+                "smart_wallets_count": smart_wallets_count,
+            }),
+        )
+        .await;
+    }
+
+    async fn notify_scam_detected(&self, token_mint: &str) {
+        self.post("scam_detected", serde_json::json!({ "token_mint": token_mint })).await;
+    }
+
+    async fn notify_cycle_complete(&self, cycle: u32, wallets_analyzed: usize, signals_found: usize) {
+        self.post(
+            "cycle_complete",
+            serde_json::json!({
+                "cycle": cycle,
+                "wallets_analyzed": wallets_analyzed,
+                "signals_found": signals_found,
+            }),
+        )
+        .await;
+    }
+
+    async fn notify_heartbeat_missed(&self, seconds_since_last_cycle: u64) {
+        self.post(
+            "heartbeat_missed",
+            serde_json::json!({ "seconds_since_last_cycle": seconds_since_last_cycle }),
+        )
+        .await;
+    }
+
+    async fn notify_time_warning(&self, token_mint: &str, token_symbol: &str, hours_remaining: i64) {
+        self.post(
+            "time_warning",
+            serde_json::json!({
+                "token_mint": token_mint,
+                "token_symbol": token_symbol,
+                "hours_remaining": hours_remaining,
+            }),
+        )
+        .await;
+    }
+
+    async fn notify_order_fired(&self, token_mint: &str, action: &str, order_description: &str, price: Decimal) {
+        self.post(
+            "order_fired",
+            serde_json::json!({
+                "token_mint": token_mint,
+                "action": action,
+                "order_description": order_description,
+                "price": price.to_string(),
+            }),
+        )
+        .await;
+    }
+
+    async fn notify_maintenance_mode(&self, active: bool, reason: &str) {
+        self.post("maintenance_mode", serde_json::json!({ "active": active, "reason": reason })).await;
+    }
+
+    async fn notify_position_rolled_over(&self, token_mint: &str, token_symbol: &str, new_expires_at: DateTime<Utc>) {
+        self.post(
+            "position_rolled_over",
+            serde_json::json!({
+                "token_mint": token_mint,
+                "token_symbol": token_symbol,
+                "new_expires_at": new_expires_at.to_rfc3339(),
+            }),
+        )
+        .await;
+    }
+}
+
+/// Posts to a Discord incoming webhook, which only understands a `content` string per message
+/// rather than arbitrary JSON - so each alert is rendered as plain text, same wording as Telegram
+/// minus the HTML tags.
+pub struct DiscordNotifier {
+    webhook_url: String,
+    client: reqwest::Client,
+}
+
+impl DiscordNotifier {
+    pub fn new(webhook_url: String) -> Self {
+        Self { webhook_url, client: reqwest::Client::new() }
+    }
+
+    async fn send(&self, content: String) {
+        let body = serde_json::json!({ "content": content });
+        if let Err(e) = self.client.post(&self.webhook_url).json(&body).send().await {
+            warn!("Failed to deliver Discord notification: {}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    async fn notify_bot_started(&self, starting_capital: Decimal) {
+        self.send(format!("🤖 **Solana Trading Bot started** - starting capital ${:.2}", starting_capital)).await;
+    }
+
+    async fn notify_position_opened(&self, position: &OpenPosition, confidence: f64) {
+        self.send(format!(
+            "🟢 **Position opened** - {} @ ${:.6} (confidence {:.0}%)",
+            position.token_symbol, position.entry_price, confidence * 100.0
+        ))
+        .await;
+    }
+
+    async fn notify_position_closed(&self, trade: &ClosedTrade) {
+        let emoji = if trade.is_win { "✅" } else { "❌" };
+        self.send(format!(
+            "{} **Position closed** - {} PnL ${:.2} ({:.1}%)",
+            emoji, trade.token_symbol, trade.pnl, trade.pnl_pct
+        ))
+        .await;
+    }
+
+    async fn notify_portfolio_update(&self, stats: &DailyStats) {
+        self.send(format!(
+            "📊 **Portfolio update** - value ${:.2}, daily PnL ${:.2}",
+            stats.portfolio_value, stats.total_pnl
+        ))
+        .await;
+    }
+
+    async fn notify_ultra_signal(&self, token_mint: &str, confidence: f64, smart_wallets_count: usize) {
+        self.send(format!(
+            "🔥 **Ultra signal** - `{}` ({:.0}% confidence, {} smart wallets)",
+            token_mint, confidence * 100.0, smart_wallets_count
+        ))
+        .await;
+    }
+
+    async fn notify_scam_detected(&self, token_mint: &str) {
+        self.send(format!("⚠️ **Scam detected** - `{}` skipped", token_mint)).await;
+    }
+
+    async fn notify_cycle_complete(&self, cycle: u32, wallets_analyzed: usize, signals_found: usize) {
+        self.send(format!(
+            "♻️ Cycle #{} complete - {} wallets analyzed, {} signals found",
+            cycle, wallets_analyzed, signals_found
+        ))
+        .await;
+    }
+
+    async fn notify_heartbeat_missed(&self, seconds_since_last_cycle: u64) {
+        self.send(format!(
+            "💓 **Heartbeat missed** - no cycle has completed in {}s, the bot may be stuck",
+            seconds_since_last_cycle
+        ))
+        .await;
+    }
+
+    async fn notify_time_warning(&self, token_mint: &str, token_symbol: &str, hours_remaining: i64) {
+        self.send(format!(
+            "⏰ **Position expiring soon** - {} (`{}`) forced exit in {}h",
+            token_symbol, token_mint, hours_remaining
+        ))
+        .await;
+    }
+
+    async fn notify_order_fired(&self, token_mint: &str, action: &str, order_description: &str, price: Decimal) {
+        self.send(format!(
+            "📐 **Conditional order fired** - {} `{}` @ ${:.6} ({})",
+            action, token_mint, price, order_description
+        ))
+        .await;
+    }
+
+    async fn notify_maintenance_mode(&self, active: bool, reason: &str) {
+        let state = if active { "entered" } else { "left" };
+        self.send(format!("🛠️ **Maintenance mode {}** - {}", state, reason)).await;
+    }
+
+    async fn notify_position_rolled_over(&self, token_mint: &str, token_symbol: &str, new_expires_at: DateTime<Utc>) {
+        self.send(format!(
+            "♻️ **Position rolled over** - {} (`{}`) new expiry {}",
+            token_symbol, token_mint, new_expires_at.format("%Y-%m-%d %H:%M UTC")
+        ))
+        .await;
+    }
+}
+
+/// Fans every call out to each backend in turn. A backend's own send failure is logged by
+/// that backend and doesn't stop the others from receiving the same notification.
+pub struct CompositeNotifier {
+    backends: Vec<Box<dyn Notifier>>,
+}
+
+impl CompositeNotifier {
+    pub fn new(backends: Vec<Box<dyn Notifier>>) -> Self {
+        Self { backends }
+    }
+}
+
+#[async_trait]
+impl Notifier for CompositeNotifier {
+    async fn notify_bot_started(&self, starting_capital: Decimal) {
+        for backend in &self.backends {
+            backend.notify_bot_started(starting_capital).await;
+        }
+    }
+
+    async fn notify_position_opened(&self, position: &OpenPosition, confidence: f64) {
+        for backend in &self.backends {
+            backend.notify_position_opened(position, confidence).await;
+        }
+    }
+
+    async fn notify_position_closed(&self, trade: &ClosedTrade) {
+        for backend in &self.backends {
+            backend.notify_position_closed(trade).await;
+        }
+    }
+
+    async fn notify_portfolio_update(&self, stats: &DailyStats) {
+        for backend in &self.backends {
+            backend.notify_portfolio_update(stats).await;
+        }
+    }
+
+    async fn notify_ultra_signal(&self, token_mint: &str, confidence: f64, smart_wallets_count: usize) {
+        for backend in &self.backends {
+            backend.notify_ultra_signal(token_mint, confidence, smart_wallets_count).await;
+        }
+    }
+
+    async fn notify_scam_detected(&self, token_mint: &str) {
+        for backend in &self.backends {
+            backend.notify_scam_detected(token_mint).await;
+        }
+    }
+
+    async fn notify_cycle_complete(&self, cycle: u32, wallets_analyzed: usize, signals_found: usize) {
+        for backend in &self.backends {
+            backend.notify_cycle_complete(cycle, wallets_analyzed, signals_found).await;
+        }
+    }
+
+    async fn notify_heartbeat_missed(&self, seconds_since_last_cycle: u64) {
+        for backend in &self.backends {
+            backend.notify_heartbeat_missed(seconds_since_last_cycle).await;
+        }
+    }
+
+    async fn notify_time_warning(&self, token_mint: &str, token_symbol: &str, hours_remaining: i64) {
+        for backend in &self.backends {
+            backend.notify_time_warning(token_mint, token_symbol, hours_remaining).await;
+        }
+    }
+
+    async fn notify_order_fired(&self, token_mint: &str, action: &str, order_description: &str, price: Decimal) {
+        for backend in &self.backends {
+            backend.notify_order_fired(token_mint, action, order_description, price).await;
+        }
+    }
+
+    async fn notify_maintenance_mode(&self, active: bool, reason: &str) {
+        for backend in &self.backends {
+            backend.notify_maintenance_mode(active, reason).await;
+        }
+    }
+
+    async fn notify_position_rolled_over(&self, token_mint: &str, token_symbol: &str, new_expires_at: DateTime<Utc>) {
+        for backend in &self.backends {
+            backend.notify_position_rolled_over(token_mint, token_symbol, new_expires_at).await;
+        }
+    }
+}
+
+/// How much a signal's confidence must move to justify re-sending `notify_ultra_signal`
+/// before its cooldown has even elapsed - otherwise the same token re-flagged cycle after
+/// cycle at a near-identical confidence would fire on every cooldown boundary regardless.
+const MATERIAL_CONFIDENCE_DELTA: f64 = 0.05;
+
+struct LastNotification {
+    sent_at: Instant,
+    confidence: Option<f64>,
+}
+
+/// Wraps another `Notifier` and suppresses repeat `notify_ultra_signal`/`notify_scam_detected`
+/// calls for the same token mint within `cooldown`, so a signal that keeps re-triggering every
+/// cycle alerts once when it first appears and again only once the cooldown has passed (or,
+/// for ultra signals, once its confidence has materially changed). Every other event passes
+/// through unthrottled - they already represent a single, genuine occurrence.
+pub struct DeduplicatingNotifier<N: Notifier> {
+    inner: N,
+    cooldown: Duration,
+    last_sent: Mutex<HashMap<(&'static str, String), LastNotification>>,
+}
+
+impl<N: Notifier> DeduplicatingNotifier<N> {
+    pub fn new(inner: N, cooldown: Duration) -> Self {
+        Self { inner, cooldown, last_sent: Mutex::new(HashMap::new()) }
+    }
+
+    async fn should_send(&self, kind: &'static str, token_mint: &str, confidence: Option<f64>) -> bool {
+        let mut last_sent = self.last_sent.lock().await;
+        let key = (kind, token_mint.to_string());
+        let now = Instant::now();
+
+        let should = match last_sent.get(&key) {
+            None => true,
+            Some(prev) => {
+                now.duration_since(prev.sent_at) >= self.cooldown
+                    || match (confidence, prev.confidence) {
+                        (Some(current), Some(last)) => (current - last).abs() >= MATERIAL_CONFIDENCE_DELTA,
+                        _ => false,
+                    }
+            }
+        };
+
+        if should {
+            last_sent.insert(key, LastNotification { sent_at: now, confidence });
+        }
+
+        should
+    }
+}
+
+#[async_trait]
+impl<N: Notifier> Notifier for DeduplicatingNotifier<N> {
+    async fn notify_bot_started(&self, starting_capital: Decimal) {
+        self.inner.notify_bot_started(starting_capital).await;
+    }
+
+    async fn notify_position_opened(&self, position: &OpenPosition, confidence: f64) {
+        self.inner.notify_position_opened(position, confidence).await;
+    }
+
+    async fn notify_position_closed(&self, trade: &ClosedTrade) {
+        self.inner.notify_position_closed(trade).await;
+    }
+
+    async fn notify_portfolio_update(&self, stats: &DailyStats) {
+        self.inner.notify_portfolio_update(stats).await;
+    }
+
+    async fn notify_ultra_signal(&self, token_mint: &str, confidence: f64, smart_wallets_count: usize) {
+        if self.should_send("ultra_signal", token_mint, Some(confidence)).await {
+            self.inner.notify_ultra_signal(token_mint, confidence, smart_wallets_count).await;
+        }
+    }
+
+    async fn notify_scam_detected(&self, token_mint: &str) {
+        if self.should_send("scam_detected", token_mint, None).await {
+            self.inner.notify_scam_detected(token_mint).await;
+        }
+    }
+
+    async fn notify_cycle_complete(&self, cycle: u32, wallets_analyzed: usize, signals_found: usize) {
+        self.inner.notify_cycle_complete(cycle, wallets_analyzed, signals_found).await;
+    }
+
+    async fn notify_heartbeat_missed(&self, seconds_since_last_cycle: u64) {
+        self.inner.notify_heartbeat_missed(seconds_since_last_cycle).await;
+    }
+
+    async fn notify_time_warning(&self, token_mint: &str, token_symbol: &str, hours_remaining: i64) {
+        self.inner.notify_time_warning(token_mint, token_symbol, hours_remaining).await;
+    }
+
+    async fn notify_order_fired(&self, token_mint: &str, action: &str, order_description: &str, price: Decimal) {
+        self.inner.notify_order_fired(token_mint, action, order_description, price).await;
+    }
+
+    async fn notify_maintenance_mode(&self, active: bool, reason: &str) {
+        self.inner.notify_maintenance_mode(active, reason).await;
+    }
+
+    async fn notify_position_rolled_over(&self, token_mint: &str, token_symbol: &str, new_expires_at: DateTime<Utc>) {
+        self.inner.notify_position_rolled_over(token_mint, token_symbol, new_expires_at).await;
+    }
+}
+
+/// Tracks when the bot last completed a full analysis cycle and fires
+/// `Notifier::notify_heartbeat_missed` if too much time passes without one - the only way to
+/// notice a bot that's silently wedged (a panicked task, a hung RPC call) rather than merely
+/// idling between cycles.
+pub struct HeartbeatMonitor {
+    last_cycle_at: Mutex<Instant>,
+}
+
+impl HeartbeatMonitor {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self { last_cycle_at: Mutex::new(Instant::now()) })
+    }
+
+    /// Call once per completed analysis cycle to reset the clock `run` watches.
+    pub async fn record_cycle(&self) {
+        *self.last_cycle_at.lock().await = Instant::now();
+    }
+
+    /// Poll every `check_interval` and notify once `threshold` has elapsed since the last
+    /// recorded cycle. Keeps firing on every tick while the bot stays stuck, rather than once,
+    /// since a missed heartbeat is worth repeating until it's acknowledged by a new cycle.
+    pub fn run<N: Notifier + 'static>(
+        self: &Arc<Self>,
+        notifier: Arc<N>,
+        threshold: Duration,
+        check_interval: Duration,
+    ) {
+        let monitor = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(check_interval);
+            loop {
+                interval.tick().await;
+                let elapsed = monitor.last_cycle_at.lock().await.elapsed();
+                if elapsed >= threshold {
+                    notifier.notify_heartbeat_missed(elapsed.as_secs()).await;
+                }
+            }
+        });
+    }
+}
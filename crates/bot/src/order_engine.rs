@@ -0,0 +1,232 @@
+// Independent conditional-order book: limit/stop-loss/take-profit/trailing-stop orders keyed
+// by token mint, evaluated against whatever price source calls `evaluate` - decoupled from
+// `OpenPosition`/`ExitParams` so a bracket order can be placed (and can fire) without an open
+// position tracking the same mint, unlike the static `suggested_entry`/`suggested_exit` a
+// `ChartSignal` hands back once and never revisits. Mirrors `CandleStore`'s DashMap-per-mint
+// layout since both are concurrent, mutable, per-token live state owned by the bot crate.
+use dashmap::DashMap;
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::money::Money;
+
+/// What triggers a `ConditionalOrder`, and the threshold/percentage that defines it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderType {
+    /// Fires (buy) once price falls to or below `threshold`.
+    Limit { threshold: Money },
+    /// Fires (sell) once price falls to or below `threshold`.
+    StopLoss { threshold: Money },
+    /// Fires (sell) once price rises to or above `threshold`.
+    TakeProfit { threshold: Money },
+    /// Fires (sell) once price falls `trail_pct` below the high-water mark recorded since
+    /// the order was placed.
+    TrailingStop { trail_pct: f64 },
+}
+
+impl OrderType {
+    fn action(self) -> OrderAction {
+        match self {
+            OrderType::Limit { .. } => OrderAction::Buy,
+            OrderType::StopLoss { .. } | OrderType::TakeProfit { .. } | OrderType::TrailingStop { .. } => {
+                OrderAction::Sell
+            }
+        }
+    }
+
+    /// Short human label for logs/notifications, e.g. "stop-loss @ $0.0012" or
+    /// "trailing stop (15.0% trail)".
+    pub fn label(&self) -> String {
+        match self {
+            OrderType::Limit { threshold } => format!("limit buy @ ${}", threshold),
+            OrderType::StopLoss { threshold } => format!("stop-loss @ ${}", threshold),
+            OrderType::TakeProfit { threshold } => format!("take-profit @ ${}", threshold),
+            OrderType::TrailingStop { trail_pct } => format!("trailing stop ({:.1}% trail)", trail_pct),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderAction {
+    Buy,
+    Sell,
+}
+
+impl OrderAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OrderAction::Buy => "BUY",
+            OrderAction::Sell => "SELL",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ConditionalOrder {
+    id: u64,
+    order_type: OrderType,
+    /// Highest price seen since this order was placed - only consulted for
+    /// `OrderType::TrailingStop`.
+    high_water_mark: Money,
+}
+
+/// A `ConditionalOrder` that just crossed its trigger, returned by `OrderEngine::evaluate`.
+#[derive(Debug, Clone, Copy)]
+pub struct FiredOrder {
+    pub id: u64,
+    pub token_mint: Pubkey,
+    pub order_type: OrderType,
+    pub action: OrderAction,
+    pub price: Money,
+}
+
+/// Venue-agnostic book of pending conditional orders, keyed per token mint. Nothing in here
+/// knows about `PortfolioMonitor`/`OpenPosition` - the caller hands a `FiredOrder` off to
+/// whatever execution path (open/close a position, notify an operator) makes sense.
+pub struct OrderEngine {
+    orders: DashMap<Pubkey, Vec<ConditionalOrder>>,
+    next_id: AtomicU64,
+}
+
+impl OrderEngine {
+    pub fn new() -> Self {
+        Self { orders: DashMap::new(), next_id: AtomicU64::new(1) }
+    }
+
+    /// Place a new conditional order for `token_mint`, returning its id (for `cancel_order`).
+    pub fn place_order(&self, token_mint: Pubkey, order_type: OrderType) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.orders
+            .entry(token_mint)
+            .or_default()
+            .push(ConditionalOrder { id, order_type, high_water_mark: Money::ZERO });
+        id
+    }
+
+    /// Remove a pending order before it fires. Returns `false` if `id` wasn't found (already
+    /// fired, already cancelled, or never existed).
+    pub fn cancel_order(&self, token_mint: &Pubkey, id: u64) -> bool {
+        let Some(mut orders) = self.orders.get_mut(token_mint) else {
+            return false;
+        };
+        let before = orders.len();
+        orders.retain(|order| order.id != id);
+        orders.len() != before
+    }
+
+    /// Drop every pending order for `token_mint`, e.g. when a position is force-closed
+    /// out-of-band (a Telegram `/close` or `/sell_all`) and its bracket orders would
+    /// otherwise fire against a position that no longer exists.
+    pub fn cancel_all(&self, token_mint: &Pubkey) {
+        self.orders.remove(token_mint);
+    }
+
+    /// Token mints with at least one pending order - callers drive `evaluate` off this list
+    /// rather than the engine reaching out for prices itself.
+    pub fn tracked_mints(&self) -> Vec<Pubkey> {
+        self.orders
+            .iter()
+            .filter(|entry| !entry.value().is_empty())
+            .map(|entry| *entry.key())
+            .collect()
+    }
+
+    /// Check every pending order for `token_mint` against `price`, firing (and removing) any
+    /// that just crossed their trigger. Updates trailing-stop high-water marks along the way,
+    /// even for orders that don't fire this call.
+    pub fn evaluate(&self, token_mint: &Pubkey, price: Money) -> Vec<FiredOrder> {
+        let Some(mut orders) = self.orders.get_mut(token_mint) else {
+            return Vec::new();
+        };
+
+        let mut fired = Vec::new();
+        orders.retain_mut(|order| {
+            if let OrderType::TrailingStop { .. } = order.order_type {
+                if price > order.high_water_mark {
+                    order.high_water_mark = price;
+                }
+            }
+
+            let triggered = match order.order_type {
+                OrderType::Limit { threshold } | OrderType::StopLoss { threshold } => price <= threshold,
+                OrderType::TakeProfit { threshold } => price >= threshold,
+                OrderType::TrailingStop { trail_pct } => {
+                    let giveback = Decimal::from_f64_retain(1.0 - trail_pct / 100.0).unwrap_or(Decimal::ONE);
+                    match order.high_water_mark.checked_mul(giveback) {
+                        Some(stop) => price <= stop,
+                        None => false,
+                    }
+                }
+            };
+
+            if triggered {
+                fired.push(FiredOrder {
+                    id: order.id,
+                    token_mint: *token_mint,
+                    order_type: order.order_type,
+                    action: order.order_type.action(),
+                    price,
+                });
+            }
+
+            !triggered
+        });
+
+        fired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn money(value: &str) -> Money {
+        Money::new(Decimal::from_str(value).unwrap())
+    }
+
+    #[test]
+    fn stop_loss_fires_when_price_crosses_below_threshold() {
+        let engine = OrderEngine::new();
+        let mint = Pubkey::new_unique();
+        engine.place_order(mint, OrderType::StopLoss { threshold: money("1.0") });
+
+        assert!(engine.evaluate(&mint, money("1.5")).is_empty());
+
+        let fired = engine.evaluate(&mint, money("0.9"));
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].action, OrderAction::Sell);
+
+        // Fired orders are removed - re-evaluating at the same price doesn't fire again.
+        assert!(engine.evaluate(&mint, money("0.9")).is_empty());
+    }
+
+    #[test]
+    fn trailing_stop_fires_off_the_high_water_mark_not_the_entry_price() {
+        let engine = OrderEngine::new();
+        let mint = Pubkey::new_unique();
+        engine.place_order(mint, OrderType::TrailingStop { trail_pct: 20.0 });
+
+        assert!(engine.evaluate(&mint, money("1.0")).is_empty());
+        assert!(engine.evaluate(&mint, money("2.0")).is_empty());
+
+        // 20% below the $2.00 high, not the $1.00 entry - $1.70 shouldn't fire yet.
+        assert!(engine.evaluate(&mint, money("1.70")).is_empty());
+
+        let fired = engine.evaluate(&mint, money("1.59"));
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].action, OrderAction::Sell);
+    }
+
+    #[test]
+    fn cancel_order_removes_a_pending_order() {
+        let engine = OrderEngine::new();
+        let mint = Pubkey::new_unique();
+        let id = engine.place_order(mint, OrderType::TakeProfit { threshold: money("2.0") });
+
+        assert!(engine.cancel_order(&mint, id));
+        assert!(engine.evaluate(&mint, money("5.0")).is_empty());
+        assert!(!engine.cancel_order(&mint, id));
+    }
+}
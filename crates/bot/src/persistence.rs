@@ -1,12 +1,18 @@
 use anyhow::Result;
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tracing::{info, warn};
+use trading_db::statistics::StatisticsRepository;
+use trading_db::trades::{ClosedPositionRecord, TradeRepository};
 
+use std::collections::HashMap;
+
+use crate::money::Money;
 use crate::portfolio_monitor::{ClosedTrade, DailyStats};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +34,11 @@ pub struct SerializableClosedTrade {
     pub pnl_pct: f64,
     pub hold_time_minutes: i64,
     pub is_win: bool,
+    /// `ExitTrigger::as_str()` at the time this trade closed, e.g. `"stop_loss"` -
+    /// kept as a plain string rather than the enum itself so old history files without
+    /// this field still deserialize (`#[serde(default)]` below).
+    #[serde(default)]
+    pub exit_reason: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,6 +108,7 @@ impl TradeHistory {
             pnl_pct: trade.pnl_pct,
             hold_time_minutes: trade.hold_time_minutes,
             is_win: trade.is_win,
+            exit_reason: trade.trigger.as_str().to_string(),
         });
 
         self.last_updated = Utc::now();
@@ -131,18 +143,41 @@ impl TradeHistory {
         (wins as f64 / self.closed_trades.len() as f64) * 100.0
     }
 
+    /// Trade count, win rate, and total PnL grouped by `exit_reason` (e.g. `"stop_loss"` vs
+    /// `"time_exit"`), so `print_summary` - and any dashboard consuming this - can tell
+    /// whether the bot's edge is coming from take-profits or just getting lucky on trailing
+    /// stops. Trades with no reason recorded (history predating this field) are skipped.
+    pub fn get_stats_by_exit_reason(&self) -> HashMap<String, ExitReasonStats> {
+        let mut by_reason: HashMap<String, ExitReasonStats> = HashMap::new();
+
+        for trade in &self.closed_trades {
+            if trade.exit_reason.is_empty() {
+                continue;
+            }
+
+            let pnl = Money::from_str(&trade.pnl).unwrap_or(Money::ZERO).as_decimal();
+            let entry = by_reason.entry(trade.exit_reason.clone()).or_default();
+            entry.trade_count += 1;
+            entry.wins += trade.is_win as u32;
+            entry.total_pnl += pnl;
+        }
+
+        by_reason
+    }
+
     pub fn get_total_pnl(&self) -> Decimal {
         self.closed_trades
             .iter()
-            .filter_map(|t| Decimal::from_str_exact(&t.pnl).ok())
+            .filter_map(|t| Money::from_str(&t.pnl))
+            .map(Money::as_decimal)
             .sum()
     }
 
     pub fn get_best_trades(&self, limit: usize) -> Vec<&SerializableClosedTrade> {
         let mut trades = self.closed_trades.iter().collect::<Vec<_>>();
         trades.sort_by(|a, b| {
-            let a_pnl = Decimal::from_str_exact(&a.pnl).unwrap_or(Decimal::ZERO);
-            let b_pnl = Decimal::from_str_exact(&b.pnl).unwrap_or(Decimal::ZERO);
+            let a_pnl = Money::from_str(&a.pnl).unwrap_or(Money::ZERO);
+            let b_pnl = Money::from_str(&b.pnl).unwrap_or(Money::ZERO);
             b_pnl.cmp(&a_pnl)
         });
         trades.into_iter().take(limit).collect()
@@ -151,13 +186,65 @@ impl TradeHistory {
     pub fn get_worst_trades(&self, limit: usize) -> Vec<&SerializableClosedTrade> {
         let mut trades = self.closed_trades.iter().collect::<Vec<_>>();
         trades.sort_by(|a, b| {
-            let a_pnl = Decimal::from_str_exact(&a.pnl).unwrap_or(Decimal::ZERO);
-            let b_pnl = Decimal::from_str_exact(&b.pnl).unwrap_or(Decimal::ZERO);
+            let a_pnl = Money::from_str(&a.pnl).unwrap_or(Money::ZERO);
+            let b_pnl = Money::from_str(&b.pnl).unwrap_or(Money::ZERO);
             a_pnl.cmp(&b_pnl)
         });
         trades.into_iter().take(limit).collect()
     }
 
+    /// Risk-adjusted view of the trade log: max drawdown, Sharpe ratio, and the `pnl_pct`
+    /// distribution. `None` in place of the whole struct once there's fewer than
+    /// `MIN_SAMPLES` closed trades - with too few samples, stddev and percentiles are just
+    /// noise dressed up as a metric.
+    pub fn compute_risk_metrics(&self) -> Option<RiskMetrics> {
+        const MIN_SAMPLES: usize = 5;
+
+        if self.closed_trades.len() < MIN_SAMPLES {
+            return None;
+        }
+
+        let mut by_exit_time = self.closed_trades.iter().collect::<Vec<_>>();
+        by_exit_time.sort_by_key(|t| t.exit_time);
+
+        let starting_value = Money::from_str(&self.daily_stats.starting_value).unwrap_or(Money::ZERO).as_decimal();
+
+        let mut equity = starting_value;
+        let mut peak = starting_value;
+        let mut max_drawdown = Decimal::ZERO;
+
+        for trade in &by_exit_time {
+            let pnl = Money::from_str(&trade.pnl).unwrap_or(Money::ZERO).as_decimal();
+            equity += pnl;
+            peak = peak.max(equity);
+
+            if !peak.is_zero() {
+                let drawdown = (peak - equity) / peak;
+                max_drawdown = max_drawdown.max(drawdown);
+            }
+        }
+
+        let returns: Vec<f64> = self.closed_trades.iter().map(|t| t.pnl_pct).collect();
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / returns.len() as f64;
+        let stddev = variance.sqrt();
+        let sharpe_ratio = if stddev == 0.0 { 0.0 } else { mean / stddev };
+
+        let mut sorted_returns = returns;
+        sorted_returns.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let percentile = |pct: usize| sorted_returns[sorted_returns.len() * pct / 100];
+
+        Some(RiskMetrics {
+            max_drawdown_pct: max_drawdown * Decimal::from(100),
+            sharpe_ratio,
+            pnl_pct_p10: percentile(10),
+            pnl_pct_p25: percentile(25),
+            pnl_pct_p50: percentile(50),
+            pnl_pct_p75: percentile(75),
+            pnl_pct_p90: percentile(90),
+        })
+    }
+
     pub fn print_summary(&self) {
         println!("\n╔═══════════════════════════════════════════════════════════╗");
         println!("║                  TRADE HISTORY SUMMARY                     ║");
@@ -165,6 +252,29 @@ impl TradeHistory {
         println!("║ Total Trades: {}", self.get_total_trades());
         println!("║ Win Rate: {:.1}%", self.get_win_rate());
         println!("║ Total PnL: ${:.2}", self.get_total_pnl());
+
+        if let Some(risk) = self.compute_risk_metrics() {
+            println!("╠═══════════════════════════════════════════════════════════╣");
+            println!("║ Max Drawdown: {:.2}%", risk.max_drawdown_pct);
+            println!("║ Sharpe Ratio: {:.2}", risk.sharpe_ratio);
+            println!(
+                "║ PnL% Distribution: p10={:.1} p25={:.1} p50={:.1} p75={:.1} p90={:.1}",
+                risk.pnl_pct_p10, risk.pnl_pct_p25, risk.pnl_pct_p50, risk.pnl_pct_p75, risk.pnl_pct_p90
+            );
+        }
+
+        let by_reason = self.get_stats_by_exit_reason();
+        if !by_reason.is_empty() {
+            println!("╠═══════════════════════════════════════════════════════════╣");
+            println!("║ By Exit Reason:");
+            for (reason, stats) in &by_reason {
+                println!(
+                    "║  {}: {} trades | {:.1}% win rate | ${:.2} PnL",
+                    reason, stats.trade_count, stats.win_rate(), stats.total_pnl
+                );
+            }
+        }
+
         println!("╠═══════════════════════════════════════════════════════════╣");
         println!("║ Best Trades:");
 
@@ -180,3 +290,212 @@ impl TradeHistory {
         println!("╚═══════════════════════════════════════════════════════════╝\n");
     }
 }
+
+/// One exit reason's slice of [`TradeHistory::get_stats_by_exit_reason`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExitReasonStats {
+    pub trade_count: u32,
+    pub wins: u32,
+    pub total_pnl: Decimal,
+}
+
+impl ExitReasonStats {
+    pub fn win_rate(&self) -> f64 {
+        if self.trade_count == 0 {
+            return 0.0;
+        }
+        (self.wins as f64 / self.trade_count as f64) * 100.0
+    }
+}
+
+/// Downside-focused companion to `get_win_rate`/`get_total_pnl`, from
+/// [`TradeHistory::compute_risk_metrics`].
+#[derive(Debug, Clone, Copy)]
+pub struct RiskMetrics {
+    pub max_drawdown_pct: Decimal,
+    pub sharpe_ratio: f64,
+    pub pnl_pct_p10: f64,
+    pub pnl_pct_p25: f64,
+    pub pnl_pct_p50: f64,
+    pub pnl_pct_p75: f64,
+    pub pnl_pct_p90: f64,
+}
+
+/// Pluggable persistence for closed trades and daily stats, so the trading loop can be
+/// pointed at a single JSON file ([`JsonFileStore`]) or a shared Postgres/SQLite database
+/// ([`SqlHistoryStore`]) without caring which. `get_best_trades`/`get_worst_trades` return
+/// owned [`SerializableClosedTrade`]s either way - `JsonFileStore` already holds them in
+/// memory, and `SqlHistoryStore` converts each `ClosedPositionRecord` row into one, so callers
+/// don't need to know which backend they're talking to.
+#[async_trait]
+pub trait HistoryStore: Send + Sync {
+    async fn add_closed_trade(&mut self, trade: &ClosedTrade) -> Result<()>;
+    async fn update_daily_stats(&mut self, stats: &DailyStats) -> Result<()>;
+    async fn get_total_trades(&self) -> Result<usize>;
+    async fn get_win_rate(&self) -> Result<f64>;
+    async fn get_total_pnl(&self) -> Result<Decimal>;
+    async fn get_best_trades(&self, limit: usize) -> Result<Vec<SerializableClosedTrade>>;
+    async fn get_worst_trades(&self, limit: usize) -> Result<Vec<SerializableClosedTrade>>;
+}
+
+/// The original single-file JSON backend, wrapped behind [`HistoryStore`]. Every write
+/// rewrites `path` in full - fine at the trade volumes this bot has always run at, but the
+/// reason [`SqlHistoryStore`] exists for anyone who outgrows it.
+pub struct JsonFileStore {
+    history: TradeHistory,
+    path: PathBuf,
+}
+
+impl JsonFileStore {
+    pub fn open(path: PathBuf, starting_value: Decimal) -> Result<Self> {
+        let history = if path.exists() {
+            TradeHistory::load(&path)?
+        } else {
+            TradeHistory::new(starting_value)
+        };
+
+        Ok(Self { history, path })
+    }
+}
+
+#[async_trait]
+impl HistoryStore for JsonFileStore {
+    async fn add_closed_trade(&mut self, trade: &ClosedTrade) -> Result<()> {
+        self.history.add_closed_trade(trade);
+        self.history.save(&self.path)
+    }
+
+    async fn update_daily_stats(&mut self, stats: &DailyStats) -> Result<()> {
+        self.history.update_daily_stats(stats);
+        self.history.save(&self.path)
+    }
+
+    async fn get_total_trades(&self) -> Result<usize> {
+        Ok(self.history.get_total_trades())
+    }
+
+    async fn get_win_rate(&self) -> Result<f64> {
+        Ok(self.history.get_win_rate())
+    }
+
+    async fn get_total_pnl(&self) -> Result<Decimal> {
+        Ok(self.history.get_total_pnl())
+    }
+
+    async fn get_best_trades(&self, limit: usize) -> Result<Vec<SerializableClosedTrade>> {
+        Ok(self.history.get_best_trades(limit).into_iter().cloned().collect())
+    }
+
+    async fn get_worst_trades(&self, limit: usize) -> Result<Vec<SerializableClosedTrade>> {
+        Ok(self.history.get_worst_trades(limit).into_iter().cloned().collect())
+    }
+}
+
+/// Postgres/SQLite-backed `HistoryStore`, built on `trading_db`'s existing `AnyPool`
+/// repositories rather than a new connection of its own - `TradeRepository`/
+/// `StatisticsRepository` are already the dialect-agnostic layer the rest of the codebase
+/// uses for `positions`/`daily_stats`. `add_closed_trade` is a single `INSERT` per call
+/// (via `TradeRepository::save_closed_position`), so history grows incrementally instead of
+/// being rewritten wholesale like `JsonFileStore`.
+///
+/// `entry_mc`/`amount`/`stop_loss`/`take_profit` aren't part of `ClosedTrade` (they only matter
+/// while a position is open), so all four are recorded as zero here - the same convention
+/// `crate::db::Database::insert_trade` already uses for the rusqlite-backed `positions` table.
+/// `exit_reason` *is* tracked on `ClosedTrade` (as `trigger`) and is passed through as-is.
+///
+/// Note on sqlx offline mode: this crate deliberately queries through `sqlx::query()` (not
+/// the `query!` macros) everywhere, specifically so `AnyPool` can erase over SQLite vs
+/// Postgres at runtime - see `trading_db::backend`. That means there's no `query!`-derived
+/// compile-time check for offline mode to cache in the first place, and generating a
+/// `.sqlx` directory here would need a live database connection this environment doesn't
+/// have. It's deliberately not included.
+pub struct SqlHistoryStore {
+    trades: TradeRepository,
+    stats: StatisticsRepository,
+}
+
+impl SqlHistoryStore {
+    pub fn new(trades: TradeRepository, stats: StatisticsRepository) -> Self {
+        Self { trades, stats }
+    }
+
+    fn to_serializable(record: ClosedPositionRecord) -> SerializableClosedTrade {
+        SerializableClosedTrade {
+            token_mint: record.token_mint,
+            token_symbol: record.token_symbol,
+            entry_time: record.entry_time,
+            exit_time: record.exit_time,
+            entry_price: record.entry_price.to_string(),
+            exit_price: record.exit_price.to_string(),
+            pnl: record.pnl.to_string(),
+            pnl_pct: record.pnl_pct,
+            hold_time_minutes: record.hold_time_minutes,
+            is_win: record.pnl.is_sign_positive(),
+            exit_reason: record.exit_reason,
+        }
+    }
+}
+
+#[async_trait]
+impl HistoryStore for SqlHistoryStore {
+    async fn add_closed_trade(&mut self, trade: &ClosedTrade) -> Result<()> {
+        self.trades
+            .save_closed_position(
+                &trade.token_mint,
+                &trade.token_symbol,
+                trade.entry_time,
+                trade.entry_price.as_decimal(),
+                Decimal::ZERO, // entry_mc: unknown once a position has closed
+                Decimal::ZERO, // amount: unknown once a position has closed
+                Decimal::ZERO, // stop_loss: only meaningful while the position is open
+                Decimal::ZERO, // take_profit: only meaningful while the position is open
+                trade.exit_time,
+                trade.exit_price.as_decimal(),
+                trade.trigger.as_str(),
+                trade.pnl.as_decimal(),
+                trade.pnl_pct,
+                trade.hold_time_minutes,
+            )
+            .await
+    }
+
+    async fn update_daily_stats(&mut self, stats: &DailyStats) -> Result<()> {
+        self.stats
+            .update_daily_stats(
+                Utc::now().date_naive(),
+                stats.total_trades as i64,
+                stats.wins as i64,
+                stats.losses as i64,
+                stats.win_rate,
+                stats.total_pnl.as_decimal(),
+                stats.biggest_win.as_decimal(),
+                stats.biggest_loss.as_decimal(),
+                stats.avg_win.as_decimal(),
+                stats.avg_loss.as_decimal(),
+                stats.portfolio_value.as_decimal(),
+            )
+            .await
+    }
+
+    async fn get_total_trades(&self) -> Result<usize> {
+        Ok(self.trades.get_closed_trade_count().await? as usize)
+    }
+
+    async fn get_win_rate(&self) -> Result<f64> {
+        self.trades.get_win_rate().await
+    }
+
+    async fn get_total_pnl(&self) -> Result<Decimal> {
+        self.trades.get_total_pnl().await
+    }
+
+    async fn get_best_trades(&self, limit: usize) -> Result<Vec<SerializableClosedTrade>> {
+        let records = self.trades.get_best_trades(limit as i64).await?;
+        Ok(records.into_iter().map(Self::to_serializable).collect())
+    }
+
+    async fn get_worst_trades(&self, limit: usize) -> Result<Vec<SerializableClosedTrade>> {
+        let records = self.trades.get_worst_trades(limit as i64).await?;
+        Ok(records.into_iter().map(Self::to_serializable).collect())
+    }
+}
@@ -3,22 +3,122 @@ use solana_sdk::pubkey::Pubkey;
 use rust_decimal::Decimal;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::money::Money;
+
+/// Why a position is being closed, so `PortfolioMonitor` can publish the matching
+/// `PortfolioEvent` variant instead of a single generic "position closed" event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitTrigger {
+    StopLoss,
+    TakeProfit,
+    TrailingStop,
+    TimeExit,
+    Manual,
+}
+
+impl ExitTrigger {
+    /// Stable lowercase label, used wherever the trigger is persisted or grouped on (trade
+    /// history's exit-type breakdown, the `positions.exit_reason` column) instead of each
+    /// call site formatting the enum's `Debug` output itself.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitTrigger::StopLoss => "stop_loss",
+            ExitTrigger::TakeProfit => "take_profit",
+            ExitTrigger::TrailingStop => "trailing_stop",
+            ExitTrigger::TimeExit => "time_exit",
+            ExitTrigger::Manual => "manual",
+        }
+    }
+
+    /// Inverse of `as_str`, for reloading a persisted `exit_reason` column. Falls back to
+    /// `Manual` for an unrecognized or missing value (e.g. a row closed before this column
+    /// was populated) rather than failing the whole load.
+    pub fn from_str(value: &str) -> Self {
+        match value {
+            "stop_loss" => ExitTrigger::StopLoss,
+            "take_profit" => ExitTrigger::TakeProfit,
+            "trailing_stop" => ExitTrigger::TrailingStop,
+            "time_exit" => ExitTrigger::TimeExit,
+            _ => ExitTrigger::Manual,
+        }
+    }
+}
+
+/// Typed portfolio state changes, published over a broadcast channel so the console
+/// logger, the dashboard SSE stream, and an optional webhook can all react without
+/// `PortfolioMonitor` knowing any of them exist.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PortfolioEvent {
+    PositionOpened {
+        token_mint: Pubkey,
+        token_symbol: String,
+        entry_price: Money,
+        amount: Money,
+    },
+    PositionClosed {
+        token_mint: Pubkey,
+        token_symbol: String,
+        pnl: Money,
+        pnl_pct: f64,
+        is_win: bool,
+    },
+    StopLossHit {
+        token_mint: Pubkey,
+        token_symbol: String,
+        price: Money,
+    },
+    TakeProfitHit {
+        token_mint: Pubkey,
+        token_symbol: String,
+        price: Money,
+    },
+    TrailingStopHit {
+        token_mint: Pubkey,
+        token_symbol: String,
+        price: Money,
+    },
+    TimeExit {
+        token_mint: Pubkey,
+        token_symbol: String,
+        hold_time_minutes: i64,
+    },
+    BigWin {
+        token_mint: Pubkey,
+        token_symbol: String,
+        pnl: Money,
+    },
+    PositionRolledOver {
+        token_mint: Pubkey,
+        token_symbol: String,
+        new_expires_at: DateTime<Utc>,
+    },
+}
 
 #[derive(Debug, Clone)]
 pub struct OpenPosition {
     pub token_mint: Pubkey,
     pub token_symbol: String,
     pub entry_time: DateTime<Utc>,
-    pub entry_price: Decimal,
-    pub entry_mc: Decimal,
-    pub amount: Decimal,
-    pub current_price: Decimal,
-    pub current_mc: Decimal,
-    pub unrealized_pnl: Decimal,
+    pub entry_price: Money,
+    pub entry_mc: Money,
+    pub amount: Money,
+    pub current_price: Money,
+    pub current_mc: Money,
+    pub unrealized_pnl: Money,
     pub unrealized_pnl_pct: f64,
-    pub stop_loss: Decimal,
-    pub take_profit: Decimal,
+    pub stop_loss: Money,
+    pub take_profit: Money,
+    /// Highest `current_price` observed since entry, monotonically non-decreasing.
+    /// Drives the trailing stop in `PositionManager::should_exit_position_price_only`
+    /// instead of the static `take_profit` target.
+    pub peak_price: Money,
     pub hold_time_minutes: i64,
+    /// When this position is due for `PositionManager`'s expiry check - computed once at
+    /// open time (`entry_time` plus the hold window for the strategy mode active then) and
+    /// only ever pushed forward by a rollover, never shortened.
+    pub expires_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone)]
@@ -27,12 +127,13 @@ pub struct ClosedTrade {
     pub token_symbol: String,
     pub entry_time: DateTime<Utc>,
     pub exit_time: DateTime<Utc>,
-    pub entry_price: Decimal,
-    pub exit_price: Decimal,
-    pub pnl: Decimal,
+    pub entry_price: Money,
+    pub exit_price: Money,
+    pub pnl: Money,
     pub pnl_pct: f64,
     pub hold_time_minutes: i64,
     pub is_win: bool,
+    pub trigger: ExitTrigger,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -41,13 +142,13 @@ pub struct DailyStats {
     pub wins: u32,
     pub losses: u32,
     pub win_rate: f64,
-    pub total_pnl: Decimal,
-    pub biggest_win: Decimal,
-    pub biggest_loss: Decimal,
-    pub avg_win: Decimal,
-    pub avg_loss: Decimal,
-    pub portfolio_value: Decimal,
-    pub starting_value: Decimal,
+    pub total_pnl: Money,
+    pub biggest_win: Money,
+    pub biggest_loss: Money,
+    pub avg_win: Money,
+    pub avg_loss: Money,
+    pub portfolio_value: Money,
+    pub starting_value: Money,
 }
 
 impl DailyStats {
@@ -60,10 +161,13 @@ pub struct PortfolioMonitor {
     pub(crate) positions: HashMap<Pubkey, OpenPosition>,
     closed_trades: Vec<ClosedTrade>,
     daily_stats: DailyStats,
+    events: broadcast::Sender<PortfolioEvent>,
 }
 
 impl PortfolioMonitor {
     pub fn new(starting_capital: Decimal) -> Self {
+        let starting_capital = Money::new(starting_capital);
+        let (events, _) = broadcast::channel(256);
         Self {
             positions: HashMap::new(),
             closed_trades: vec![],
@@ -72,6 +176,7 @@ impl PortfolioMonitor {
                 portfolio_value: starting_capital,
                 ..Default::default()
             },
+            events,
         }
     }
 
@@ -83,42 +188,76 @@ impl PortfolioMonitor {
         self.closed_trades.last()
     }
 
+    /// Subscribe to portfolio state changes (position opens/closes, exit triggers).
+    /// Each subscriber gets its own receiver, so the console logger, the dashboard's
+    /// SSE stream, and a webhook notifier can all listen independently.
+    pub fn subscribe(&self) -> broadcast::Receiver<PortfolioEvent> {
+        self.events.subscribe()
+    }
+
+    /// Clone of the sender side, for handing to things (like the dashboard) that need
+    /// to mint their own receiver per-connection rather than holding one long-lived.
+    pub fn events_sender(&self) -> broadcast::Sender<PortfolioEvent> {
+        self.events.clone()
+    }
+
+    /// Seed an open position loaded from disk at startup, without broadcasting
+    /// `PositionOpened` - the position was already opened in a prior run.
+    pub fn restore_position(&mut self, position: OpenPosition) {
+        self.positions.insert(position.token_mint, position);
+    }
+
+    /// Seed today's daily stats loaded from disk at startup.
+    pub fn restore_daily_stats(&mut self, stats: DailyStats) {
+        self.daily_stats = stats;
+    }
+
     /// Add new position
     pub fn open_position(&mut self, position: OpenPosition) {
-        println!("\n┌─ NEW POSITION ────────────────────");
-        println!("│ Token: {}", position.token_symbol);
-        println!("│ Entry: ${:.6}", position.entry_price);
-        println!("│ Amount: ${:.2}", position.amount);
-        println!("│ Stop Loss: ${:.6} ({:.1}%)",
-            position.stop_loss,
-            ((position.stop_loss - position.entry_price) / position.entry_price * Decimal::from(100))
-                .to_string().parse::<f64>().unwrap_or(0.0)
-        );
-        println!("│ Take Profit: ${:.6} ({:.1}%)",
-            position.take_profit,
-            ((position.take_profit - position.entry_price) / position.entry_price * Decimal::from(100))
-                .to_string().parse::<f64>().unwrap_or(0.0)
-        );
-        println!("└───────────────────────────────────\n");
+        let _ = self.events.send(PortfolioEvent::PositionOpened {
+            token_mint: position.token_mint,
+            token_symbol: position.token_symbol.clone(),
+            entry_price: position.entry_price,
+            amount: position.amount,
+        });
 
         self.positions.insert(position.token_mint, position);
     }
 
-    /// Close position
+    /// Push a position's `expires_at` out to `new_expires_at` instead of closing it - called
+    /// when `PositionManager` re-evaluates an expired position against fresh chart data and
+    /// the signal still supports holding. Publishes `PositionRolledOver` to event subscribers
+    /// (the Telegram alert itself is sent by the caller of `check_and_update_positions`, from
+    /// its returned rollovers); a no-op if the position already closed.
+    pub fn extend_expiry(&mut self, token_mint: &Pubkey, new_expires_at: DateTime<Utc>) {
+        if let Some(position) = self.positions.get_mut(token_mint) {
+            position.expires_at = new_expires_at;
+            let _ = self.events.send(PortfolioEvent::PositionRolledOver {
+                token_mint: *token_mint,
+                token_symbol: position.token_symbol.clone(),
+                new_expires_at,
+            });
+        }
+    }
+
+    /// Close position, publishing the `PortfolioEvent` variant matching `trigger`
+    /// (or a generic `PositionClosed` for a manual close) instead of printing directly.
     pub fn close_position(
         &mut self,
         token_mint: &Pubkey,
         exit_price: Decimal,
+        trigger: ExitTrigger,
     ) -> Option<ClosedTrade> {
         if let Some(position) = self.positions.remove(token_mint) {
+            let exit_price = Money::new(exit_price);
             let exit_time = Utc::now();
-            let pnl = (exit_price - position.entry_price) * position.amount / position.entry_price;
-            let pnl_pct = ((exit_price - position.entry_price) / position.entry_price * Decimal::from(100))
-                .to_string()
-                .parse::<f64>()
-                .unwrap_or(0.0);
+            let pnl = Money::new(
+                (exit_price.as_decimal() - position.entry_price.as_decimal()) * position.amount.as_decimal()
+                    / position.entry_price.as_decimal(),
+            );
+            let pnl_pct = Money::pct_change(position.entry_price, exit_price);
             let hold_time = (exit_time - position.entry_time).num_minutes();
-            let is_win = pnl > Decimal::ZERO;
+            let is_win = pnl > Money::ZERO;
 
             let trade = ClosedTrade {
                 token_mint: *token_mint,
@@ -131,10 +270,48 @@ impl PortfolioMonitor {
                 pnl_pct,
                 hold_time_minutes: hold_time,
                 is_win,
+                trigger,
+            };
+
+            // Publish the exit-reason-specific event (or a generic close for a manual exit)
+            let close_event = match trigger {
+                ExitTrigger::StopLoss => PortfolioEvent::StopLossHit {
+                    token_mint: trade.token_mint,
+                    token_symbol: trade.token_symbol.clone(),
+                    price: trade.exit_price,
+                },
+                ExitTrigger::TakeProfit => PortfolioEvent::TakeProfitHit {
+                    token_mint: trade.token_mint,
+                    token_symbol: trade.token_symbol.clone(),
+                    price: trade.exit_price,
+                },
+                ExitTrigger::TrailingStop => PortfolioEvent::TrailingStopHit {
+                    token_mint: trade.token_mint,
+                    token_symbol: trade.token_symbol.clone(),
+                    price: trade.exit_price,
+                },
+                ExitTrigger::TimeExit => PortfolioEvent::TimeExit {
+                    token_mint: trade.token_mint,
+                    token_symbol: trade.token_symbol.clone(),
+                    hold_time_minutes: trade.hold_time_minutes,
+                },
+                ExitTrigger::Manual => PortfolioEvent::PositionClosed {
+                    token_mint: trade.token_mint,
+                    token_symbol: trade.token_symbol.clone(),
+                    pnl: trade.pnl,
+                    pnl_pct: trade.pnl_pct,
+                    is_win: trade.is_win,
+                },
             };
+            let _ = self.events.send(close_event);
 
-            // Print close notification
-            self.print_trade_closed(&trade);
+            if pnl > Money::new(Decimal::from(5)) {
+                let _ = self.events.send(PortfolioEvent::BigWin {
+                    token_mint: trade.token_mint,
+                    token_symbol: trade.token_symbol.clone(),
+                    pnl,
+                });
+            }
 
             // Update stats
             self.daily_stats.total_trades += 1;
@@ -171,15 +348,18 @@ impl PortfolioMonitor {
     pub fn update_prices(&mut self, prices: &HashMap<Pubkey, (Decimal, Decimal)>) {
         for (token_mint, position) in self.positions.iter_mut() {
             if let Some((current_price, current_mc)) = prices.get(token_mint) {
-                position.current_price = *current_price;
-                position.current_mc = *current_mc;
-                position.unrealized_pnl = (*current_price - position.entry_price)
-                    * position.amount / position.entry_price;
-                position.unrealized_pnl_pct = ((*current_price - position.entry_price)
-                    / position.entry_price * Decimal::from(100))
-                    .to_string()
-                    .parse::<f64>()
-                    .unwrap_or(0.0);
+                let current_price = Money::new(*current_price);
+                position.current_price = current_price;
+                position.current_mc = Money::new(*current_mc);
+                position.unrealized_pnl = Money::new(
+                    (current_price.as_decimal() - position.entry_price.as_decimal())
+                        * position.amount.as_decimal()
+                        / position.entry_price.as_decimal(),
+                );
+                position.unrealized_pnl_pct = Money::pct_change(position.entry_price, current_price);
+                if current_price > position.peak_price {
+                    position.peak_price = current_price;
+                }
                 position.hold_time_minutes = (Utc::now() - position.entry_time).num_minutes();
             }
         }
@@ -193,9 +373,11 @@ impl PortfolioMonitor {
         println!("║ Portfolio Value: ${:.2}", self.daily_stats.portfolio_value);
         println!("║ Daily PnL: ${:.2} ({:.1}%)",
             self.daily_stats.total_pnl,
-            if self.daily_stats.starting_value > Decimal::ZERO {
-                ((self.daily_stats.total_pnl / self.daily_stats.starting_value) * Decimal::from(100))
-                    .to_string().parse::<f64>().unwrap_or(0.0)
+            if self.daily_stats.starting_value > Money::ZERO {
+                Money::pct_change(
+                    self.daily_stats.starting_value,
+                    self.daily_stats.starting_value + self.daily_stats.total_pnl,
+                )
             } else { 0.0 }
         );
         println!("║ Win Rate: {}/{} ({:.1}%)",
@@ -228,26 +410,6 @@ impl PortfolioMonitor {
         println!("╚═══════════════════════════════════════════════════════════╝\n");
     }
 
-    /// Print trade closed notification
-    fn print_trade_closed(&self, trade: &ClosedTrade) {
-        let emoji = if trade.is_win { "✅" } else { "❌" };
-        let result = if trade.is_win { "WIN" } else { "LOSS" };
-
-        println!("\n┌─ TRADE CLOSED ────────────────────");
-        println!("│ {} {}", emoji, result);
-        println!("│ Token: {}", trade.token_symbol);
-        println!("│ Entry: ${:.6}", trade.entry_price);
-        println!("│ Exit: ${:.6}", trade.exit_price);
-        println!("│ PnL: ${:.2} ({:.1}%)", trade.pnl, trade.pnl_pct);
-        println!("│ Hold Time: {} min", trade.hold_time_minutes);
-        println!("└───────────────────────────────────\n");
-
-        // Big win celebration
-        if trade.pnl > Decimal::from(5) {
-            println!("🎉 BIG WIN! ${:.2} profit! 🎉\n", trade.pnl);
-        }
-    }
-
     /// Print summary report
     pub fn print_summary(&self) {
         println!("\n╔═══════════════════════════════════════════════════════════╗");
@@ -258,9 +420,11 @@ impl PortfolioMonitor {
         println!("║ Win Rate: {:.1}%", self.daily_stats.win_rate);
         println!("║ Total PnL: ${:.2}", self.daily_stats.total_pnl);
         println!("║ ROI: {:.1}%",
-            if self.daily_stats.starting_value > Decimal::ZERO {
-                ((self.daily_stats.total_pnl / self.daily_stats.starting_value) * Decimal::from(100))
-                    .to_string().parse::<f64>().unwrap_or(0.0)
+            if self.daily_stats.starting_value > Money::ZERO {
+                Money::pct_change(
+                    self.daily_stats.starting_value,
+                    self.daily_stats.starting_value + self.daily_stats.total_pnl,
+                )
             } else { 0.0 }
         );
         println!("╠═══════════════════════════════════════════════════════════╣");
@@ -279,3 +443,52 @@ impl PortfolioMonitor {
         println!("╚═══════════════════════════════════════════════════════════╝\n");
     }
 }
+
+/// Console subscriber: renders the same boxes `open_position`/`close_position` used to
+/// print directly, but driven by `PortfolioEvent`s instead of being called inline. Run
+/// this as a background task alongside any other subscriber (dashboard SSE, webhook).
+pub async fn run_console_event_logger(mut events: broadcast::Receiver<PortfolioEvent>) {
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        match event {
+            PortfolioEvent::PositionOpened { token_symbol, entry_price, amount, .. } => {
+                println!("\n┌─ NEW POSITION ────────────────────");
+                println!("│ Token: {}", token_symbol);
+                println!("│ Entry: ${:.6}", entry_price);
+                println!("│ Amount: ${:.2}", amount);
+                println!("└───────────────────────────────────\n");
+            }
+            PortfolioEvent::PositionClosed { token_symbol, pnl, pnl_pct, is_win } => {
+                let (emoji, result) = if is_win { ("✅", "WIN") } else { ("❌", "LOSS") };
+                println!("\n┌─ TRADE CLOSED ────────────────────");
+                println!("│ {} {}", emoji, result);
+                println!("│ Token: {}", token_symbol);
+                println!("│ PnL: ${:.2} ({:.1}%)", pnl, pnl_pct);
+                println!("└───────────────────────────────────\n");
+            }
+            PortfolioEvent::StopLossHit { token_symbol, price, .. } => {
+                println!("\n🛑 Stop-loss hit for {} at ${:.6}", token_symbol, price);
+            }
+            PortfolioEvent::TakeProfitHit { token_symbol, price, .. } => {
+                println!("\n🎯 Take-profit hit for {} at ${:.6}", token_symbol, price);
+            }
+            PortfolioEvent::TrailingStopHit { token_symbol, price, .. } => {
+                println!("\n📈 Trailing stop hit for {} at ${:.6}", token_symbol, price);
+            }
+            PortfolioEvent::TimeExit { token_symbol, hold_time_minutes, .. } => {
+                println!("\n⏰ Time exit for {} after {} min", token_symbol, hold_time_minutes);
+            }
+            PortfolioEvent::BigWin { token_symbol, pnl, .. } => {
+                println!("🎉 BIG WIN on {}! ${:.2} profit! 🎉\n", token_symbol, pnl);
+            }
+            PortfolioEvent::PositionRolledOver { token_symbol, new_expires_at, .. } => {
+                println!("\n♻️  Rolled over {}, new expiry {}", token_symbol, new_expires_at.format("%Y-%m-%d %H:%M UTC"));
+            }
+        }
+    }
+}
@@ -1,13 +1,83 @@
 use std::collections::HashMap;
 use solana_sdk::pubkey::Pubkey;
 use rust_decimal::Decimal;
-use chrono::Utc;
+use chrono::{DateTime, Timelike, Utc};
 use tracing::{info, warn};
 use trading_core::*;
 use trading_data::*;
 use trading_analysis::*;
 
-use crate::portfolio_monitor::{OpenPosition, PortfolioMonitor};
+use crate::market_feed::MarketFeed;
+use crate::money::Money;
+use crate::portfolio_monitor::{ExitTrigger, OpenPosition, PortfolioMonitor};
+
+/// Configurable thresholds for `should_exit_position_price_only`'s time-exit and
+/// trailing-stop checks. Live trading starts from `ExitParams::default()`, with a few fields
+/// overridden from the environment; the backtester
+/// (`crate::backtest`) sweeps these to validate the defaults against history.
+#[derive(Debug, Clone, Copy)]
+pub struct ExitParams {
+    pub max_hold_hours: i64,
+    pub time_exit_pnl_pct: f64,
+    pub trailing_activation_pct: f64,
+    pub trailing_giveback_pct: f64,
+    /// Hard cutoff, unlike `max_hold_hours` (which only exits a position still below
+    /// `time_exit_pnl_pct`) - the `expires_at` window for a position opened under
+    /// `StrategyMode::SwingTrading`. Once `expires_at` passes, the position is closed
+    /// regardless of PnL (or, on the REST-fetch path, rolled over if its chart signal still
+    /// supports holding), so a paper position can't ride out a restart/backtest window indefinitely.
+    pub hard_max_hold_hours: i64,
+    /// If set, a position past `max_hold_hours` is also force-closed the next time the
+    /// wall clock crosses this UTC hour (0-23), rather than waiting for `hard_max_hold_hours` -
+    /// e.g. clearing out stale holds at a fixed "start of day" instead of only at the hard cap.
+    pub rollover_hour_utc: Option<u32>,
+    /// How many hours before `OpenPosition::expires_at` a position starts reporting as
+    /// `PositionManager::hours_until_forced_exit`, for an advance-warning notification.
+    pub warning_threshold_hours: i64,
+    /// `OpenPosition::expires_at` window for a position opened under `StrategyMode::Scalping` -
+    /// a scalp left open as long as a swing position isn't "still waiting it out", it's stuck,
+    /// so scalps get their own (much shorter) expiry instead of sharing `hard_max_hold_hours`.
+    pub scalping_max_hold_minutes: i64,
+}
+
+impl Default for ExitParams {
+    fn default() -> Self {
+        Self {
+            max_hold_hours: 24,
+            time_exit_pnl_pct: 5.0,
+            trailing_activation_pct: 30.0,
+            trailing_giveback_pct: 15.0,
+            hard_max_hold_hours: 48,
+            rollover_hour_utc: None,
+            warning_threshold_hours: 4,
+            scalping_max_hold_minutes: 20,
+        }
+    }
+}
+
+impl ExitParams {
+    /// `OpenPosition::expires_at` window for a position opened under `mode` - `DayTrading`
+    /// reuses the existing soft `max_hold_hours`, `SwingTrading` reuses `hard_max_hold_hours`
+    /// (unchanged defaults), so only `Scalping` needed a dedicated field above.
+    pub fn expiry_window(&self, mode: StrategyMode) -> chrono::Duration {
+        match mode {
+            StrategyMode::Scalping => chrono::Duration::minutes(self.scalping_max_hold_minutes),
+            StrategyMode::DayTrading => chrono::Duration::hours(self.max_hold_hours),
+            StrategyMode::SwingTrading => chrono::Duration::hours(self.hard_max_hold_hours),
+        }
+    }
+}
+
+/// Result of re-evaluating an open position against a freshly fetched `Token`.
+#[derive(Debug, Clone, Copy)]
+enum PositionOutcome {
+    /// Close the position now, for the given reason.
+    Exit(ExitTrigger),
+    /// Keep the position open, pushing `expires_at` out to the given time instead of closing it.
+    Rollover(DateTime<Utc>),
+    /// Keep the position open with its expiry unchanged.
+    Hold,
+}
 
 pub struct PositionManager;
 
@@ -16,24 +86,56 @@ impl PositionManager {
         Self
     }
 
-    /// Check all open positions and close if stop-loss, take-profit, or chart signals indicate exit
+    /// Check all open positions and close if stop-loss, take-profit, or chart signals indicate exit.
+    /// Positions with a live price from `market_feed` skip the REST fetch (and its rate-limit
+    /// delay) entirely; only positions the feed hasn't delivered a price for yet fall back to
+    /// `token_fetcher`, which is also the only path that runs `ChartAnalyzer::analyze_entry_exit`
+    /// on a fresh `Token` (and so the only path that can roll an expired position over instead
+    /// of force-closing it - the fast path only reads the feed's candles, via
+    /// `should_exit_on_chart_signal`). Returns positions entering
+    /// `params.warning_threshold_hours` of their forced exit, as `(token_mint, token_symbol,
+    /// hours_remaining)`, and positions just rolled over, as `(token_mint, token_symbol,
+    /// new_expires_at)`, so the caller (which owns the `Notifier`) can push the matching
+    /// alerts - this module stays notification-free since it's shared verbatim with the backtester.
     pub async fn check_and_update_positions(
         &self,
         portfolio: &mut PortfolioMonitor,
         token_fetcher: &TokenDataFetcher,
-    ) -> anyhow::Result<()> {
+        market_feed: &MarketFeed,
+        params: &ExitParams,
+    ) -> anyhow::Result<(Vec<(Pubkey, String, i64)>, Vec<(Pubkey, String, DateTime<Utc>)>)> {
         let positions: Vec<Pubkey> = portfolio.get_position_mints();
 
         if positions.is_empty() {
-            return Ok(());
+            return Ok((Vec::new(), Vec::new()));
         }
 
         info!("📊 Checking {} open positions...", positions.len());
 
         let mut prices_to_update = HashMap::new();
         let mut positions_to_close = Vec::new();
+        let mut rollovers = Vec::new();
+        let mut warnings = Vec::new();
 
         for token_mint in positions {
+            if let Some(market) = market_feed.get(&token_mint) {
+                let current_price = market.price_usd;
+                prices_to_update.insert(token_mint, (current_price, market.market_cap));
+
+                if let Some(position) = portfolio.get_position(&token_mint) {
+                    let trigger = self
+                        .should_exit_position_price_only(&position, Money::new(current_price), params)
+                        .or_else(|| self.should_exit_on_chart_signal(&position, &market_feed.all_candles(&token_mint)));
+
+                    if let Some(trigger) = trigger {
+                        positions_to_close.push((token_mint, current_price, trigger));
+                    } else if let Some(hours_remaining) = self.hours_until_forced_exit(&position, params) {
+                        warnings.push((token_mint, position.token_symbol.clone(), hours_remaining));
+                    }
+                }
+                continue;
+            }
+
             // Fetch current token data
             match token_fetcher.get_token_data(&token_mint).await {
                 Ok(token) => {
@@ -44,10 +146,16 @@ impl PositionManager {
 
                     // Get the position to check exit conditions
                     if let Some(position) = portfolio.get_position(&token_mint) {
-                        let should_exit = self.should_exit_position(&position, &token);
-
-                        if should_exit {
-                            positions_to_close.push((token_mint, current_price));
+                        match self.evaluate_position(&position, &token, params) {
+                            PositionOutcome::Exit(trigger) => positions_to_close.push((token_mint, current_price, trigger)),
+                            PositionOutcome::Rollover(new_expires_at) => {
+                                rollovers.push((token_mint, position.token_symbol.clone(), new_expires_at));
+                            }
+                            PositionOutcome::Hold => {
+                                if let Some(hours_remaining) = self.hours_until_forced_exit(&position, params) {
+                                    warnings.push((token_mint, position.token_symbol.clone(), hours_remaining));
+                                }
+                            }
                         }
                     }
                 }
@@ -56,37 +164,41 @@ impl PositionManager {
                 }
             }
 
-            // Rate limiting
+            // Rate limiting (only hit when we fell back to a REST fetch above)
             tokio::time::sleep(std::time::Duration::from_millis(500)).await;
         }
 
         // Update all prices
         portfolio.update_prices(&prices_to_update);
 
+        // Roll over positions the fresh chart signal still supports holding
+        for (token_mint, _, new_expires_at) in &rollovers {
+            portfolio.extend_expiry(token_mint, *new_expires_at);
+        }
+
         // Close positions that hit exit conditions
-        for (token_mint, exit_price) in positions_to_close {
-            portfolio.close_position(&token_mint, exit_price);
+        for (token_mint, exit_price, trigger) in positions_to_close {
+            portfolio.close_position(&token_mint, exit_price, trigger);
         }
 
-        Ok(())
+        Ok((warnings, rollovers))
     }
 
-    /// Determine if position should be exited
-    fn should_exit_position(&self, position: &OpenPosition, token: &Token) -> bool {
-        let current_price = token.market_data.price_usd;
-
-        // 1. Stop-loss hit
-        if current_price <= position.stop_loss {
-            info!("🛑 Stop-loss triggered for {}: ${} <= ${}",
-                position.token_symbol, current_price, position.stop_loss);
-            return true;
-        }
+    /// Determine if a position should be exited, kept open, or rolled over, given a freshly
+    /// fetched `Token`. A `TimeExit` that `should_exit_position_price_only` raised purely
+    /// because `expires_at` has passed gets one more chance here: re-run
+    /// `ChartAnalyzer::analyze_entry_exit` against the fresh data, and only exit if the signal
+    /// no longer supports holding - otherwise push the expiry forward by `expires_at -
+    /// entry_time` (the opening hold window on the first rollover, longer on each later one)
+    /// instead of losing the trade to a stale clock.
+    fn evaluate_position(&self, position: &OpenPosition, token: &Token, params: &ExitParams) -> PositionOutcome {
+        let current_price = Money::new(token.market_data.price_usd);
 
-        // 2. Take-profit hit
-        if current_price >= position.take_profit {
-            info!("🎯 Take-profit triggered for {}: ${} >= ${}",
-                position.token_symbol, current_price, position.take_profit);
-            return true;
+        if let Some(trigger) = self.should_exit_position_price_only(position, current_price, params) {
+            if trigger == ExitTrigger::TimeExit && Utc::now() >= position.expires_at {
+                return self.evaluate_expired_position(position, token);
+            }
+            return PositionOutcome::Exit(trigger);
         }
 
         // 3. Chart shows strong sell signal
@@ -95,37 +207,153 @@ impl PositionManager {
             TradeAction::StrongSell => {
                 info!("📉 Strong sell signal for {}: {}",
                     position.token_symbol, chart_signal.reason);
-                return true;
+                PositionOutcome::Exit(ExitTrigger::Manual)
             }
             TradeAction::Sell if position.unrealized_pnl_pct > 15.0 => {
                 // Only exit on weak sell if we're already up 15%+
                 info!("📊 Sell signal with profit for {}: {} (+{:.1}%)",
                     position.token_symbol, chart_signal.reason, position.unrealized_pnl_pct);
-                return true;
+                PositionOutcome::Exit(ExitTrigger::Manual)
             }
-            _ => {}
+            _ => PositionOutcome::Hold,
+        }
+    }
+
+    /// Re-evaluates an expired position against fresh chart data instead of force-closing it
+    /// outright - a position is "stuck", not necessarily dead, and a signal that's still
+    /// `Buy`/`StrongBuy` means the original thesis hasn't broken, just outlasted the clock.
+    fn evaluate_expired_position(&self, position: &OpenPosition, token: &Token) -> PositionOutcome {
+        let chart_signal = ChartAnalyzer::analyze_entry_exit(token);
+        match chart_signal.action {
+            TradeAction::Buy | TradeAction::StrongBuy => {
+                let hold_window = position.expires_at - position.entry_time;
+                let new_expires_at = Utc::now() + hold_window;
+                info!("♻️  Rolling over {} past expiry - signal still {:?}: {}",
+                    position.token_symbol, chart_signal.action, chart_signal.reason);
+                PositionOutcome::Rollover(new_expires_at)
+            }
+            _ => {
+                info!("⏰ Expiry exit for {} - signal no longer supports holding: {}",
+                    position.token_symbol, chart_signal.reason);
+                PositionOutcome::Exit(ExitTrigger::TimeExit)
+            }
+        }
+    }
+
+    /// Price-only exit checks (stop-loss, take-profit, time exit, trailing stop) that don't
+    /// need a full `Token`/chart fetch - the fast path used when `MarketFeed` already has a
+    /// live price for the position. Shared verbatim with the backtester (`crate::backtest`)
+    /// so a parameter sweep exercises the exact logic live trading runs.
+    pub(crate) fn should_exit_position_price_only(
+        &self,
+        position: &OpenPosition,
+        current_price: Money,
+        params: &ExitParams,
+    ) -> Option<ExitTrigger> {
+        // 1. Stop-loss hit
+        if current_price <= position.stop_loss {
+            info!("🛑 Stop-loss triggered for {}: ${} <= ${}",
+                position.token_symbol, current_price, position.stop_loss);
+            return Some(ExitTrigger::StopLoss);
+        }
+
+        // 2. Take-profit hit
+        if current_price >= position.take_profit {
+            info!("🎯 Take-profit triggered for {}: ${} >= ${}",
+                position.token_symbol, current_price, position.take_profit);
+            return Some(ExitTrigger::TakeProfit);
         }
 
         // 4. Time-based exit (position open too long)
         let hold_time_hours = position.hold_time_minutes / 60;
-        if hold_time_hours > 24 && position.unrealized_pnl_pct < 5.0 {
+        if hold_time_hours > params.max_hold_hours && position.unrealized_pnl_pct < params.time_exit_pnl_pct {
             info!("⏰ Time exit for {} after {} hours with low profit ({:.1}%)",
                 position.token_symbol, hold_time_hours, position.unrealized_pnl_pct);
-            return true;
+            return Some(ExitTrigger::TimeExit);
+        }
+
+        // 4b. Hard expiry - closes regardless of PnL once `expires_at` has passed. The
+        // fast (market-feed) path has no fresh `Token` to re-evaluate against, so it force-closes
+        // outright; the slow (REST-fetch) path in `check_and_update_positions` instead gives the
+        // position a chance to roll over via `evaluate_expired_position` once this returns `TimeExit`.
+        if Utc::now() >= position.expires_at {
+            info!("⏰ Hard expiry exit for {} (expired {})",
+                position.token_symbol, position.expires_at.format("%Y-%m-%d %H:%M UTC"));
+            return Some(ExitTrigger::TimeExit);
         }
 
-        // 5. Trailing stop (moved stop-loss up as price rises)
-        // If we're up 30%+, exit if price drops 15% from peak
-        if position.unrealized_pnl_pct > 30.0 {
-            let trailing_stop = position.take_profit * Decimal::from_f64_retain(0.85).unwrap();
+        // 4c. Scheduled rollover - once a position has cleared the soft `max_hold_hours`
+        // window, force it closed the next time the clock hits `rollover_hour_utc` (e.g. to
+        // avoid carrying positions across a daily settlement/funding boundary) instead of
+        // waiting for the hard cutoff.
+        if let Some(rollover_hour) = params.rollover_hour_utc {
+            if hold_time_hours > params.max_hold_hours && Utc::now().hour() == rollover_hour {
+                info!("⏰ Rollover exit for {} after {} hours at UTC hour {}",
+                    position.token_symbol, hold_time_hours, rollover_hour);
+                return Some(ExitTrigger::TimeExit);
+            }
+        }
+
+        // 5. Trailing stop, tracking the position's realized peak rather than its static
+        // take-profit target. Once up `trailing_activation_pct`+, the stop rides up to
+        // `peak_price * (1 - trailing_giveback_pct)` and - since `peak_price` only ever
+        // increases - never moves back down.
+        if position.unrealized_pnl_pct > params.trailing_activation_pct {
+            let giveback_multiplier =
+                Decimal::from_f64_retain(1.0 - params.trailing_giveback_pct / 100.0).unwrap_or(Decimal::ONE);
+            let trailing_stop = position
+                .peak_price
+                .checked_mul(giveback_multiplier)
+                .unwrap_or(Money::ZERO);
             if current_price <= trailing_stop {
                 info!("📈 Trailing stop triggered for {}: ${} <= ${}",
                     position.token_symbol, current_price, trailing_stop);
-                return true;
+                return Some(ExitTrigger::TrailingStop);
             }
         }
 
-        false
+        None
+    }
+
+    /// Same StrongSell/profitable-Sell exit rule `evaluate_position` applies to a `Token`
+    /// snapshot's chart signal, but driven by `MarketFeed`'s real OHLCV candles instead - lets
+    /// the fast (market-feed) path exit on genuine multi-resolution momentum/breakout reads
+    /// rather than only stop-loss/take-profit/time checks. `None` until the feed has
+    /// accumulated enough candles to read.
+    fn should_exit_on_chart_signal(
+        &self,
+        position: &OpenPosition,
+        candles_by_resolution: &HashMap<Resolution, Vec<Candle>>,
+    ) -> Option<ExitTrigger> {
+        if candles_by_resolution.values().all(|candles| candles.len() < 2) {
+            return None;
+        }
+
+        let chart_signal = ChartAnalyzer::analyze_multi_resolution(candles_by_resolution, Decimal::ZERO);
+        match chart_signal.action {
+            TradeAction::StrongSell => {
+                info!("📉 Strong sell signal for {} (candle-based): {}", position.token_symbol, chart_signal.reason);
+                Some(ExitTrigger::Manual)
+            }
+            TradeAction::Sell if position.unrealized_pnl_pct > 15.0 => {
+                info!("📊 Sell signal with profit for {} (candle-based): {} (+{:.1}%)",
+                    position.token_symbol, chart_signal.reason, position.unrealized_pnl_pct);
+                Some(ExitTrigger::Manual)
+            }
+            _ => None,
+        }
+    }
+
+    /// How many hours remain before `position.expires_at` forces this position closed, once
+    /// it's within `params.warning_threshold_hours` of that cutoff - `None` otherwise. Used to
+    /// fire an advance-warning notification before the hard cutoff actually fires.
+    pub(crate) fn hours_until_forced_exit(&self, position: &OpenPosition, params: &ExitParams) -> Option<i64> {
+        let remaining = (position.expires_at - Utc::now()).num_hours();
+        if remaining > 0 && remaining <= params.warning_threshold_hours {
+            Some(remaining)
+        } else {
+            None
+        }
     }
 }
 
@@ -139,3 +367,17 @@ impl PortfolioMonitor {
         self.positions.get(token_mint)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expiry_window_maps_each_strategy_mode_to_its_own_field() {
+        let params = ExitParams { scalping_max_hold_minutes: 20, max_hold_hours: 24, hard_max_hold_hours: 48, ..ExitParams::default() };
+
+        assert_eq!(params.expiry_window(StrategyMode::Scalping), chrono::Duration::minutes(20));
+        assert_eq!(params.expiry_window(StrategyMode::DayTrading), chrono::Duration::hours(24));
+        assert_eq!(params.expiry_window(StrategyMode::SwingTrading), chrono::Duration::hours(48));
+    }
+}
@@ -0,0 +1,218 @@
+// WebSocket push service: external clients subscribe to `Trade`/`CopyTradeSignal`/portfolio
+// `Position` updates instead of polling the SQLite-backed dashboard API. Reuses the
+// same `tokio::sync::broadcast` fan-out pattern as `portfolio_monitor`'s event bus - a
+// producer just sends a `StreamEvent` and every connected client filters it for itself.
+// The only producer is the wallet backfill in main.rs, which sends `Trade` events; nothing
+// sends `Signal` or `Position` events, so those subscriptions receive no live updates.
+use std::str::FromStr;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::broadcast;
+use tracing::{error, info, warn};
+use trading_core::{CopyTradeSignal, Position, Trade};
+use trading_db::trades::TradeRepository;
+
+/// Which event categories a client wants pushed, as a bitset so one subscribe frame can
+/// combine them (`"flags": 5` = `TRADES | POSITIONS`) instead of four separate booleans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubFlags(pub u8);
+
+impl SubFlags {
+    pub const TRADES: SubFlags = SubFlags(1 << 0);
+    pub const SIGNALS: SubFlags = SubFlags(1 << 1);
+    pub const POSITIONS: SubFlags = SubFlags(1 << 2);
+    pub const INSIDER_ACTIVITY: SubFlags = SubFlags(1 << 3);
+    pub const ALL: SubFlags = SubFlags(0b1111);
+
+    pub fn contains(self, flag: SubFlags) -> bool {
+        self.0 & flag.0 == flag.0
+    }
+}
+
+impl std::ops::BitOr for SubFlags {
+    type Output = SubFlags;
+    fn bitor(self, rhs: SubFlags) -> SubFlags {
+        SubFlags(self.0 | rhs.0)
+    }
+}
+
+/// A live update pushed to subscribed clients. Tagged so clients can deserialize without
+/// tracking which variant is which ahead of time.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event")]
+pub enum StreamEvent {
+    Trade(Trade),
+    Signal(CopyTradeSignal),
+    Position(Position),
+}
+
+/// The subscribe frame a client sends immediately after connecting. `replay` asks for the
+/// last N rows of trade history before the live feed starts, so a late joiner isn't missing
+/// context; `source_wallet`/`token_mint` narrow the feed to one wallet or token.
+/// `Position` events carry no wallet, so only `token_mint` narrows them.
+#[derive(Debug, Deserialize)]
+struct SubscribeFrame {
+    flags: u8,
+    #[serde(default)]
+    replay: Option<i64>,
+    #[serde(default)]
+    source_wallet: Option<String>,
+    #[serde(default)]
+    token_mint: Option<String>,
+}
+
+#[derive(Clone)]
+struct StreamState {
+    events: broadcast::Sender<StreamEvent>,
+    trade_repo: Option<TradeRepository>,
+}
+
+pub struct StreamServer {
+    port: u16,
+    events: broadcast::Sender<StreamEvent>,
+    trade_repo: Option<TradeRepository>,
+}
+
+impl StreamServer {
+    pub fn new(port: u16, trade_repo: Option<TradeRepository>) -> Self {
+        let (events, _) = broadcast::channel(1024);
+        Self { port, events, trade_repo }
+    }
+
+    /// A handle producers (the wallet backfill in main.rs) use to publish events.
+    pub fn sender(&self) -> broadcast::Sender<StreamEvent> {
+        self.events.clone()
+    }
+
+    pub async fn start(self) {
+        info!("🔌 Starting WebSocket stream server on ws://localhost:{}/stream", self.port);
+
+        let state = StreamState {
+            events: self.events,
+            trade_repo: self.trade_repo,
+        };
+
+        let app = Router::new()
+            .route("/stream", get(ws_handler))
+            .with_state(state);
+
+        let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", self.port))
+            .await
+            .expect("Failed to bind stream server");
+
+        info!("🔌 Stream server ready at ws://localhost:{}/stream", self.port);
+
+        axum::serve(listener, app)
+            .await
+            .expect("Failed to start stream server");
+    }
+}
+
+async fn ws_handler(ws: WebSocketUpgrade, State(state): State<StreamState>) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_socket(socket, state))
+}
+
+async fn handle_socket(mut socket: WebSocket, state: StreamState) {
+    let frame = match socket.recv().await {
+        Some(Ok(Message::Text(text))) => match serde_json::from_str::<SubscribeFrame>(&text) {
+            Ok(frame) => frame,
+            Err(e) => {
+                warn!("Malformed subscribe frame, dropping connection: {}", e);
+                return;
+            }
+        },
+        _ => return,
+    };
+
+    let flags = SubFlags(frame.flags);
+    let source_wallet = frame.source_wallet.as_deref().and_then(|s| Pubkey::from_str(s).ok());
+    let token_mint = frame.token_mint.as_deref().and_then(|s| Pubkey::from_str(s).ok());
+
+    if flags.contains(SubFlags::TRADES) {
+        if let (Some(repo), Some(count)) = (&state.trade_repo, frame.replay) {
+            match repo.get_recent_trades(count).await {
+                Ok(trades) => {
+                    for trade in trades.into_iter().rev() {
+                        if !trade_matches(&trade, source_wallet, token_mint) {
+                            continue;
+                        }
+                        if send_event(&mut socket, &StreamEvent::Trade(trade)).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(e) => warn!("Failed to load replay trades: {}", e),
+            }
+        }
+    }
+
+    let mut receiver = state.events.subscribe();
+    loop {
+        tokio::select! {
+            incoming = socket.recv() => {
+                match incoming {
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Err(_)) => break,
+                    _ => {}
+                }
+            }
+            event = receiver.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+
+                if !passes(&event, flags, source_wallet, token_mint) {
+                    continue;
+                }
+
+                if send_event(&mut socket, &event).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+async fn send_event(socket: &mut WebSocket, event: &StreamEvent) -> Result<(), ()> {
+    let json = match serde_json::to_string(event) {
+        Ok(json) => json,
+        Err(e) => {
+            error!("Failed to serialize stream event: {}", e);
+            return Ok(());
+        }
+    };
+
+    socket.send(Message::Text(json)).await.map_err(|_| ())
+}
+
+fn trade_matches(trade: &Trade, source_wallet: Option<Pubkey>, token_mint: Option<Pubkey>) -> bool {
+    source_wallet.map_or(true, |w| w == trade.wallet) && token_mint.map_or(true, |m| m == trade.token_mint)
+}
+
+fn passes(event: &StreamEvent, flags: SubFlags, source_wallet: Option<Pubkey>, token_mint: Option<Pubkey>) -> bool {
+    match event {
+        StreamEvent::Trade(trade) => {
+            flags.contains(SubFlags::TRADES) && trade_matches(trade, source_wallet, token_mint)
+        }
+        StreamEvent::Signal(signal) => {
+            flags.contains(SubFlags::SIGNALS)
+                && source_wallet.map_or(true, |w| w == signal.source_wallet)
+                && token_mint.map_or(true, |m| m == signal.token_mint)
+        }
+        StreamEvent::Position(position) => {
+            flags.contains(SubFlags::POSITIONS) && token_mint.map_or(true, |m| m == position.token_mint)
+        }
+    }
+}
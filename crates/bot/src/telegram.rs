@@ -4,6 +4,7 @@ use teloxide::prelude::*;
 use teloxide::Bot;
 use tracing::{error, info, warn};
 
+use crate::money::Money;
 use crate::portfolio_monitor::{ClosedTrade, DailyStats, OpenPosition};
 
 pub struct TelegramNotifier {
@@ -83,11 +84,9 @@ impl TelegramNotifier {
             position.amount,
             confidence * 100.0,
             position.take_profit,
-            ((position.take_profit - position.entry_price) / position.entry_price * Decimal::from(100))
-                .to_string().parse::<f64>().unwrap_or(0.0),
+            Money::pct_change(position.entry_price, position.take_profit),
             position.stop_loss,
-            ((position.stop_loss - position.entry_price) / position.entry_price * Decimal::from(100))
-                .to_string().parse::<f64>().unwrap_or(0.0),
+            Money::pct_change(position.entry_price, position.stop_loss),
             chrono::Utc::now().format("%H:%M:%S UTC")
         );
 
@@ -127,7 +126,7 @@ impl TelegramNotifier {
         }
 
         // Big win celebration
-        if trade.is_win && trade.pnl > Decimal::from(5) {
+        if trade.is_win && trade.pnl > Money::new(Decimal::from(5)) {
             self.notify_big_win(trade).await;
         }
     }
@@ -152,18 +151,15 @@ impl TelegramNotifier {
 
     /// Send portfolio update
     pub async fn notify_portfolio_update(&self, stats: &DailyStats) {
-        let roi = if stats.starting_value > Decimal::ZERO {
-            ((stats.total_pnl / stats.starting_value) * Decimal::from(100))
-                .to_string()
-                .parse::<f64>()
-                .unwrap_or(0.0)
+        let roi = if stats.starting_value > Money::ZERO {
+            Money::pct_change(stats.starting_value, stats.starting_value + stats.total_pnl)
         } else {
             0.0
         };
 
-        let emoji = if stats.total_pnl > Decimal::ZERO {
+        let emoji = if stats.total_pnl > Money::ZERO {
             "📈"
-        } else if stats.total_pnl < Decimal::ZERO {
+        } else if stats.total_pnl < Money::ZERO {
             "📉"
         } else {
             "➡️"
@@ -246,6 +242,69 @@ impl TelegramNotifier {
         }
     }
 
+    /// Warn that a position is approaching its forced time-based exit
+    pub async fn notify_time_warning(&self, token_mint: &str, token_symbol: &str, hours_remaining: i64) {
+        let message = format!(
+            "⏰ <b>Position Expiring Soon</b>\n\n\
+             🪙 Token: {} (<code>{}</code>)\n\
+             ⏳ Forced exit in {}h",
+            token_symbol,
+            &token_mint[..16],
+            hours_remaining
+        );
+
+        if let Err(e) = self.send_message(message).await {
+            warn!("Failed to send time warning notification: {}", e);
+        }
+    }
+
+    /// Notify that a conditional order placed through `OrderEngine` crossed its trigger
+    pub async fn notify_order_fired(&self, token_mint: &str, action: &str, order_description: &str, price: Decimal) {
+        let message = format!(
+            "📐 <b>Conditional Order Fired</b>\n\n\
+             🪙 Token: <code>{}</code>\n\
+             📋 Order: {}\n\
+             {} @ ${:.6}",
+            &token_mint[..16],
+            order_description,
+            action,
+            price
+        );
+
+        if let Err(e) = self.send_message(message).await {
+            warn!("Failed to send order fired notification: {}", e);
+        }
+    }
+
+    /// Notify that maintenance (resume-only) mode was entered or left
+    pub async fn notify_maintenance_mode(&self, active: bool, reason: &str) {
+        let message = if active {
+            format!("🛠️ <b>Maintenance Mode ON</b>\n\nNew entries blocked - {}\nOpen positions still managed.", reason)
+        } else {
+            format!("🛠️ <b>Maintenance Mode OFF</b>\n\nNew entries resumed - {}", reason)
+        };
+
+        if let Err(e) = self.send_message(message).await {
+            warn!("Failed to send maintenance mode notification: {}", e);
+        }
+    }
+
+    /// Notify that a position past its expiry was rolled over instead of closed
+    pub async fn notify_position_rolled_over(&self, token_mint: &str, token_symbol: &str, new_expires_at: chrono::DateTime<chrono::Utc>) {
+        let message = format!(
+            "♻️ <b>Position Rolled Over</b>\n\n\
+             🪙 Token: {} (<code>{}</code>)\n\
+             ⏳ New expiry: {}",
+            token_symbol,
+            &token_mint[..16],
+            new_expires_at.format("%Y-%m-%d %H:%M UTC")
+        );
+
+        if let Err(e) = self.send_message(message).await {
+            warn!("Failed to send position rolled over notification: {}", e);
+        }
+    }
+
     /// Test notification to verify setup
     pub async fn test_notification(&self) -> Result<()> {
         let message = "✅ <b>Telegram Bot Connected!</b>\n\nYou will receive notifications here.".to_string();
@@ -0,0 +1,88 @@
+// Inbound counterpart to `TelegramNotifier`: that type only ever pushes messages out, this
+// one listens for bot commands from the authorized chat and forwards them to whichever task
+// owns `PortfolioMonitor`/`OrderEngine` (`run_execution_task` in main.rs, the trading loop in
+// enhanced_main.rs) over a channel, the same hand-off shape `shutdown_tx` already uses for
+// the opposite direction. The engine replies with a plain string, which gets sent straight
+// back to the chat - there's no structured response type because Telegram only wants text.
+use teloxide::dptree;
+use teloxide::prelude::*;
+use teloxide::utils::command::BotCommands;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{error, warn};
+
+#[derive(BotCommands, Clone, Debug)]
+#[command(rename_rule = "lowercase", description = "These commands are supported:")]
+pub enum Command {
+    #[command(description = "show today's PnL/win-rate stats")]
+    Status,
+    #[command(description = "list open positions with live PnL")]
+    Positions,
+    #[command(description = "force-close a position by symbol")]
+    Close(String),
+    #[command(description = "stop accepting new entries")]
+    Pause,
+    #[command(description = "resume accepting new entries")]
+    Resume,
+    #[command(rename = "sell_all", description = "liquidate every open position")]
+    SellAll,
+}
+
+/// One inbound command plus where to send the reply text once the engine has handled it.
+pub struct ControlRequest {
+    pub command: Command,
+    pub respond_to: oneshot::Sender<String>,
+}
+
+/// Owns the inbound side of the Telegram connection. `run` blocks forever polling for
+/// updates, so callers spawn it rather than awaiting it inline.
+pub struct TelegramControl {
+    bot: Bot,
+    chat_id: ChatId,
+    tx: mpsc::Sender<ControlRequest>,
+}
+
+impl TelegramControl {
+    /// Builds the dispatcher half and the receiver the owning task should poll inside its
+    /// own `tokio::select!` loop alongside signals/shutdown.
+    pub fn new(token: String, chat_id: i64) -> (Self, mpsc::Receiver<ControlRequest>) {
+        let (tx, rx) = mpsc::channel(32);
+        (Self { bot: Bot::new(token), chat_id: ChatId(chat_id), tx }, rx)
+    }
+
+    pub async fn run(self) {
+        let handler = Update::filter_message().filter_command::<Command>().endpoint(handle_command);
+
+        // No `.enable_ctrlc_handler()` here - `main` already owns SIGINT via
+        // `shutdown_tx`/`ctrl_c()`; a second handler inside the dispatcher would race it.
+        // `enhanced_main` installs no SIGINT handler and keeps the default behaviour.
+        Dispatcher::builder(self.bot, handler)
+            .dependencies(dptree::deps![self.chat_id, self.tx])
+            .build()
+            .dispatch()
+            .await;
+    }
+}
+
+async fn handle_command(
+    bot: Bot,
+    msg: Message,
+    cmd: Command,
+    chat_id: ChatId,
+    tx: mpsc::Sender<ControlRequest>,
+) -> ResponseResult<()> {
+    if msg.chat.id != chat_id {
+        warn!("Ignoring Telegram command from unauthorized chat {}", msg.chat.id);
+        return Ok(());
+    }
+
+    let (respond_to, reply_rx) = oneshot::channel();
+    if tx.send(ControlRequest { command: cmd, respond_to }).await.is_err() {
+        error!("Control channel closed, trading engine is no longer listening");
+        bot.send_message(msg.chat.id, "⚠️ Trading engine is unavailable").await?;
+        return Ok(());
+    }
+
+    let reply = reply_rx.await.unwrap_or_else(|_| "⚠️ Engine dropped the request without a reply".to_string());
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
@@ -0,0 +1,210 @@
+// Compact on-disk trade log: a fixed-width binary record per closed trade, memory-mapped
+// so large backtests (hundreds of thousands of fills) start instantly and stay flat in
+// memory instead of re-parsing `trade_history.json` on every run.
+
+use anyhow::{anyhow, Result};
+use chrono::{TimeZone, Utc};
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::Write;
+use std::mem::size_of;
+use std::path::Path;
+use tracing::info;
+
+use crate::money::Money;
+use crate::persistence::TradeHistory;
+
+const MAGIC: &[u8; 4] = b"TRLH";
+const VERSION: u32 = 1;
+
+/// One closed trade packed into 32 bytes: a code into the side table (rather than the
+/// raw mint/symbol strings) plus millisecond timestamps and f32-packed prices/size.
+/// f32 rather than f64 is what makes the 32-byte budget work; backtest PnL only needs
+/// enough precision to match the f64 `Decimal` source to a few significant digits.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TradeRecord {
+    pub token_code: u8,
+    _reserved: [u8; 3],
+    pub entry_ms: u64,
+    pub exit_ms: u64,
+    pub entry_price: f32,
+    pub exit_price: f32,
+    pub size: f32,
+}
+
+const RECORD_SIZE: usize = size_of::<TradeRecord>();
+
+/// Memory-mapped, zero-copy view over a binary trade log produced by
+/// `convert_json_to_binary`.
+pub struct BinaryTradeLog {
+    mmap: Mmap,
+    code_table: Vec<(String, String)>,
+    records_offset: usize,
+    record_count: usize,
+}
+
+impl BinaryTradeLog {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path)?;
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        if mmap.len() < 16 || &mmap[0..4] != MAGIC {
+            return Err(anyhow!("Not a trade log binary file: {}", path.display()));
+        }
+
+        let version = u32::from_le_bytes(mmap[4..8].try_into().unwrap());
+        if version != VERSION {
+            return Err(anyhow!("Unsupported trade log version: {}", version));
+        }
+
+        let record_count = u32::from_le_bytes(mmap[8..12].try_into().unwrap()) as usize;
+        let code_table_len = u32::from_le_bytes(mmap[12..16].try_into().unwrap()) as usize;
+
+        let code_table_start = 16;
+        let code_table_end = code_table_start + code_table_len;
+        let code_table: Vec<(String, String)> = bincode::deserialize(&mmap[code_table_start..code_table_end])?;
+
+        // Records start on an 8-byte boundary (TradeRecord's u64 fields require it).
+        let records_offset = (code_table_end + 7) & !7;
+
+        let expected_len = records_offset + record_count * RECORD_SIZE;
+        if mmap.len() < expected_len {
+            return Err(anyhow!(
+                "Trade log truncated: expected at least {} bytes, found {}",
+                expected_len,
+                mmap.len()
+            ));
+        }
+
+        Ok(Self {
+            mmap,
+            code_table,
+            records_offset,
+            record_count,
+        })
+    }
+
+    /// Zero-copy view of every record in the file.
+    pub fn records(&self) -> &[TradeRecord] {
+        let ptr = self.mmap[self.records_offset..].as_ptr() as *const TradeRecord;
+        unsafe { std::slice::from_raw_parts(ptr, self.record_count) }
+    }
+
+    pub fn token_mint(&self, code: u8) -> &str {
+        self.code_table
+            .get(code as usize)
+            .map(|(mint, _)| mint.as_str())
+            .unwrap_or("unknown")
+    }
+
+    pub fn token_symbol(&self, code: u8) -> &str {
+        self.code_table
+            .get(code as usize)
+            .map(|(_, symbol)| symbol.as_str())
+            .unwrap_or("UNKNOWN")
+    }
+}
+
+/// One-time converter from the existing JSON `TradeHistory` to the binary format.
+pub fn convert_json_to_binary(history: &TradeHistory, path: &Path) -> Result<()> {
+    let mut code_table: Vec<(String, String)> = Vec::new();
+    let mut code_of = std::collections::HashMap::new();
+
+    let mut records = Vec::with_capacity(history.closed_trades.len());
+    for trade in &history.closed_trades {
+        let code = *code_of.entry(trade.token_mint.clone()).or_insert_with(|| {
+            code_table.push((trade.token_mint.clone(), trade.token_symbol.clone()));
+            (code_table.len() - 1) as u8
+        });
+
+        let entry_price: f32 = Money::from_str(&trade.entry_price)
+            .unwrap_or(Money::ZERO)
+            .as_decimal()
+            .to_string()
+            .parse()
+            .unwrap_or(0.0);
+        let exit_price: f32 = Money::from_str(&trade.exit_price)
+            .unwrap_or(Money::ZERO)
+            .as_decimal()
+            .to_string()
+            .parse()
+            .unwrap_or(0.0);
+
+        records.push(TradeRecord {
+            token_code: code,
+            _reserved: [0; 3],
+            entry_ms: trade.entry_time.timestamp_millis() as u64,
+            exit_ms: trade.exit_time.timestamp_millis() as u64,
+            entry_price,
+            exit_price,
+            // The JSON format never recorded position size per-trade; 0 signals
+            // "unknown" to readers until a size-aware writer populates this field.
+            size: 0.0,
+        });
+    }
+
+    let code_table_bytes = bincode::serialize(&code_table)?;
+    let records_offset = (16 + code_table_bytes.len() + 7) & !7;
+
+    let mut file = File::create(path)?;
+    file.write_all(MAGIC)?;
+    file.write_all(&VERSION.to_le_bytes())?;
+    file.write_all(&(records.len() as u32).to_le_bytes())?;
+    file.write_all(&(code_table_bytes.len() as u32).to_le_bytes())?;
+    file.write_all(&code_table_bytes)?;
+    file.write_all(&vec![0u8; records_offset - 16 - code_table_bytes.len()])?;
+
+    for record in &records {
+        let bytes = unsafe {
+            std::slice::from_raw_parts((record as *const TradeRecord) as *const u8, RECORD_SIZE)
+        };
+        file.write_all(bytes)?;
+    }
+
+    info!(
+        "Converted {} trades ({} distinct tokens) to binary trade log {}",
+        records.len(),
+        code_table.len(),
+        path.display()
+    );
+
+    Ok(())
+}
+
+/// Portable fallback for environments where mmap isn't available or desirable (e.g.
+/// copying logs between machines) - a plain bincode-serialized `TradeHistory`.
+pub fn save_bincode(history: &TradeHistory, path: &Path) -> Result<()> {
+    let bytes = bincode::serialize(history)?;
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+pub fn load_bincode(path: &Path) -> Result<TradeHistory> {
+    let bytes = std::fs::read(path)?;
+    Ok(bincode::deserialize(&bytes)?)
+}
+
+/// Same fallback via `postcard`, for the no_std-friendly / smaller-footprint case.
+pub fn save_postcard(history: &TradeHistory, path: &Path) -> Result<()> {
+    let bytes = postcard::to_allocvec(history)?;
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+pub fn load_postcard(path: &Path) -> Result<TradeHistory> {
+    let bytes = std::fs::read(path)?;
+    Ok(postcard::from_bytes(&bytes)?)
+}
+
+pub fn record_entry_time(record: &TradeRecord) -> chrono::DateTime<Utc> {
+    Utc.timestamp_millis_opt(record.entry_ms as i64)
+        .single()
+        .unwrap_or_else(Utc::now)
+}
+
+pub fn record_exit_time(record: &TradeRecord) -> chrono::DateTime<Utc> {
+    Utc.timestamp_millis_opt(record.exit_ms as i64)
+        .single()
+        .unwrap_or_else(Utc::now)
+}
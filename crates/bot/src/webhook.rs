@@ -0,0 +1,37 @@
+// Outbound webhook subscriber: POSTs serialized PortfolioEvents to a configured URL,
+// so external integrations can react to trades without polling the dashboard API.
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+use crate::portfolio_monitor::PortfolioEvent;
+
+pub struct WebhookNotifier {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Subscribe to portfolio events and POST each one as JSON until the channel closes.
+    pub async fn run(&self, mut events: broadcast::Receiver<PortfolioEvent>) {
+        info!("🔗 Webhook notifier forwarding portfolio events to {}", self.url);
+
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            if let Err(e) = self.client.post(&self.url).json(&event).send().await {
+                warn!("Failed to deliver webhook event: {}", e);
+            }
+        }
+    }
+}
@@ -31,6 +31,15 @@ pub struct SolanaConfig {
 pub struct DataSourcesConfig {
     /// Jupiter API URL
     pub jupiter_api_url: String,
+    /// Sanctum API URL
+    pub sanctum_api_url: String,
+    /// Which swap venues `BestRouteProvider` should quote against, by name (e.g.
+    /// `["jupiter", "sanctum"]`) - an operator can disable a venue without a rebuild.
+    pub swap_providers: Vec<String>,
+    /// JSON fixture of synthesized prices for `MockJupiterClient`, used instead of the
+    /// live venues above when `trading.enabled` is false. `None` falls back to a flat
+    /// 1:1 price with no impact/fee for every pair.
+    pub mock_price_table_path: Option<String>,
     /// DexScreener API URL
     pub dexscreener_api_url: String,
     /// Rugcheck API URL
@@ -60,8 +69,27 @@ pub struct TradingConfig {
     pub jito_tip_lamports: u64,
     /// Default slippage tolerance (basis points)
     pub default_slippage_bps: u16,
-    /// Priority fee in microlamports
+    /// Priority fee in microlamports (when `auto_priority_fee` is true, used only if no
+    /// recent prioritization fee samples are available)
     pub priority_fee_microlamports: u64,
+    /// Compute unit limit to request via `ComputeBudgetProgram::set_compute_unit_limit`
+    pub compute_unit_limit: u32,
+    /// When true, sample `getRecentPrioritizationFees` instead of using a fixed price
+    pub auto_priority_fee: bool,
+    /// Percentile (0-100) of recent prioritization fees to target in auto mode
+    pub auto_priority_fee_percentile: u8,
+    /// Max slots allowed to pass between signal generation and execution before
+    /// the state is considered stale
+    pub max_slot_drift: u64,
+    /// Max price move (percent) allowed between signal generation and execution
+    pub max_price_move_pct: f64,
+    /// Force Jupiter to return a legacy (non-versioned) transaction instead of a v0
+    /// transaction with Address Lookup Table references - a fallback for routes or RPC
+    /// endpoints that don't yet handle versioned transactions cleanly.
+    pub as_legacy_transaction: bool,
+    /// Let Jupiter simulate and set its own compute unit limit on the built transaction
+    /// instead of the fixed `compute_unit_limit` above.
+    pub dynamic_compute_unit_limit: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -119,6 +147,9 @@ impl Default for BotConfig {
             },
             data_sources: DataSourcesConfig {
                 jupiter_api_url: "https://quote-api.jup.ag/v6".to_string(),
+                sanctum_api_url: "https://sanctum-extra-api.ngrok.dev".to_string(),
+                swap_providers: vec!["jupiter".to_string(), "sanctum".to_string()],
+                mock_price_table_path: None,
                 dexscreener_api_url: "https://api.dexscreener.com/latest".to_string(),
                 rugcheck_api_url: "https://api.rugcheck.xyz/v1".to_string(),
                 birdeye_api_key: None,
@@ -136,6 +167,13 @@ impl Default for BotConfig {
                 jito_tip_lamports: 10000,
                 default_slippage_bps: 100,
                 priority_fee_microlamports: 10000,
+                compute_unit_limit: 200_000,
+                auto_priority_fee: true,
+                auto_priority_fee_percentile: 75,
+                max_slot_drift: 50,
+                max_price_move_pct: 3.0,
+                as_legacy_transaction: false,
+                dynamic_compute_unit_limit: true,
             },
             risk: RiskLimits::default(),
             strategy: StrategyConfig {
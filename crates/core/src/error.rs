@@ -38,6 +38,12 @@ pub enum TradingError {
     #[error("Rate limit exceeded: {0}")]
     RateLimitExceeded(String),
 
+    #[error("Transaction dropped, insufficient priority fee: {0}")]
+    InsufficientPriorityFee(String),
+
+    #[error("Market state went stale before execution: {0}")]
+    StaleStateError(String),
+
     #[error("Timeout: {0}")]
     Timeout(String),
 
@@ -46,6 +52,9 @@ pub enum TradingError {
 
     #[error("Unknown error: {0}")]
     Unknown(String),
+
+    #[error("Arithmetic overflow: {0}")]
+    ArithmeticOverflow(String),
 }
 
 pub type Result<T> = std::result::Result<T, TradingError>;
@@ -33,10 +33,35 @@ pub struct WalletMetrics {
     pub largest_loss: Decimal,
     pub sharpe_ratio: Option<f64>,
     pub max_drawdown: f64,
+    /// Start of the equity curve's worst drawdown period (the peak it fell from), `None`
+    /// if there's no closed position history to chart.
+    pub max_drawdown_start: Option<DateTime<Utc>>,
+    /// End of the worst drawdown period (where `max_drawdown` itself was hit).
+    pub max_drawdown_end: Option<DateTime<Utc>>,
+    /// Total time the equity curve spent below its running peak, summed across every
+    /// drawdown period, not just the worst one.
+    pub underwater_seconds: f64,
     pub trades_last_24h: u64,
     pub trades_last_7d: u64,
     pub volume_24h: Decimal,
     pub volume_7d: Decimal,
+    pub return_metrics: ReturnSeriesMetrics,
+}
+
+/// Risk/reward metrics computed from the per-position return series (each closed position's
+/// `pnl_percentage / 100`), the way a full account tracker like `lfest` reports them -
+/// distinct from `WalletMetrics::sharpe_ratio`, which is a naive mean/stddev over raw dollar
+/// PnL with no annualization or downside weighting. Every field is `None` rather than a
+/// misleading `0.0` when its denominator would be zero (fewer than two closed positions, no
+/// losing trades, no drawdown, etc).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+pub struct ReturnSeriesMetrics {
+    pub annualized_sharpe: Option<f64>,
+    pub sortino: Option<f64>,
+    pub calmar: Option<f64>,
+    pub profit_factor: Option<f64>,
+    pub expectancy: Option<f64>,
+    pub recovery_factor: Option<f64>,
 }
 
 /// Represents a token on Solana
@@ -144,6 +169,11 @@ pub struct TradePosition {
     pub entry_market_cap: Decimal,
     pub exit_market_cap: Option<Decimal>,
     pub status: PositionStatus,
+    /// Set when the matched entry/exit trades produced an implausible `hold_time_seconds`
+    /// (negative, i.e. the sell's timestamp preceded the buy's, or beyond the configured
+    /// plausibility bound) - a sign of corrupt or out-of-order feed data rather than a real
+    /// position, so callers can exclude it from metrics that assume a sane hold time.
+    pub timestamp_suspect: bool,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -153,6 +183,27 @@ pub enum PositionStatus {
     PartiallyFilled,
 }
 
+/// Which buy lot(s) a sell is matched against when grouping trades into positions -
+/// needed because a wallet's buys and sells for a token rarely pair up 1:1 (scaling in,
+/// partial exits, a sell larger than any single buy).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum LotMatchingMode {
+    /// Oldest open buy lot is consumed first.
+    Fifo,
+    /// Most recent open buy lot is consumed first - this bot's behavior before lot
+    /// accounting existed, when every sell was matched against a single `buys.pop()`.
+    Lifo,
+    /// Every buy lot for a token shares one running weighted-average cost basis instead
+    /// of being tracked as discrete lots.
+    AverageCost,
+}
+
+impl Default for LotMatchingMode {
+    fn default() -> Self {
+        Self::Lifo
+    }
+}
+
 /// Copy trade signal
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CopyTradeSignal {
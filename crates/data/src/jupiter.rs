@@ -66,6 +66,16 @@ struct SwapRequest {
     use_shared_accounts: bool,
     fee_account: Option<String>,
     prioritization_fee_lamports: Option<u64>,
+    as_legacy_transaction: bool,
+    dynamic_compute_unit_limit: bool,
+}
+
+/// Versioned-transaction knobs threaded through from `TradingConfig` - see
+/// `TradingConfig::as_legacy_transaction`/`dynamic_compute_unit_limit`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SwapTransactionOptions {
+    pub as_legacy_transaction: bool,
+    pub dynamic_compute_unit_limit: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -143,6 +153,19 @@ impl JupiterClient {
         quote: QuoteResponse,
         user_pubkey: &Pubkey,
         priority_fee_lamports: Option<u64>,
+    ) -> Result<SwapResponse> {
+        self.get_swap_transaction_with_options(quote, user_pubkey, priority_fee_lamports, SwapTransactionOptions::default())
+            .await
+    }
+
+    /// Get swap transaction from Jupiter, opting into (or out of) versioned transactions
+    /// and Jupiter's own compute-unit simulation via `options`.
+    pub async fn get_swap_transaction_with_options(
+        &self,
+        quote: QuoteResponse,
+        user_pubkey: &Pubkey,
+        priority_fee_lamports: Option<u64>,
+        options: SwapTransactionOptions,
     ) -> Result<SwapResponse> {
         let url = format!("{}/swap", self.api_url);
 
@@ -155,6 +178,8 @@ impl JupiterClient {
             use_shared_accounts: true,
             fee_account: None,
             prioritization_fee_lamports: priority_fee_lamports,
+            as_legacy_transaction: options.as_legacy_transaction,
+            dynamic_compute_unit_limit: options.dynamic_compute_unit_limit,
         };
 
         let response = self
@@ -3,9 +3,29 @@ pub mod token;
 pub mod transaction;
 pub mod scam_check;
 pub mod jupiter;
+pub mod sanctum;
+pub mod mock_swap;
+pub mod swap_provider;
+pub mod versioned_swap;
+pub mod priority_fee;
+pub mod price_oracle;
+pub mod price_stream;
+pub mod price_window;
+pub mod wallet_discovery;
+pub mod wallet_stream;
 
 pub use rpc::*;
 pub use token::*;
 pub use transaction::*;
 pub use scam_check::*;
 pub use jupiter::*;
+pub use sanctum::*;
+pub use mock_swap::*;
+pub use swap_provider::*;
+pub use versioned_swap::*;
+pub use priority_fee::*;
+pub use price_oracle::*;
+pub use price_stream::*;
+pub use price_window::*;
+pub use wallet_discovery::*;
+pub use wallet_stream::*;
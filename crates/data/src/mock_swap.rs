@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::fs;
+
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use tracing::debug;
+use trading_core::{Result, TradingError};
+
+/// A synthesized quote for one mint pair, keyed by `"{input_mint}/{output_mint}"` in the
+/// fixture file. `price` is how many output-mint base units one input-mint base unit is
+/// worth; `price_impact_pct`/`fee_bps` let dry-run strategy code exercise the same
+/// impact/fee-aware sizing paths a live quote would.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MockPriceEntry {
+    pub price: f64,
+    #[serde(default)]
+    pub price_impact_pct: f64,
+    #[serde(default)]
+    pub fee_bps: u16,
+}
+
+/// The fixed or file-driven price table `MockJupiterClient` quotes from. Falls back to a
+/// flat 1:1 price with no impact/fee for any pair not in the table, so dry-run mode never
+/// hard-fails just because a fixture is missing an entry.
+#[derive(Debug, Clone, Default)]
+pub struct MockPriceTable {
+    entries: HashMap<String, MockPriceEntry>,
+}
+
+impl MockPriceTable {
+    pub fn new(entries: HashMap<String, MockPriceEntry>) -> Self {
+        Self { entries }
+    }
+
+    /// Load a table from a JSON file of `{"<input_mint>/<output_mint>": {"price": ..., ...}}`.
+    pub fn from_file(path: &str) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| TradingError::ParseError(format!("Failed to read mock price table {}: {}", path, e)))?;
+        let entries: HashMap<String, MockPriceEntry> = serde_json::from_str(&contents)
+            .map_err(|e| TradingError::ParseError(format!("Failed to parse mock price table {}: {}", path, e)))?;
+        Ok(Self::new(entries))
+    }
+
+    fn lookup(&self, input_mint: &Pubkey, output_mint: &Pubkey) -> MockPriceEntry {
+        let key = format!("{}/{}", input_mint, output_mint);
+        self.entries.get(&key).cloned().unwrap_or(MockPriceEntry {
+            price: 1.0,
+            price_impact_pct: 0.0,
+            fee_bps: 0,
+        })
+    }
+}
+
+/// A no-network stand-in for `JupiterClient`, used when `TradingConfig::enabled` is
+/// false: it synthesizes `QuoteResponse`/`SwapResponse`-shaped data from a
+/// `MockPriceTable` instead of calling the live API, so strategy and risk-limit code run
+/// unchanged in dry-run - right down to price impact and slippage math - without ever
+/// submitting a transaction.
+pub struct MockJupiterClient {
+    table: MockPriceTable,
+}
+
+impl MockJupiterClient {
+    pub fn new(table: MockPriceTable) -> Self {
+        Self { table }
+    }
+
+    pub fn get_quote(
+        &self,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> MockQuote {
+        let entry = self.table.lookup(input_mint, output_mint);
+        let out_amount = ((amount as f64) * entry.price) as u64;
+        let fee_amount = (out_amount as u128 * entry.fee_bps as u128 / 10_000) as u64;
+
+        debug!(
+            "Mock quote: {} {} -> {} {} (impact {}%, fee {}bps)",
+            amount, input_mint, out_amount, output_mint, entry.price_impact_pct, entry.fee_bps
+        );
+
+        MockQuote {
+            input_mint: *input_mint,
+            output_mint: *output_mint,
+            in_amount: amount,
+            out_amount,
+            fee_amount,
+            fee_bps: entry.fee_bps,
+            price_impact_pct: entry.price_impact_pct,
+            slippage_bps,
+        }
+    }
+
+    pub fn get_swap_transaction(&self, quote: &MockQuote) -> MockSwap {
+        MockSwap {
+            // A real transaction never leaves the ground in dry-run; this placeholder
+            // exists only so callers that log/inspect `swap_transaction` don't special-case
+            // mock mode.
+            swap_transaction: format!(
+                "MOCK::{}->{}::{}",
+                quote.input_mint, quote.output_mint, quote.out_amount
+            ),
+        }
+    }
+
+    pub fn get_price(&self, input_mint: &Pubkey, output_mint: &Pubkey) -> Result<Decimal> {
+        let entry = self.table.lookup(input_mint, output_mint);
+        Decimal::try_from(entry.price)
+            .map_err(|e| TradingError::ParseError(format!("Invalid mock price: {}", e)))
+    }
+}
+
+/// Mirrors `jupiter::QuoteResponse`'s fields relevant to sizing/impact math, without the
+/// route-plan structure a synthesized quote has no use for.
+#[derive(Debug, Clone)]
+pub struct MockQuote {
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub in_amount: u64,
+    pub out_amount: u64,
+    pub fee_amount: u64,
+    pub fee_bps: u16,
+    pub price_impact_pct: f64,
+    pub slippage_bps: u16,
+}
+
+/// Mirrors `jupiter::SwapResponse`'s shape; `swap_transaction` is a placeholder string,
+/// never a real signable transaction.
+#[derive(Debug, Clone)]
+pub struct MockSwap {
+    pub swap_transaction: String,
+}
@@ -0,0 +1,502 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures_util::{SinkExt, StreamExt};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use tokio::sync::RwLock;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+use trading_core::{Result, TradingError};
+
+use crate::jupiter::JupiterClient;
+use crate::rpc::FallbackRpcClient;
+use crate::token::TokenDataFetcher;
+
+/// A price reading from a single source, with enough metadata for the oracle to judge
+/// freshness and cross-source agreement.
+#[derive(Debug, Clone, Copy)]
+pub struct PriceQuote {
+    pub price_usd: Decimal,
+    pub observed_at: DateTime<Utc>,
+}
+
+/// One venue the oracle can read a price from, ranked by the order sources are passed
+/// to `PriceOracle::new` (first = primary).
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn get_price(&self, mint: &Pubkey) -> Result<PriceQuote>;
+}
+
+/// DexScreener via `TokenDataFetcher`, the primary source.
+pub struct DexScreenerSource {
+    fetcher: TokenDataFetcher,
+}
+
+impl DexScreenerSource {
+    pub fn new(fetcher: TokenDataFetcher) -> Self {
+        Self { fetcher }
+    }
+}
+
+#[async_trait]
+impl PriceSource for DexScreenerSource {
+    fn name(&self) -> &'static str {
+        "DexScreener"
+    }
+
+    async fn get_price(&self, mint: &Pubkey) -> Result<PriceQuote> {
+        let token = self.fetcher.get_token_data(mint).await?;
+        Ok(PriceQuote {
+            price_usd: token.market_data.price_usd,
+            observed_at: token.updated_at,
+        })
+    }
+}
+
+/// An on-chain AMM pool read as a fallback, computed directly from pool token-account
+/// reserves rather than an off-chain indexer. The caller supplies the pool's base/quote
+/// token accounts and the USD price of the quote side (e.g. SOL or USDC).
+pub struct OnChainPoolSource {
+    rpc: FallbackRpcClient,
+    base_vault: Pubkey,
+    quote_vault: Pubkey,
+    quote_price_usd: Decimal,
+    base_decimals: u8,
+    quote_decimals: u8,
+}
+
+impl OnChainPoolSource {
+    pub fn new(
+        rpc: FallbackRpcClient,
+        base_vault: Pubkey,
+        quote_vault: Pubkey,
+        quote_price_usd: Decimal,
+        base_decimals: u8,
+        quote_decimals: u8,
+    ) -> Self {
+        Self {
+            rpc,
+            base_vault,
+            quote_vault,
+            quote_price_usd,
+            base_decimals,
+            quote_decimals,
+        }
+    }
+}
+
+#[async_trait]
+impl PriceSource for OnChainPoolSource {
+    fn name(&self) -> &'static str {
+        "OnChainPool"
+    }
+
+    async fn get_price(&self, _mint: &Pubkey) -> Result<PriceQuote> {
+        let base_reserve = self.rpc.get_token_account_balance(&self.base_vault).await?;
+        let quote_reserve = self.rpc.get_token_account_balance(&self.quote_vault).await?;
+
+        if base_reserve == 0 {
+            return Err(TradingError::DataFetchError(
+                "On-chain pool has zero base reserve".to_string(),
+            ));
+        }
+
+        let base_amount = Decimal::from(base_reserve) / Decimal::from(10u64.pow(self.base_decimals as u32));
+        let quote_amount = Decimal::from(quote_reserve) / Decimal::from(10u64.pow(self.quote_decimals as u32));
+
+        let price_in_quote = quote_amount / base_amount;
+        let price_usd = price_in_quote * self.quote_price_usd;
+
+        Ok(PriceQuote {
+            price_usd,
+            observed_at: Utc::now(),
+        })
+    }
+}
+
+/// Jupiter's aggregated quote, used as the last-resort fallback.
+pub struct JupiterPriceSource {
+    client: JupiterClient,
+    quote_mint: Pubkey,
+    quote_decimals: u8,
+}
+
+impl JupiterPriceSource {
+    pub fn new(client: JupiterClient, quote_mint: Pubkey, quote_decimals: u8) -> Self {
+        Self {
+            client,
+            quote_mint,
+            quote_decimals,
+        }
+    }
+}
+
+#[async_trait]
+impl PriceSource for JupiterPriceSource {
+    fn name(&self) -> &'static str {
+        "Jupiter"
+    }
+
+    async fn get_price(&self, mint: &Pubkey) -> Result<PriceQuote> {
+        let price = self
+            .client
+            .get_price(mint, &self.quote_mint, self.quote_decimals)
+            .await?;
+
+        Ok(PriceQuote {
+            price_usd: price,
+            observed_at: Utc::now(),
+        })
+    }
+}
+
+/// Birdeye's `/defi/price` endpoint, queried directly with the API key from
+/// `DataSourcesConfig::birdeye_api_key`.
+pub struct BirdeyeSource {
+    client: Client,
+    api_url: String,
+    api_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BirdeyePriceResponse {
+    data: Option<BirdeyePriceData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BirdeyePriceData {
+    value: f64,
+    #[serde(rename = "updateUnixTime")]
+    update_unix_time: i64,
+}
+
+impl BirdeyeSource {
+    pub fn new(api_url: String, api_key: String) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .expect("Failed to create HTTP client"),
+            api_url,
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl PriceSource for BirdeyeSource {
+    fn name(&self) -> &'static str {
+        "Birdeye"
+    }
+
+    async fn get_price(&self, mint: &Pubkey) -> Result<PriceQuote> {
+        let url = format!("{}/defi/price", self.api_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .header("X-API-KEY", &self.api_key)
+            .query(&[("address", mint.to_string())])
+            .send()
+            .await
+            .map_err(|e| TradingError::DataFetchError(format!("Birdeye request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(TradingError::DataFetchError(format!(
+                "Birdeye price lookup failed with status {}",
+                status
+            )));
+        }
+
+        let parsed: BirdeyePriceResponse = response
+            .json()
+            .await
+            .map_err(|e| TradingError::ParseError(format!("Failed to parse Birdeye response: {}", e)))?;
+
+        let data = parsed.data.ok_or_else(|| {
+            TradingError::DataFetchError(format!("Birdeye has no price for {}", mint))
+        })?;
+
+        Ok(PriceQuote {
+            price_usd: Decimal::try_from(data.value)
+                .map_err(|e| TradingError::ParseError(format!("Invalid Birdeye price: {}", e)))?,
+            observed_at: DateTime::from_timestamp(data.update_unix_time, 0).unwrap_or_else(Utc::now),
+        })
+    }
+}
+
+/// A live price map fed by a WebSocket ticker feed, usable as just another `PriceSource`
+/// in `PriceOracle`'s ranked list. Like `price_stream::PriceStream` it answers pings and
+/// reconnects when the socket drops, but it sends its subscribe frame without waiting for
+/// a status handshake, and on a heartbeat timeout it only sends a ping: a socket that
+/// stays quiet is not reconnected. It keys a plain price map instead of writing into
+/// `TokenDataFetcher`'s token cache.
+pub struct StreamingPriceSource {
+    ws_url: String,
+    prices: Arc<RwLock<HashMap<Pubkey, PriceQuote>>>,
+    heartbeat_timeout: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamFrame {
+    Subscribed { mints: Vec<String> },
+    Ticker { mint: String, price_usd: String },
+    Pong,
+}
+
+impl StreamingPriceSource {
+    pub fn new(ws_url: String) -> Self {
+        Self {
+            ws_url,
+            prices: Arc::new(RwLock::new(HashMap::new())),
+            heartbeat_timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Start the connection loop in the background. Reconnects on drop and re-subscribes
+    /// to every mint this source has ever been asked for.
+    pub fn run(&self) {
+        let ws_url = self.ws_url.clone();
+        let prices = self.prices.clone();
+        let heartbeat_timeout = self.heartbeat_timeout;
+
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = Self::run_connection(&ws_url, &prices, heartbeat_timeout).await {
+                    warn!("Streaming price source connection to {} ended: {}", ws_url, e);
+                }
+                tokio::time::sleep(Duration::from_secs(2)).await;
+            }
+        });
+    }
+
+    async fn run_connection(
+        ws_url: &str,
+        prices: &Arc<RwLock<HashMap<Pubkey, PriceQuote>>>,
+        heartbeat_timeout: Duration,
+    ) -> Result<()> {
+        info!("Opening streaming price source WebSocket to {}", ws_url);
+        let (ws, _response) = tokio_tungstenite::connect_async(ws_url)
+            .await
+            .map_err(|e| TradingError::DataFetchError(format!("Streaming price source connect failed: {}", e)))?;
+        let (mut write, mut read) = ws.split();
+
+        let mints: Vec<String> = prices.read().await.keys().map(|m| m.to_string()).collect();
+        if !mints.is_empty() {
+            let frame = serde_json::json!({ "type": "subscribe", "mints": mints }).to_string();
+            write
+                .send(Message::Text(frame))
+                .await
+                .map_err(|e| TradingError::DataFetchError(format!("Failed to send subscribe frame: {}", e)))?;
+        }
+
+        loop {
+            match tokio::time::timeout(heartbeat_timeout, read.next()).await {
+                Ok(Some(Ok(Message::Text(text)))) => match serde_json::from_str::<StreamFrame>(&text) {
+                    Ok(StreamFrame::Ticker { mint, price_usd }) => {
+                        Self::handle_ticker(prices, &mint, &price_usd).await;
+                    }
+                    Ok(StreamFrame::Subscribed { mints }) => {
+                        debug!("Streaming price source confirmed subscription to {} mints", mints.len());
+                    }
+                    Ok(StreamFrame::Pong) => {}
+                    Err(e) => debug!("Ignoring unparseable streaming price frame: {}", e),
+                },
+                Ok(Some(Ok(Message::Ping(payload)))) => {
+                    let _ = write.send(Message::Pong(payload)).await;
+                }
+                Ok(Some(Ok(_))) => {}
+                Ok(Some(Err(e))) => {
+                    return Err(TradingError::DataFetchError(format!("Streaming price source error: {}", e)));
+                }
+                Ok(None) => {
+                    return Err(TradingError::DataFetchError(
+                        "Streaming price source socket closed by server".to_string(),
+                    ));
+                }
+                Err(_) => {
+                    warn!("Streaming price source heartbeat timeout on {}, sending ping", ws_url);
+                    let ping = serde_json::json!({ "type": "ping" }).to_string();
+                    if write.send(Message::Text(ping)).await.is_err() {
+                        return Err(TradingError::DataFetchError(
+                            "Streaming price source ping failed, reconnecting".to_string(),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    async fn handle_ticker(prices: &Arc<RwLock<HashMap<Pubkey, PriceQuote>>>, mint: &str, price_usd: &str) {
+        let Ok(mint) = Pubkey::from_str(mint) else {
+            debug!("Streaming price source ticker had unparseable mint: {}", mint);
+            return;
+        };
+        let price_usd = Decimal::from_str(price_usd).unwrap_or(Decimal::ZERO);
+
+        prices.write().await.insert(
+            mint,
+            PriceQuote {
+                price_usd,
+                observed_at: Utc::now(),
+            },
+        );
+    }
+}
+
+#[async_trait]
+impl PriceSource for StreamingPriceSource {
+    fn name(&self) -> &'static str {
+        "StreamingFeed"
+    }
+
+    async fn get_price(&self, mint: &Pubkey) -> Result<PriceQuote> {
+        self.prices.write().await.entry(*mint).or_insert_with(|| PriceQuote {
+            price_usd: Decimal::ZERO,
+            observed_at: DateTime::<Utc>::MIN_UTC,
+        });
+
+        self.prices
+            .read()
+            .await
+            .get(mint)
+            .copied()
+            .filter(|q| q.observed_at > DateTime::<Utc>::MIN_UTC)
+            .ok_or_else(|| TradingError::DataFetchError(format!("No streamed price yet for {}", mint)))
+    }
+}
+
+/// Aggregates price sources with ranked fallback: the first source whose reading is
+/// fresh within `max_staleness_seconds` wins. If multiple sources are fresh and they
+/// disagree by more than `disagreement_threshold_pct`, the result is flagged
+/// low-confidence so callers can downgrade the signal rather than blindly trade on it.
+pub struct PriceOracle {
+    sources: Vec<Box<dyn PriceSource>>,
+    max_staleness_seconds: i64,
+    disagreement_threshold_pct: f64,
+    last_good: RwLock<Option<(OraclePrice, DateTime<Utc>)>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct OraclePrice {
+    pub price_usd: Decimal,
+    pub source: &'static str,
+    pub low_confidence: bool,
+}
+
+/// Snapshot of the oracle's cache for `MonitoringConfig` metrics: which source answered
+/// last and how long ago, so dashboards can alert when every live source is down and the
+/// bot is trading (or not trading) on a stale cached value.
+#[derive(Debug, Clone, Copy)]
+pub struct OracleHealth {
+    pub source: &'static str,
+    pub age_seconds: i64,
+}
+
+impl PriceOracle {
+    pub fn new(
+        sources: Vec<Box<dyn PriceSource>>,
+        max_staleness_seconds: i64,
+        disagreement_threshold_pct: f64,
+    ) -> Self {
+        Self {
+            sources,
+            max_staleness_seconds,
+            disagreement_threshold_pct,
+            last_good: RwLock::new(None),
+        }
+    }
+
+    /// The most recent successful lookup across all mints this oracle has served, and how
+    /// long ago it was - `None` until the first successful `get_price` call.
+    pub async fn health(&self) -> Option<OracleHealth> {
+        let last_good = self.last_good.read().await;
+        last_good.as_ref().map(|(price, observed_at)| OracleHealth {
+            source: price.source,
+            age_seconds: (Utc::now() - *observed_at).num_seconds(),
+        })
+    }
+
+    pub async fn get_price(&self, mint: &Pubkey) -> Result<OraclePrice> {
+        let mut fresh_quotes: Vec<(&'static str, PriceQuote)> = Vec::new();
+
+        for source in &self.sources {
+            match source.get_price(mint).await {
+                Ok(quote) => {
+                    let age = (Utc::now() - quote.observed_at).num_seconds();
+                    if age <= self.max_staleness_seconds {
+                        debug!("{} quote for {} is fresh ({}s old)", source.name(), mint, age);
+                        fresh_quotes.push((source.name(), quote));
+                    } else {
+                        warn!("{} quote for {} is stale ({}s old), skipping", source.name(), mint, age);
+                    }
+                }
+                Err(e) => {
+                    warn!("{} price lookup failed for {}: {}", source.name(), mint, e);
+                }
+            }
+
+            // The first fresh quote is enough to answer; we only keep reading further
+            // sources to detect disagreement when we already have one.
+            if fresh_quotes.len() >= 2 {
+                break;
+            }
+        }
+
+        let Some((primary_source, primary_quote)) = fresh_quotes.first().copied() else {
+            if let Some((cached, observed_at)) = *self.last_good.read().await {
+                warn!(
+                    "All price sources failed for {}, falling back to {}-old cached {} quote",
+                    mint,
+                    (Utc::now() - observed_at).num_seconds(),
+                    cached.source
+                );
+                return Ok(OraclePrice {
+                    low_confidence: true,
+                    ..cached
+                });
+            }
+            return Err(TradingError::DataFetchError(format!(
+                "No price source returned a fresh quote for {}",
+                mint
+            )));
+        };
+
+        let low_confidence = fresh_quotes.iter().skip(1).any(|(_, quote)| {
+            if primary_quote.price_usd.is_zero() {
+                return false;
+            }
+            let diff_pct = ((quote.price_usd - primary_quote.price_usd) / primary_quote.price_usd
+                * Decimal::from(100))
+            .abs();
+            diff_pct > Decimal::from_f64_retain(self.disagreement_threshold_pct).unwrap_or(Decimal::MAX)
+        });
+
+        if low_confidence {
+            warn!(
+                "Price sources disagree beyond {}% for {}, flagging low-confidence",
+                self.disagreement_threshold_pct, mint
+            );
+        }
+
+        let result = OraclePrice {
+            price_usd: primary_quote.price_usd,
+            source: primary_source,
+            low_confidence,
+        };
+
+        *self.last_good.write().await = Some((result, Utc::now()));
+
+        Ok(result)
+    }
+}
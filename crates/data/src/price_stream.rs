@@ -0,0 +1,266 @@
+use chrono::Utc;
+use futures_util::{SinkExt, StreamExt};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, error, info, warn};
+use trading_core::{MarketData, Result, Token, TokenMetadata, TradingError};
+
+use crate::token::TokenDataFetcher;
+
+/// One live tick pushed by the feed for a subscribed mint.
+#[derive(Debug, Clone)]
+pub struct PriceTick {
+    pub mint: Pubkey,
+    pub price_usd: Decimal,
+    pub volume_24h: Decimal,
+    pub observed_at: chrono::DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientFrame<'a> {
+    Subscribe { mints: &'a [String] },
+    Ping,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerFrame {
+    Status { status: String },
+    Subscribed { mints: Vec<String> },
+    Ticker {
+        mint: String,
+        price_usd: String,
+        #[serde(default)]
+        volume_24h: String,
+    },
+    Pong,
+}
+
+/// Persistent WebSocket connection to a ticker feed, pushing live prices into the same
+/// cache `TokenDataFetcher::get_token_data` reads from so the trading loop reacts to
+/// price moves without re-polling DexScreener. Models a typical exchange feed: a
+/// system-status/subscribe handshake, a stream of ticker frames, and a heartbeat
+/// watchdog that forces a reconnect (with automatic resubscribe) if the socket goes
+/// quiet.
+pub struct PriceStream {
+    ws_url: String,
+    cache: Arc<RwLock<HashMap<Pubkey, (Token, i64)>>>,
+    subscribed: Arc<RwLock<HashSet<Pubkey>>>,
+    ticks: broadcast::Sender<PriceTick>,
+    heartbeat_timeout: Duration,
+}
+
+impl PriceStream {
+    /// Build a stream that writes ticks into `fetcher`'s own cache, so callers can keep
+    /// using `TokenDataFetcher::get_token_data` and transparently get push-fresh prices.
+    pub fn new(ws_url: String, fetcher: &TokenDataFetcher) -> Self {
+        let (ticks, _rx) = broadcast::channel(1024);
+        Self {
+            ws_url,
+            cache: fetcher.cache_handle(),
+            subscribed: Arc::new(RwLock::new(HashSet::new())),
+            ticks,
+            heartbeat_timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Register `mint` for live ticks. Returns a receiver of all ticks broadcast by the
+    /// stream; callers filter for their mint of interest. This does not start the
+    /// connection (see `run`) and sends nothing on a connection that is already up: the
+    /// mint goes out in the subscribe frame of the next (re)connect, so a mint added while
+    /// connected gets no ticks until the stream reconnects.
+    pub async fn subscribe(&self, mint: Pubkey) -> broadcast::Receiver<PriceTick> {
+        let mut subscribed = self.subscribed.write().await;
+        subscribed.insert(mint);
+        self.ticks.subscribe()
+    }
+
+    /// Start the connection loop in the background. Safe to call once; reconnects and
+    /// resubscribes to every mint seen via `subscribe` so far whenever the socket drops.
+    pub fn run(&self) {
+        let ws_url = self.ws_url.clone();
+        let cache = self.cache.clone();
+        let subscribed = self.subscribed.clone();
+        let ticks = self.ticks.clone();
+        let heartbeat_timeout = self.heartbeat_timeout;
+
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) =
+                    Self::run_connection(&ws_url, &cache, &subscribed, &ticks, heartbeat_timeout).await
+                {
+                    warn!("Price stream connection to {} ended: {}", ws_url, e);
+                }
+                tokio::time::sleep(Duration::from_secs(2)).await;
+            }
+        });
+    }
+
+    async fn run_connection(
+        ws_url: &str,
+        cache: &Arc<RwLock<HashMap<Pubkey, (Token, i64)>>>,
+        subscribed: &Arc<RwLock<HashSet<Pubkey>>>,
+        ticks: &broadcast::Sender<PriceTick>,
+        heartbeat_timeout: Duration,
+    ) -> Result<()> {
+        info!("Opening price stream WebSocket to {}", ws_url);
+        let (ws, _response) = tokio_tungstenite::connect_async(ws_url)
+            .await
+            .map_err(|e| TradingError::DataFetchError(format!("Price stream connect failed: {}", e)))?;
+        let (mut write, mut read) = ws.split();
+
+        // Handshake: wait for the feed's system-status frame before subscribing.
+        match tokio::time::timeout(Duration::from_secs(10), read.next()).await {
+            Ok(Some(Ok(Message::Text(text)))) => match serde_json::from_str::<ServerFrame>(&text) {
+                Ok(ServerFrame::Status { status }) if status == "online" => {
+                    debug!("Price stream handshake ok ({})", ws_url);
+                }
+                other => {
+                    return Err(TradingError::DataFetchError(format!(
+                        "Unexpected handshake frame from price stream: {:?}",
+                        other
+                    )));
+                }
+            },
+            _ => {
+                return Err(TradingError::DataFetchError(
+                    "Price stream closed before sending a status frame".to_string(),
+                ));
+            }
+        }
+
+        let mints: Vec<String> = subscribed.read().await.iter().map(|m| m.to_string()).collect();
+        if !mints.is_empty() {
+            let frame = serde_json::to_string(&ClientFrame::Subscribe { mints: &mints })
+                .map_err(|e| TradingError::ParseError(e.to_string()))?;
+            write
+                .send(Message::Text(frame))
+                .await
+                .map_err(|e| TradingError::DataFetchError(format!("Failed to send subscribe frame: {}", e)))?;
+        }
+
+        let mut awaiting_pong = false;
+        loop {
+            let next = tokio::time::timeout(heartbeat_timeout, read.next()).await;
+            if next.is_ok() {
+                awaiting_pong = false;
+            }
+            match next {
+                Ok(Some(Ok(Message::Text(text)))) => {
+                    match serde_json::from_str::<ServerFrame>(&text) {
+                        Ok(ServerFrame::Ticker {
+                            mint,
+                            price_usd,
+                            volume_24h,
+                        }) => {
+                            Self::handle_ticker(cache, ticks, &mint, &price_usd, &volume_24h).await;
+                        }
+                        Ok(ServerFrame::Subscribed { mints }) => {
+                            debug!("Price stream confirmed subscription to {} mints", mints.len());
+                        }
+                        Ok(ServerFrame::Pong) | Ok(ServerFrame::Status { .. }) => {}
+                        Err(e) => {
+                            debug!("Ignoring unparseable price stream frame: {}", e);
+                        }
+                    }
+                }
+                Ok(Some(Ok(Message::Ping(payload)))) => {
+                    let _ = write.send(Message::Pong(payload)).await;
+                }
+                Ok(Some(Ok(_))) => {}
+                Ok(Some(Err(e))) => {
+                    return Err(TradingError::DataFetchError(format!("Price stream error: {}", e)));
+                }
+                Ok(None) => {
+                    return Err(TradingError::DataFetchError(
+                        "Price stream closed by server".to_string(),
+                    ));
+                }
+                Err(_) => {
+                    // No frame within heartbeat_timeout; send a ping and let the next
+                    // timeout (if it also misses) be caught by the reconnect loop above.
+                    if awaiting_pong {
+                        return Err(TradingError::DataFetchError(
+                            "Price stream missed two heartbeats, reconnecting".to_string(),
+                        ));
+                    }
+                    awaiting_pong = true;
+                    warn!("Price stream heartbeat timeout on {}, sending ping", ws_url);
+                    let ping = serde_json::to_string(&ClientFrame::Ping)
+                        .unwrap_or_else(|_| "{\"type\":\"ping\"}".to_string());
+                    if write.send(Message::Text(ping)).await.is_err() {
+                        return Err(TradingError::DataFetchError(
+                            "Price stream ping failed, reconnecting".to_string(),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    async fn handle_ticker(
+        cache: &Arc<RwLock<HashMap<Pubkey, (Token, i64)>>>,
+        ticks: &broadcast::Sender<PriceTick>,
+        mint: &str,
+        price_usd: &str,
+        volume_24h: &str,
+    ) {
+        let Ok(mint) = Pubkey::from_str(mint) else {
+            debug!("Price stream ticker had unparseable mint: {}", mint);
+            return;
+        };
+        let price_usd = Decimal::from_str(price_usd).unwrap_or(Decimal::ZERO);
+        let volume_24h = Decimal::from_str(volume_24h).unwrap_or(Decimal::ZERO);
+        let now = Utc::now();
+
+        {
+            let mut cache = cache.write().await;
+            let entry = cache.entry(mint).or_insert_with(|| {
+                (
+                    Token {
+                        mint,
+                        symbol: String::new(),
+                        name: String::new(),
+                        decimals: 9,
+                        metadata: TokenMetadata::default(),
+                        security: Default::default(),
+                        market_data: MarketData {
+                            price_usd: Decimal::ZERO,
+                            price_sol: Decimal::ZERO,
+                            market_cap: Decimal::ZERO,
+                            liquidity_usd: Decimal::ZERO,
+                            volume_24h: Decimal::ZERO,
+                            price_change_24h: 0.0,
+                            price_change_1h: 0.0,
+                            price_change_5m: 0.0,
+                            holders: None,
+                            dex: None,
+                        },
+                        created_at: now,
+                        updated_at: now,
+                    },
+                    now.timestamp(),
+                )
+            });
+            entry.0.market_data.price_usd = price_usd;
+            entry.0.market_data.volume_24h = volume_24h;
+            entry.0.updated_at = now;
+            entry.1 = now.timestamp();
+        }
+
+        let _ = ticks.send(PriceTick {
+            mint,
+            price_usd,
+            volume_24h,
+            observed_at: now,
+        });
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+#[derive(Debug, Clone, Copy)]
+struct PriceSample {
+    price: Decimal,
+    volume: Decimal,
+    at: DateTime<Utc>,
+}
+
+/// Rolling per-mint price history backing `TokenDataFetcher::vwap`/`twap`, so trading
+/// logic can compare the latest print against a smoothed reference instead of reacting
+/// to a single anomalous tick. Samples are recorded each time `get_token_data` fetches
+/// fresh data (a cache hit records nothing) and are evicted once they are more than a
+/// day older than the mint's newest sample.
+pub struct PriceWindowTracker {
+    samples: Arc<RwLock<HashMap<Pubkey, VecDeque<PriceSample>>>>,
+}
+
+impl Default for PriceWindowTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PriceWindowTracker {
+    pub fn new() -> Self {
+        Self {
+            samples: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Record a price/volume print for `mint` at `at` (the print's observation time, not
+    /// necessarily "now" - `get_token_data` passes the fetched token's `updated_at`).
+    pub async fn record(&self, mint: Pubkey, price: Decimal, volume: Decimal, at: DateTime<Utc>) {
+        let mut samples = self.samples.write().await;
+        let history = samples.entry(mint).or_insert_with(VecDeque::new);
+        history.push_back(PriceSample { price, volume, at });
+
+        // Cap unbounded growth for mints nobody queries a window for; a generous bound
+        // (1 day of per-call samples at a realistic polling cadence) keeps memory flat
+        // without needing to know every caller's window length up front.
+        let cutoff = at - ChronoDuration::days(1);
+        while history.front().is_some_and(|s| s.at < cutoff) {
+            history.pop_front();
+        }
+    }
+
+    /// Volume-weighted average price over the trailing `window`: `Σ(price·volume) / Σ(volume)`.
+    pub async fn vwap(&self, mint: &Pubkey, window: Duration) -> Option<Decimal> {
+        let samples = self.samples.read().await;
+        let history = samples.get(mint)?;
+        let now = history.back()?.at;
+        let cutoff = now - ChronoDuration::seconds(window.as_secs() as i64);
+
+        let mut weighted_sum = Decimal::ZERO;
+        let mut volume_sum = Decimal::ZERO;
+        for sample in history.iter().rev().take_while(|s| s.at >= cutoff) {
+            weighted_sum += sample.price * sample.volume;
+            volume_sum += sample.volume;
+        }
+
+        if volume_sum.is_zero() {
+            None
+        } else {
+            Some(weighted_sum / volume_sum)
+        }
+    }
+
+    /// Exponentially time-weighted mean price with decay constant `tau`: each sample's
+    /// weight is `exp(-Δt/τ)`, so recent prints dominate without a hard window cutoff.
+    pub async fn twap(&self, mint: &Pubkey, tau: Duration) -> Option<Decimal> {
+        let samples = self.samples.read().await;
+        let history = samples.get(mint)?;
+        if history.is_empty() {
+            return None;
+        }
+        let now = history.back()?.at;
+        let tau_secs = tau.as_secs_f64().max(f64::EPSILON);
+
+        let mut weighted_sum = 0.0f64;
+        let mut weight_sum = 0.0f64;
+        for sample in history.iter() {
+            let delta_t = (now - sample.at).num_milliseconds() as f64 / 1000.0;
+            let weight = (-delta_t / tau_secs).exp();
+            let price: f64 = sample.price.to_string().parse().unwrap_or(0.0);
+            weighted_sum += price * weight;
+            weight_sum += weight;
+        }
+
+        if weight_sum == 0.0 {
+            None
+        } else {
+            Decimal::from_f64_retain(weighted_sum / weight_sum)
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use tracing::debug;
+use trading_core::Result;
+
+use crate::rpc::FallbackRpcClient;
+
+/// Compute-budget parameters for a swap transaction, mirroring the Solana CLI's
+/// `compute_unit_price_arg` / `WithComputeUnitPrice`.
+#[derive(Debug, Clone, Copy)]
+pub struct ComputeBudget {
+    /// Micro-lamports per compute unit
+    pub unit_price_micro_lamports: u64,
+    pub unit_limit: u32,
+}
+
+impl ComputeBudget {
+    /// `ComputeBudgetProgram::set_compute_unit_limit` and `set_compute_unit_price`
+    /// instructions, in the order Solana expects them prepended to a transaction.
+    pub fn instructions(&self) -> [Instruction; 2] {
+        [
+            ComputeBudgetInstruction::set_compute_unit_limit(self.unit_limit),
+            ComputeBudgetInstruction::set_compute_unit_price(self.unit_price_micro_lamports),
+        ]
+    }
+}
+
+/// Estimates the compute-unit price to attach to a swap transaction.
+pub struct PriorityFeeEstimator;
+
+impl PriorityFeeEstimator {
+    /// Sample `getRecentPrioritizationFees` for the token's accounts (e.g. the AMM pool
+    /// and the mint) and pick the given percentile, so the fee adapts to current
+    /// congestion instead of using a fixed `priority_fee_microlamports`.
+    pub async fn estimate_auto(
+        rpc: &FallbackRpcClient,
+        accounts: &[Pubkey],
+        percentile: u8,
+        fallback_micro_lamports: u64,
+    ) -> Result<u64> {
+        let samples = rpc.get_recent_prioritization_fees(accounts).await?;
+
+        if samples.is_empty() {
+            debug!("No recent prioritization fee samples, using fallback fee");
+            return Ok(fallback_micro_lamports);
+        }
+
+        let mut fees: Vec<u64> = samples.iter().map(|s| s.prioritization_fee).collect();
+        fees.sort_unstable();
+
+        let percentile = percentile.min(100) as usize;
+        let index = (fees.len().saturating_sub(1) * percentile) / 100;
+        let estimated = fees[index];
+
+        debug!(
+            "Estimated priority fee at p{}: {} micro-lamports/CU ({} samples)",
+            percentile, estimated, fees.len()
+        );
+
+        Ok(estimated)
+    }
+
+    /// Resolve a `ComputeBudget` from config: either the fixed price, or an auto
+    /// estimate sampled against the given accounts.
+    pub async fn resolve(
+        rpc: &FallbackRpcClient,
+        accounts: &[Pubkey],
+        auto: bool,
+        percentile: u8,
+        fixed_micro_lamports: u64,
+        unit_limit: u32,
+    ) -> Result<ComputeBudget> {
+        let unit_price_micro_lamports = if auto {
+            Self::estimate_auto(rpc, accounts, percentile, fixed_micro_lamports).await?
+        } else {
+            fixed_micro_lamports
+        };
+
+        Ok(ComputeBudget {
+            unit_price_micro_lamports,
+            unit_limit,
+        })
+    }
+}
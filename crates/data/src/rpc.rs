@@ -1,7 +1,14 @@
+use futures_util::StreamExt;
+use solana_account_decoder::UiAccountEncoding;
 use solana_client::{
     nonblocking::rpc_client::RpcClient,
-    rpc_config::RpcTransactionConfig,
-    rpc_response::RpcConfirmedTransactionStatusWithSignature,
+    nonblocking::pubsub_client::PubsubClient,
+    rpc_config::{
+        RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionConfig,
+        RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+    },
+    rpc_filter::RpcFilterType,
+    rpc_response::{RpcConfirmedTransactionStatusWithSignature, RpcLogsResponse},
 };
 use solana_sdk::{
     commitment_config::CommitmentConfig,
@@ -9,18 +16,203 @@ use solana_sdk::{
     signature::Signature,
 };
 use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
+use std::collections::VecDeque;
+use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::RwLock;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, RwLock, Semaphore};
 use tracing::{debug, error, info, warn};
 use trading_core::{Result, TradingError};
 
+/// A confirmed log entry yielded by a `logsSubscribe` stream
+#[derive(Debug, Clone)]
+pub struct LogEvent {
+    pub signature: Signature,
+    pub logs: Vec<String>,
+    pub err: Option<String>,
+}
+
+/// Doubles the reconnect delay on each consecutive failure, up to a ceiling, so a flapping
+/// endpoint doesn't get hammered every 2 seconds; `reset()` drops back to the base delay as
+/// soon as a subscription is established.
+struct ReconnectBackoff {
+    delay: Duration,
+}
+
+impl ReconnectBackoff {
+    const BASE: Duration = Duration::from_secs(2);
+    const MAX: Duration = Duration::from_secs(30);
+
+    fn new() -> Self {
+        Self { delay: Self::BASE }
+    }
+
+    fn reset(&mut self) {
+        self.delay = Self::BASE;
+    }
+
+    fn next_delay(&mut self) -> Duration {
+        let delay = self.delay;
+        self.delay = (self.delay * 2).min(Self::MAX);
+        delay
+    }
+}
+
+/// Caps the aggregate rate of calls made through a `FallbackRpcClient`, shared across every
+/// concurrent caller rather than per-caller - so a bounded worker pool fanning `analyze_wallet`
+/// out across many wallets can't collectively blow through the provider's requests-per-second
+/// limit even though no single worker is doing anything wrong on its own.
+struct RateLimiter {
+    semaphore: Arc<Semaphore>,
+}
+
+impl RateLimiter {
+    fn new(max_rps: usize) -> Self {
+        let semaphore = Arc::new(Semaphore::new(max_rps));
+        let refill = semaphore.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(1));
+            loop {
+                interval.tick().await;
+                let available = refill.available_permits();
+                if available < max_rps {
+                    refill.add_permits(max_rps - available);
+                }
+            }
+        });
+
+        Self { semaphore }
+    }
+
+    /// Wait for a free slot in the current second's budget. The permit is never returned to
+    /// the pool by the caller - only the refill task above replenishes it - so this throttles
+    /// how often a new call may *start*, independent of how long that call takes to finish.
+    async fn acquire(&self) {
+        self.semaphore
+            .acquire()
+            .await
+            .expect("rate limiter semaphore is never closed")
+            .forget();
+    }
+}
+
+/// How many of the most recent calls an `EndpointHealth` bases its p95 latency and error rate
+/// on - old enough samples age out so a since-recovered endpoint doesn't stay penalized.
+const HEALTH_WINDOW: usize = 20;
+
+/// Consecutive failures before an endpoint is put in cooldown and skipped by endpoint
+/// selection, regardless of how fast it used to be.
+const COOLDOWN_FAILURE_THRESHOLD: u32 = 3;
+
+/// How long an endpoint sits out of selection after tripping `COOLDOWN_FAILURE_THRESHOLD`.
+const COOLDOWN_DURATION: Duration = Duration::from_secs(30);
+
+/// Rolling error rate above which `select_endpoint` treats an endpoint as unhealthy and
+/// ranks it behind every endpoint under the threshold, regardless of latency.
+const MAX_ERROR_RATE_BEFORE_PENALTY: f64 = 0.2;
+
+/// Per-endpoint stats surfaced by `FallbackRpcClient::health_report`.
+#[derive(Debug, Clone)]
+pub struct EndpointStats {
+    pub url: String,
+    /// `None` until at least one call has succeeded.
+    pub p95_latency_ms: Option<u64>,
+    pub error_rate: f64,
+    pub in_cooldown: bool,
+}
+
+/// Tracks one endpoint's recent latency/error history so `FallbackRpcClient::select_endpoint`
+/// can route around a slow-but-up primary instead of only reacting to hard failures.
+struct EndpointHealth {
+    /// Latency of the last `HEALTH_WINDOW` successful calls, in milliseconds.
+    latencies_ms: RwLock<VecDeque<u64>>,
+    /// Outcome of the last `HEALTH_WINDOW` calls (`true` = success), for the rolling error rate.
+    outcomes: RwLock<VecDeque<bool>>,
+    consecutive_failures: RwLock<u32>,
+    cooldown_until: RwLock<Option<Instant>>,
+}
+
+impl EndpointHealth {
+    fn new() -> Self {
+        Self {
+            latencies_ms: RwLock::new(VecDeque::with_capacity(HEALTH_WINDOW)),
+            outcomes: RwLock::new(VecDeque::with_capacity(HEALTH_WINDOW)),
+            consecutive_failures: RwLock::new(0),
+            cooldown_until: RwLock::new(None),
+        }
+    }
+
+    async fn record_success(&self, latency: Duration) {
+        let mut latencies = self.latencies_ms.write().await;
+        if latencies.len() == HEALTH_WINDOW {
+            latencies.pop_front();
+        }
+        latencies.push_back(latency.as_millis() as u64);
+        drop(latencies);
+
+        Self::push_outcome(&mut *self.outcomes.write().await, true);
+        *self.consecutive_failures.write().await = 0;
+        *self.cooldown_until.write().await = None;
+    }
+
+    async fn record_failure(&self) {
+        Self::push_outcome(&mut *self.outcomes.write().await, false);
+
+        let mut failures = self.consecutive_failures.write().await;
+        *failures += 1;
+        if *failures >= COOLDOWN_FAILURE_THRESHOLD {
+            *self.cooldown_until.write().await = Some(Instant::now() + COOLDOWN_DURATION);
+        }
+    }
+
+    fn push_outcome(outcomes: &mut VecDeque<bool>, success: bool) {
+        if outcomes.len() == HEALTH_WINDOW {
+            outcomes.pop_front();
+        }
+        outcomes.push_back(success);
+    }
+
+    async fn is_in_cooldown(&self) -> bool {
+        match *self.cooldown_until.read().await {
+            Some(until) => Instant::now() < until,
+            None => false,
+        }
+    }
+
+    /// p95 latency over the sliding window - `None` until a call has ever succeeded.
+    async fn p95_latency_ms(&self) -> Option<u64> {
+        let latencies = self.latencies_ms.read().await;
+        if latencies.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<u64> = latencies.iter().copied().collect();
+        sorted.sort_unstable();
+        let index = ((sorted.len() as f64) * 0.95).ceil() as usize;
+        Some(sorted[index.saturating_sub(1).min(sorted.len() - 1)])
+    }
+
+    async fn error_rate(&self) -> f64 {
+        let outcomes = self.outcomes.read().await;
+        if outcomes.is_empty() {
+            return 0.0;
+        }
+        let failures = outcomes.iter().filter(|success| !**success).count();
+        failures as f64 / outcomes.len() as f64
+    }
+}
+
 /// RPC client with automatic fallback to backup endpoints
 pub struct FallbackRpcClient {
     primary: Arc<RpcClient>,
     fallbacks: Vec<Arc<RpcClient>>,
-    current_index: Arc<RwLock<usize>>,
+    /// Health of `primary` (index 0) and each of `fallbacks` (index `n + 1`), in the same
+    /// order - `select_endpoint` picks among these by recent latency/error rate instead of
+    /// the fixed primary-then-fallback ordering `execute_with_fallback` used to walk.
+    health: Vec<EndpointHealth>,
     commitment: CommitmentConfig,
+    ws_urls: Vec<String>,
+    rate_limiter: Option<RateLimiter>,
 }
 
 impl FallbackRpcClient {
@@ -44,17 +236,35 @@ impl FallbackRpcClient {
 
         info!("Initialized RPC client with {} fallback endpoints", fallbacks.len());
 
+        let health = (0..fallbacks.len() + 1).map(|_| EndpointHealth::new()).collect();
+
         Self {
             primary,
             fallbacks,
-            current_index: Arc::new(RwLock::new(0)),
+            health,
             commitment,
+            ws_urls: Vec::new(),
+            rate_limiter: None,
         }
     }
 
-    /// Get current active RPC client
-    async fn get_client(&self) -> Arc<RpcClient> {
-        let index = *self.current_index.read().await;
+    /// Attach WebSocket endpoints (primary first, then fallbacks) used by `subscribe_logs`.
+    /// Mirrors the HTTP endpoint order so resubscription falls over the same chain.
+    pub fn with_ws_urls(mut self, ws_urls: Vec<String>) -> Self {
+        self.ws_urls = ws_urls;
+        self
+    }
+
+    /// Cap the aggregate rate of calls made through this client to `max_rps` per second,
+    /// shared across every clone/caller - see `RateLimiter`. Without this, a concurrent
+    /// worker pool is bounded only by its own concurrency, not by what the provider allows.
+    pub fn with_rate_limit(mut self, max_rps: usize) -> Self {
+        self.rate_limiter = Some(RateLimiter::new(max_rps));
+        self
+    }
+
+    /// Client for a given endpoint index (0 = primary, `n + 1` = `fallbacks[n]`).
+    fn client_at(&self, index: usize) -> Arc<RpcClient> {
         if index == 0 {
             self.primary.clone()
         } else {
@@ -65,17 +275,55 @@ impl FallbackRpcClient {
         }
     }
 
-    /// Switch to next fallback endpoint
-    async fn switch_to_fallback(&self) {
-        let mut index = self.current_index.write().await;
-        let max_index = self.fallbacks.len();
-        *index = (*index + 1) % (max_index + 1);
+    /// Pick the endpoint with the lowest recent p95 latency among those not in cooldown and
+    /// not above `MAX_ERROR_RATE_BEFORE_PENALTY`, rather than always starting from the
+    /// primary - so a slow-but-technically-up primary gets passed over without needing to
+    /// hard-fail first. A flaky endpoint that fails often enough to matter but never strings
+    /// together `COOLDOWN_FAILURE_THRESHOLD` consecutive failures would otherwise keep
+    /// winning on latency alone forever; it's only picked if every other endpoint is also in
+    /// cooldown or over the error-rate threshold. An endpoint with no successful calls yet
+    /// reads as 0ms so it gets a chance to prove itself. Falls back to the primary if every
+    /// endpoint is currently in cooldown, rather than refusing to make the call.
+    async fn select_endpoint(&self) -> (usize, Arc<RpcClient>) {
+        let mut best: Option<(usize, u64)> = None;
+        let mut best_unhealthy: Option<(usize, u64)> = None;
 
-        if *index == 0 {
-            warn!("Switched back to primary RPC endpoint");
-        } else {
-            warn!("Switched to fallback RPC endpoint #{}", *index);
+        for (index, health) in self.health.iter().enumerate() {
+            if health.is_in_cooldown().await {
+                continue;
+            }
+            let p95 = health.p95_latency_ms().await.unwrap_or(0);
+
+            if health.error_rate().await > MAX_ERROR_RATE_BEFORE_PENALTY {
+                if best_unhealthy.map_or(true, |(_, best_p95)| p95 < best_p95) {
+                    best_unhealthy = Some((index, p95));
+                }
+                continue;
+            }
+
+            if best.map_or(true, |(_, best_p95)| p95 < best_p95) {
+                best = Some((index, p95));
+            }
         }
+
+        let index = best.or(best_unhealthy).map(|(index, _)| index).unwrap_or(0);
+        (index, self.client_at(index))
+    }
+
+    /// Per-endpoint latency/error stats, in the same order as `new`'s `fallback_urls`
+    /// (primary first). Exposed for operational visibility into why `select_endpoint` is
+    /// routing where it is.
+    pub async fn health_report(&self) -> Vec<EndpointStats> {
+        let mut report = Vec::with_capacity(self.health.len());
+        for (index, health) in self.health.iter().enumerate() {
+            report.push(EndpointStats {
+                url: self.client_at(index).url(),
+                p95_latency_ms: health.p95_latency_ms().await,
+                error_rate: health.error_rate().await,
+                in_cooldown: health.is_in_cooldown().await,
+            });
+        }
+        report
     }
 
     /// Execute RPC call with automatic fallback
@@ -88,16 +336,23 @@ impl FallbackRpcClient {
         let max_attempts = self.fallbacks.len() + 1;
 
         loop {
-            let client = self.get_client().await;
+            if let Some(limiter) = &self.rate_limiter {
+                limiter.acquire().await;
+            }
+
+            let (index, client) = self.select_endpoint().await;
+            let started = Instant::now();
 
             match operation(client).await {
                 Ok(result) => {
+                    self.health[index].record_success(started.elapsed()).await;
                     if attempts > 0 {
                         debug!("RPC call succeeded after {} attempts", attempts + 1);
                     }
                     return Ok(result);
                 }
                 Err(e) => {
+                    self.health[index].record_failure().await;
                     attempts += 1;
                     error!("RPC call failed (attempt {}/{}): {}", attempts, max_attempts, e);
 
@@ -108,7 +363,6 @@ impl FallbackRpcClient {
                         )));
                     }
 
-                    self.switch_to_fallback().await;
                     tokio::time::sleep(Duration::from_millis(500)).await;
                 }
             }
@@ -163,6 +417,94 @@ impl FallbackRpcClient {
         .await
     }
 
+    /// Get recent prioritization fees (micro-lamports per CU) paid by transactions
+    /// touching any of `accounts`, most recent slot first.
+    pub async fn get_recent_prioritization_fees(
+        &self,
+        accounts: &[Pubkey],
+    ) -> Result<Vec<solana_client::rpc_response::RpcPrioritizationFee>> {
+        let accounts = accounts.to_vec();
+        self.execute_with_fallback(|client| {
+            let accounts = accounts.clone();
+            async move { client.get_recent_prioritization_fees(&accounts).await }
+        })
+        .await
+    }
+
+    /// Get a single page of signatures for an address, with an optional `before` cursor
+    /// (walk backwards in time) and `until` cursor (stop once this signature is reached).
+    pub async fn get_signatures_for_address_page(
+        &self,
+        address: &Pubkey,
+        before: Option<Signature>,
+        until: Option<Signature>,
+        limit: usize,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        self.execute_with_fallback(|client| async move {
+            client
+                .get_signatures_for_address_with_config(
+                    address,
+                    solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config {
+                        before,
+                        until,
+                        limit: Some(limit),
+                        ..Default::default()
+                    },
+                )
+                .await
+        })
+        .await
+    }
+
+    /// Top holder token accounts of `mint`, as `(token account pubkey, raw amount)` pairs,
+    /// highest balance first. Each token account still needs resolving back to its owner
+    /// wallet (see `wallet_discovery::token_account_owner`) - `getTokenLargestAccounts` only
+    /// returns the token account itself, not who holds it.
+    pub async fn get_token_largest_accounts(&self, mint: &Pubkey) -> Result<Vec<(Pubkey, u64)>> {
+        self.execute_with_fallback(|client| async move { client.get_token_largest_accounts(mint).await })
+            .await
+            .map(|accounts| {
+                accounts
+                    .into_iter()
+                    .filter_map(|a| {
+                        let account = Pubkey::from_str(&a.address).ok()?;
+                        let amount = a.amount.amount.parse::<u64>().ok()?;
+                        Some((account, amount))
+                    })
+                    .collect()
+            })
+    }
+
+    /// Scan all accounts owned by `program` matching `filters` (typically `Memcmp` byte-offset
+    /// filters plus a `dataSize` filter), returning each match as `(account pubkey, raw data)`.
+    pub async fn get_program_accounts(
+        &self,
+        program: &Pubkey,
+        filters: Vec<RpcFilterType>,
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        self.execute_with_fallback(|client| {
+            let filters = filters.clone();
+            async move {
+                client
+                    .get_program_accounts_with_config(
+                        program,
+                        RpcProgramAccountsConfig {
+                            filters: Some(filters),
+                            account_config: RpcAccountInfoConfig {
+                                encoding: Some(UiAccountEncoding::Base64),
+                                commitment: Some(self.commitment),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                    )
+                    .await
+            }
+        })
+        .await
+        .map(|accounts| accounts.into_iter().map(|(pubkey, account)| (pubkey, account.data)).collect())
+    }
+
     /// Get token account balance
     pub async fn get_token_account_balance(&self, pubkey: &Pubkey) -> Result<u64> {
         self.execute_with_fallback(|client| async move {
@@ -174,6 +516,13 @@ impl FallbackRpcClient {
         .await
     }
 
+    /// Mint decimals, via `getTokenSupply` rather than decoding the mint account by hand.
+    pub async fn get_mint_decimals(&self, mint: &Pubkey) -> Result<u8> {
+        self.execute_with_fallback(|client| async move { client.get_token_supply(mint).await })
+            .await
+            .map(|supply| supply.decimals)
+    }
+
     /// Get slot
     pub async fn get_slot(&self) -> Result<u64> {
         self.execute_with_fallback(|client| async move { client.get_slot().await })
@@ -205,6 +554,20 @@ impl FallbackRpcClient {
             client.send_transaction(transaction).await
         })
         .await
+        .map_err(Self::classify_send_error)
+    }
+
+    /// `execute_with_fallback` reports every send failure as a generic `RpcError`;
+    /// reclassify the "blockhash not found" / "block height exceeded" pattern that
+    /// shows up when a transaction was dropped for lacking enough priority fee to land.
+    fn classify_send_error(error: TradingError) -> TradingError {
+        if let TradingError::RpcError(ref msg) = error {
+            let lower = msg.to_lowercase();
+            if lower.contains("block height exceeded") || lower.contains("blockhash not found") {
+                return TradingError::InsufficientPriorityFee(msg.clone());
+            }
+        }
+        error
     }
 
     /// Get account data
@@ -214,6 +577,154 @@ impl FallbackRpcClient {
         })
         .await
     }
+
+    /// Subscribe to `logsSubscribe` for transactions mentioning any of `mentions`.
+    ///
+    /// Returns a broadcast channel that yields confirmed `(signature, logs)` pairs as they
+    /// arrive, rather than waiting on the periodic polling loop - `subscribe()` the returned
+    /// receiver again to hand a second consumer (e.g. a dashboard) the same stream. The
+    /// subscription auto-reconnects across the configured WebSocket endpoints (set via
+    /// `with_ws_urls`) if the socket drops, resubscribing with the same filter.
+    pub async fn subscribe_logs(&self, mentions: Vec<Pubkey>) -> Result<broadcast::Receiver<LogEvent>> {
+        if self.ws_urls.is_empty() {
+            return Err(TradingError::ConfigError(
+                "No WebSocket endpoints configured; call with_ws_urls() first".to_string(),
+            ));
+        }
+        if mentions.is_empty() {
+            return Err(TradingError::ConfigError(
+                "subscribe_logs requires at least one mentioned pubkey".to_string(),
+            ));
+        }
+
+        let (tx, rx) = broadcast::channel(256);
+        let ws_urls = self.ws_urls.clone();
+        let commitment = self.commitment;
+
+        tokio::spawn(async move {
+            let mentioned: Vec<String> = mentions.iter().map(|p| p.to_string()).collect();
+            let mut ws_index = 0usize;
+            let mut backoff = ReconnectBackoff::new();
+
+            loop {
+                let ws_url = &ws_urls[ws_index % ws_urls.len()];
+                info!("Opening logsSubscribe WebSocket to {}", ws_url);
+
+                match PubsubClient::new(ws_url).await {
+                    Ok(client) => {
+                        let filter = RpcTransactionLogsFilter::Mentions(mentioned.clone());
+                        let config = RpcTransactionLogsConfig {
+                            commitment: Some(commitment),
+                        };
+
+                        match client.logs_subscribe(filter, config).await {
+                            Ok((mut stream, _unsubscribe)) => {
+                                info!("logsSubscribe active on {} ({} mentions)", ws_url, mentioned.len());
+                                backoff.reset();
+
+                                while let Some(update) = stream.next().await {
+                                    if let Some(event) = Self::parse_logs_response(&update.value) {
+                                        if tx.send(event).is_err() {
+                                            debug!("logsSubscribe has no receivers left, stopping");
+                                            return;
+                                        }
+                                    }
+                                }
+
+                                warn!("logsSubscribe stream on {} ended, reconnecting", ws_url);
+                            }
+                            Err(e) => {
+                                error!("logsSubscribe failed on {}: {}", ws_url, e);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        error!("Failed to open pubsub WebSocket {}: {}", ws_url, e);
+                    }
+                }
+
+                ws_index = (ws_index + 1) % ws_urls.len();
+                tokio::time::sleep(backoff.next_delay()).await;
+            }
+        });
+
+        Ok(rx)
+    }
+
+    /// Subscribe to `accountSubscribe` for `account`, yielding its raw data on every update.
+    /// Reconnects with the same backoff/failover behavior as `subscribe_logs`. Broadcast-based
+    /// like `subscribe_logs`, so `subscribe()` the returned receiver again to fan the same
+    /// account stream out to more than one consumer.
+    pub async fn subscribe_account(&self, account: Pubkey) -> Result<broadcast::Receiver<Vec<u8>>> {
+        if self.ws_urls.is_empty() {
+            return Err(TradingError::ConfigError(
+                "No WebSocket endpoints configured; call with_ws_urls() first".to_string(),
+            ));
+        }
+
+        let (tx, rx) = broadcast::channel(256);
+        let ws_urls = self.ws_urls.clone();
+        let commitment = self.commitment;
+
+        tokio::spawn(async move {
+            let mut ws_index = 0usize;
+            let mut backoff = ReconnectBackoff::new();
+
+            loop {
+                let ws_url = &ws_urls[ws_index % ws_urls.len()];
+                info!("Opening accountSubscribe WebSocket to {} for {}", ws_url, account);
+
+                match PubsubClient::new(ws_url).await {
+                    Ok(client) => {
+                        let config = solana_client::rpc_config::RpcAccountInfoConfig {
+                            encoding: Some(UiAccountEncoding::Base64),
+                            commitment: Some(commitment),
+                            ..Default::default()
+                        };
+
+                        match client.account_subscribe(&account, Some(config)).await {
+                            Ok((mut stream, _unsubscribe)) => {
+                                info!("accountSubscribe active on {} ({})", ws_url, account);
+                                backoff.reset();
+
+                                while let Some(update) = stream.next().await {
+                                    let Some(data) = update.value.data.decode() else {
+                                        continue;
+                                    };
+                                    if tx.send(data).is_err() {
+                                        debug!("accountSubscribe has no receivers left, stopping");
+                                        return;
+                                    }
+                                }
+
+                                warn!("accountSubscribe stream on {} ended, reconnecting", ws_url);
+                            }
+                            Err(e) => {
+                                error!("accountSubscribe failed on {}: {}", ws_url, e);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        error!("Failed to open pubsub WebSocket {}: {}", ws_url, e);
+                    }
+                }
+
+                ws_index = (ws_index + 1) % ws_urls.len();
+                tokio::time::sleep(backoff.next_delay()).await;
+            }
+        });
+
+        Ok(rx)
+    }
+
+    fn parse_logs_response(response: &RpcLogsResponse) -> Option<LogEvent> {
+        let signature = Signature::from_str(&response.signature).ok()?;
+        Some(LogEvent {
+            signature,
+            logs: response.logs.clone(),
+            err: response.err.as_ref().map(|e| e.to_string()),
+        })
+    }
 }
 
 #[cfg(test)]
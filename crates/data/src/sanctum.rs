@@ -0,0 +1,171 @@
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::debug;
+use trading_core::{Result, TradingError};
+
+/// Sanctum API client for swap quotes and routing. Sanctum specializes in LST
+/// (liquid-staked SOL) swaps but exposes the same quote/swap shape as Jupiter, so it's
+/// a second venue `BestRouteProvider` can quote side by side with Jupiter.
+pub struct SanctumClient {
+    client: Client,
+    api_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SanctumQuoteResponse {
+    pub input_mint: String,
+    pub in_amount: String,
+    pub output_mint: String,
+    pub out_amount: String,
+    pub fee_bps: u16,
+    pub price_impact_pct: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SanctumSwapRequest {
+    input_mint: String,
+    output_mint: String,
+    amount: String,
+    user_public_key: String,
+    priority_fee_lamports: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SanctumSwapResponse {
+    pub swap_transaction: String,
+}
+
+impl SanctumClient {
+    pub fn new(api_url: String) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(15))
+                .build()
+                .expect("Failed to create HTTP client"),
+            api_url,
+        }
+    }
+
+    /// Get swap quote from Sanctum
+    pub async fn get_quote(
+        &self,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> Result<SanctumQuoteResponse> {
+        let url = format!("{}/quote", self.api_url);
+
+        debug!(
+            "Getting Sanctum quote: {} {} for {}",
+            amount, input_mint, output_mint
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&[
+                ("inputMint", input_mint.to_string()),
+                ("outputMint", output_mint.to_string()),
+                ("amount", amount.to_string()),
+                ("slippageBps", slippage_bps.to_string()),
+            ])
+            .send()
+            .await
+            .map_err(|e| TradingError::DataFetchError(format!("Sanctum quote request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(TradingError::DataFetchError(format!(
+                "Sanctum quote failed with status {}: {}",
+                status, body
+            )));
+        }
+
+        let quote: SanctumQuoteResponse = response
+            .json()
+            .await
+            .map_err(|e| TradingError::ParseError(format!("Failed to parse Sanctum quote: {}", e)))?;
+
+        debug!(
+            "Sanctum quote received: {} in -> {} out (fee {}bps)",
+            quote.in_amount, quote.out_amount, quote.fee_bps
+        );
+
+        Ok(quote)
+    }
+
+    /// Get swap transaction from Sanctum
+    pub async fn get_swap_transaction(
+        &self,
+        quote: &SanctumQuoteResponse,
+        user_pubkey: &Pubkey,
+        priority_fee_lamports: Option<u64>,
+    ) -> Result<SanctumSwapResponse> {
+        let url = format!("{}/swap", self.api_url);
+
+        let request = SanctumSwapRequest {
+            input_mint: quote.input_mint.clone(),
+            output_mint: quote.output_mint.clone(),
+            amount: quote.in_amount.clone(),
+            user_public_key: user_pubkey.to_string(),
+            priority_fee_lamports,
+        };
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| TradingError::ExecutionError(format!("Sanctum swap request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(TradingError::ExecutionError(format!(
+                "Sanctum swap failed with status {}: {}",
+                status, body
+            )));
+        }
+
+        let swap: SanctumSwapResponse = response
+            .json()
+            .await
+            .map_err(|e| TradingError::ParseError(format!("Failed to parse Sanctum swap: {}", e)))?;
+
+        Ok(swap)
+    }
+
+    /// Get price for a token pair (1 unit of input)
+    pub async fn get_price(
+        &self,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+        decimals: u8,
+    ) -> Result<Decimal> {
+        let amount = 10_u64.pow(decimals as u32);
+
+        let quote = self.get_quote(input_mint, output_mint, amount, 100).await?;
+
+        let in_amount = Decimal::from_str(&quote.in_amount)
+            .map_err(|e| TradingError::ParseError(format!("Invalid in_amount: {}", e)))?;
+
+        let out_amount = Decimal::from_str(&quote.out_amount)
+            .map_err(|e| TradingError::ParseError(format!("Invalid out_amount: {}", e)))?;
+
+        if in_amount == Decimal::ZERO {
+            return Err(TradingError::ParseError("Input amount is zero".to_string()));
+        }
+
+        Ok(out_amount / in_amount)
+    }
+}
@@ -8,6 +8,8 @@ use tracing::{debug, error, warn};
 use trading_core::{Result, RiskLevel, SecurityInfo, TradingError};
 use std::collections::HashMap;
 
+use crate::wallet_discovery::token_account_owner;
+
 /// Scam detection using rugcheck.xyz free API
 pub struct ScamDetector {
     client: Client,
@@ -16,6 +18,50 @@ pub struct ScamDetector {
     cache_ttl_seconds: i64,
 }
 
+/// Why `detect_bundle_heuristic` flagged a token, so callers can surface more than a bare
+/// bool: how many wallets are linked, how much of the top-holder supply they collectively
+/// control, and which slots (if any) saw suspiciously many of them enter at once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundleReport {
+    pub cluster_size: usize,
+    pub combined_pct: f64,
+    pub coordinated_slots: Vec<u64>,
+}
+
+/// A top holder resolved to its owner wallet, plus what we could learn about how it entered.
+struct WalletHolding {
+    wallet: Pubkey,
+    pct: f64,
+    funding_source: Option<Pubkey>,
+    entry_slot: Option<u64>,
+}
+
+/// Minimal union-find over wallet indices, used to cluster linked wallets into connected
+/// components without pulling in a graph crate for one pass.
+struct DisjointSet {
+    parent: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(n: usize) -> Self {
+        Self { parent: (0..n).collect() }
+    }
+
+    fn find(&mut self, i: usize) -> usize {
+        if self.parent[i] != i {
+            self.parent[i] = self.find(self.parent[i]);
+        }
+        self.parent[i]
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra != rb {
+            self.parent[ra] = rb;
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct RugCheckResponse {
     status: String,
@@ -61,6 +107,46 @@ struct LiquidityPool {
     locked: Option<bool>,
 }
 
+/// The two `COption<Pubkey>` authority fields of an SPL mint account that matter for
+/// honeypot detection - `None` means the authority has been revoked.
+struct MintAuthorities {
+    mint_authority: Option<Pubkey>,
+    freeze_authority: Option<Pubkey>,
+}
+
+/// Decode the `mintAuthority`/`freezeAuthority` fields out of a raw SPL token mint account
+/// (`spl_token::state::Mint`'s packed layout), without depending on the `spl_token` crate:
+///
+/// ```text
+/// offset  0..4   mintAuthority COption tag (1 = Some, 0 = None)
+/// offset  4..36  mintAuthority Pubkey (only valid if tag == 1)
+/// offset 36..44  supply
+/// offset 44      decimals
+/// offset 45      isInitialized
+/// offset 46..50  freezeAuthority COption tag
+/// offset 50..82  freezeAuthority Pubkey (only valid if tag == 1)
+/// ```
+fn decode_mint_authorities(data: &[u8]) -> Option<MintAuthorities> {
+    const MINT_LEN: usize = 82;
+    if data.len() < MINT_LEN {
+        return None;
+    }
+
+    let read_coption = |tag_offset: usize, pubkey_offset: usize| -> Option<Pubkey> {
+        let tag = u32::from_le_bytes(data[tag_offset..tag_offset + 4].try_into().ok()?);
+        if tag == 0 {
+            return None;
+        }
+        let bytes: [u8; 32] = data[pubkey_offset..pubkey_offset + 32].try_into().ok()?;
+        Some(Pubkey::new_from_array(bytes))
+    };
+
+    Some(MintAuthorities {
+        mint_authority: read_coption(0, 4),
+        freeze_authority: read_coption(46, 50),
+    })
+}
+
 impl ScamDetector {
     pub fn new(rugcheck_url: String) -> Self {
         Self {
@@ -74,8 +160,15 @@ impl ScamDetector {
         }
     }
 
-    /// Check if a token is a scam using rugcheck.xyz
-    pub async fn check_token_security(&self, mint: &Pubkey) -> Result<SecurityInfo> {
+    /// Check if a token is a scam using rugcheck.xyz, plus on-chain mint/freeze authority
+    /// state fetched directly via `rpc_client` - the two most important honeypot signals,
+    /// which rugcheck can't substitute for. The authority check still applies when rugcheck
+    /// answers with an error status; a rugcheck request that fails outright fails the check.
+    pub async fn check_token_security(
+        &self,
+        rpc_client: &crate::rpc::FallbackRpcClient,
+        mint: &Pubkey,
+    ) -> Result<SecurityInfo> {
         // Check cache first
         {
             let cache = self.cache.read().await;
@@ -88,6 +181,8 @@ impl ScamDetector {
             }
         }
 
+        let authorities = Self::fetch_mint_authorities(rpc_client, mint).await;
+
         // Fetch from rugcheck API
         let url = format!("{}/tokens/{}/report", self.rugcheck_url, mint);
         debug!("Fetching security data from rugcheck: {}", url);
@@ -99,18 +194,35 @@ impl ScamDetector {
             .await
             .map_err(|e| TradingError::DataFetchError(format!("Rugcheck request failed: {}", e)))?;
 
-        if !response.status().is_success() {
+        let mut security_info = if !response.status().is_success() {
             warn!("Rugcheck returned status: {}", response.status());
-            // Return default security info if rugcheck fails
-            return Ok(SecurityInfo::default());
-        }
+            // Rugcheck is down - fall back to a default, but still apply the on-chain
+            // authority check below rather than losing it entirely.
+            SecurityInfo::default()
+        } else {
+            let data: RugCheckResponse = response
+                .json()
+                .await
+                .map_err(|e| TradingError::ParseError(format!("Failed to parse rugcheck response: {}", e)))?;
 
-        let data: RugCheckResponse = response
-            .json()
-            .await
-            .map_err(|e| TradingError::ParseError(format!("Failed to parse rugcheck response: {}", e)))?;
+            self.parse_rugcheck_data(data)?
+        };
 
-        let security_info = self.parse_rugcheck_data(data)?;
+        Self::apply_mint_authorities(&mut security_info, authorities);
+
+        // The wallet-relationship-graph heuristic runs independently of rugcheck's own
+        // bundle signal; either one finding a bundle is enough to flag it.
+        match self.detect_bundle_heuristic(rpc_client, mint).await {
+            Ok(Some(report)) => {
+                debug!(
+                    "Bundle heuristic confirms {} as a bundle: {:?}",
+                    mint, report
+                );
+                security_info.is_bundle = true;
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Bundle heuristic failed for {}: {}", mint, e),
+        }
 
         // Update cache
         {
@@ -121,6 +233,42 @@ impl ScamDetector {
         Ok(security_info)
     }
 
+    /// Fetch `mint`'s account via `getAccountInfo` and decode the SPL mint layout's
+    /// `mintAuthority`/`freezeAuthority` `COption<Pubkey>` fields - `None` means the
+    /// authority has been revoked. Returns `None` (leaving authority flags unset) if the
+    /// account can't be fetched or doesn't look like a mint, rather than failing the whole
+    /// security check over an RPC hiccup.
+    async fn fetch_mint_authorities(
+        rpc_client: &crate::rpc::FallbackRpcClient,
+        mint: &Pubkey,
+    ) -> Option<MintAuthorities> {
+        let data = match rpc_client.get_account_data(mint).await {
+            Ok(data) => data,
+            Err(e) => {
+                warn!("Failed to fetch mint account {}: {}", mint, e);
+                return None;
+            }
+        };
+
+        decode_mint_authorities(&data)
+    }
+
+    /// Set `mint_authority_disabled`/`freeze_authority_disabled` from the on-chain mint
+    /// state and escalate `risk_level` to at least `High` if the freeze authority is still
+    /// live - a live freeze authority means the issuer can freeze holders' tokens at will.
+    fn apply_mint_authorities(security_info: &mut SecurityInfo, authorities: Option<MintAuthorities>) {
+        let Some(authorities) = authorities else {
+            return;
+        };
+
+        security_info.mint_authority_disabled = authorities.mint_authority.is_none();
+        security_info.freeze_authority_disabled = authorities.freeze_authority.is_none();
+
+        if authorities.freeze_authority.is_some() && security_info.risk_level < RiskLevel::High {
+            security_info.risk_level = RiskLevel::High;
+        }
+    }
+
     /// Parse rugcheck data into SecurityInfo
     fn parse_rugcheck_data(&self, data: RugCheckResponse) -> Result<SecurityInfo> {
         let rugcheck_score = data.score.map(|s| s as f64 / 100.0);
@@ -199,19 +347,24 @@ impl ScamDetector {
             rugcheck_score,
             lp_locked,
             lp_lock_duration,
-            mint_authority_disabled: false, // Would need to check on-chain
-            freeze_authority_disabled: false, // Would need to check on-chain
+            // Filled in by `apply_mint_authorities` from on-chain data once fetched.
+            mint_authority_disabled: false,
+            freeze_authority_disabled: false,
             top_holders_percentage,
             risk_level,
         })
     }
 
     /// Batch check multiple tokens
-    pub async fn check_tokens_batch(&self, mints: &[Pubkey]) -> Vec<(Pubkey, Result<SecurityInfo>)> {
+    pub async fn check_tokens_batch(
+        &self,
+        rpc_client: &crate::rpc::FallbackRpcClient,
+        mints: &[Pubkey],
+    ) -> Vec<(Pubkey, Result<SecurityInfo>)> {
         let mut results = Vec::new();
 
         for mint in mints {
-            let result = self.check_token_security(mint).await;
+            let result = self.check_token_security(rpc_client, mint).await;
             results.push((*mint, result));
 
             // Rate limiting - be conservative with free API
@@ -221,24 +374,148 @@ impl ScamDetector {
         results
     }
 
-    /// Simple heuristic-based bundle detection (doesn't require API)
+    /// Wallet-relationship-graph bundle detection: pull the top holders, link wallets that
+    /// share a funding source or that all entered in the same slot, and flag a bundle if a
+    /// connected component of linked wallets controls more than `bundle_supply_threshold_pct`
+    /// of the top-holder supply, or if `coordinated_wallet_threshold`+ wallets share an entry
+    /// slot. Returns `None` when nothing suspicious is found.
     pub async fn detect_bundle_heuristic(
         &self,
         rpc_client: &crate::rpc::FallbackRpcClient,
         token_mint: &Pubkey,
-    ) -> Result<bool> {
-        // Check if token has suspicious characteristics:
-        // 1. Very high concentration in top holders
-        // 2. Multiple related wallets with similar activity patterns
-        // 3. Coordinated buying/selling
-
-        // For now, return false - full implementation would require:
-        // - Token account analysis
-        // - Holder distribution check
-        // - Wallet relationship graph analysis
-
-        debug!("Bundle detection for {} - returning false (not implemented)", token_mint);
-        Ok(false)
+    ) -> Result<Option<BundleReport>> {
+        const TOP_N: usize = 20;
+        const BUNDLE_SUPPLY_THRESHOLD_PCT: f64 = 60.0;
+        const COORDINATED_WALLET_THRESHOLD: usize = 3;
+
+        let holders = rpc_client.get_token_largest_accounts(token_mint).await?;
+        let total_amount: u64 = holders.iter().map(|(_, amount)| *amount).sum();
+        if total_amount == 0 {
+            return Ok(None);
+        }
+
+        // Resolve each top token account back to its owner wallet and note its share of
+        // the top-holder supply.
+        let mut wallets: Vec<WalletHolding> = Vec::new();
+        for (token_account, amount) in holders.into_iter().take(TOP_N) {
+            let Ok(data) = rpc_client.get_account_data(&token_account).await else {
+                continue;
+            };
+            let Some(owner) = token_account_owner(&data) else {
+                continue;
+            };
+            wallets.push(WalletHolding {
+                wallet: owner,
+                pct: amount as f64 / total_amount as f64 * 100.0,
+                funding_source: None,
+                entry_slot: None,
+            });
+        }
+
+        // For each wallet, take the oldest of its five most recent signatures as its entry:
+        // that transaction's slot, and whoever funded it (its fee payer, if not itself).
+        for holding in &mut wallets {
+            let Ok(signatures) = rpc_client
+                .get_signatures_for_address_page(&holding.wallet, None, None, 5)
+                .await
+            else {
+                continue;
+            };
+            let Some(earliest) = signatures.last() else {
+                continue;
+            };
+            holding.entry_slot = Some(earliest.slot);
+
+            let Ok(signature) = earliest.signature.parse() else {
+                continue;
+            };
+            if let Ok(transaction) = rpc_client.get_transaction(&signature).await {
+                if let Some(funder) = crate::transaction::first_signer(&transaction) {
+                    if funder != holding.wallet {
+                        holding.funding_source = Some(funder);
+                    }
+                }
+            }
+        }
+
+        // Union wallets that share a funding source or an entry slot into clusters.
+        let mut clusters = DisjointSet::new(wallets.len());
+        for i in 0..wallets.len() {
+            for j in (i + 1)..wallets.len() {
+                let same_funder = matches!(
+                    (wallets[i].funding_source, wallets[j].funding_source),
+                    (Some(a), Some(b)) if a == b
+                );
+                let same_slot = matches!(
+                    (wallets[i].entry_slot, wallets[j].entry_slot),
+                    (Some(a), Some(b)) if a == b
+                );
+                if same_funder || same_slot {
+                    clusters.union(i, j);
+                }
+            }
+        }
+
+        // A slot shared by enough wallets is coordinated on its own, regardless of cluster size.
+        let mut per_slot: HashMap<u64, usize> = HashMap::new();
+        for holding in &wallets {
+            if let Some(slot) = holding.entry_slot {
+                *per_slot.entry(slot).or_insert(0) += 1;
+            }
+        }
+        let coordinated_slots: Vec<u64> = per_slot
+            .into_iter()
+            .filter(|(_, count)| *count >= COORDINATED_WALLET_THRESHOLD)
+            .map(|(slot, _)| slot)
+            .collect();
+
+        // Largest cluster by combined supply share.
+        let mut cluster_pct: HashMap<usize, f64> = HashMap::new();
+        let mut cluster_size: HashMap<usize, usize> = HashMap::new();
+        for (i, holding) in wallets.iter().enumerate() {
+            let root = clusters.find(i);
+            *cluster_pct.entry(root).or_insert(0.0) += holding.pct;
+            *cluster_size.entry(root).or_insert(0) += 1;
+        }
+        let largest = cluster_pct
+            .iter()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+            .map(|(root, pct)| (*root, *pct));
+
+        let bundle = match largest {
+            Some((root, combined_pct))
+                if combined_pct > BUNDLE_SUPPLY_THRESHOLD_PCT && cluster_size[&root] >= 2 =>
+            {
+                Some(BundleReport {
+                    cluster_size: cluster_size[&root],
+                    combined_pct,
+                    coordinated_slots: coordinated_slots.clone(),
+                })
+            }
+            _ if !coordinated_slots.is_empty() => {
+                // Wallets sharing a slot were unioned above, so report that slot's cluster.
+                let root = wallets
+                    .iter()
+                    .position(|h| h.entry_slot == Some(coordinated_slots[0]))
+                    .map(|i| clusters.find(i))
+                    .expect("a coordinated slot has at least one wallet");
+                Some(BundleReport {
+                    cluster_size: cluster_size[&root],
+                    combined_pct: cluster_pct[&root],
+                    coordinated_slots,
+                })
+            }
+            _ => None,
+        };
+
+        if let Some(report) = &bundle {
+            debug!(
+                "Bundle detected for {}: cluster of {} wallets controlling {:.1}%, {} coordinated slot(s)",
+                token_mint, report.cluster_size, report.combined_pct, report.coordinated_slots.len()
+            );
+        }
+
+        Ok(bundle)
     }
 
     /// Clear cache
@@ -258,15 +535,36 @@ mod tests {
     #[ignore] // Ignore in CI to avoid hitting API rate limits
     async fn test_rugcheck() {
         let detector = ScamDetector::new("https://api.rugcheck.xyz/v1".to_string());
+        let rpc_client = crate::rpc::FallbackRpcClient::new(
+            "https://api.mainnet-beta.solana.com".to_string(),
+            vec![],
+            solana_sdk::commitment_config::CommitmentConfig::confirmed(),
+        );
 
         // Test with a known token
         let bonk_mint = Pubkey::from_str("DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263")
             .unwrap();
 
-        let security_info = detector.check_token_security(&bonk_mint).await;
+        let security_info = detector.check_token_security(&rpc_client, &bonk_mint).await;
         assert!(security_info.is_ok());
 
         let info = security_info.unwrap();
         println!("Security info: {:?}", info);
     }
+
+    #[test]
+    fn test_decode_mint_authorities_revoked() {
+        let mut data = vec![0u8; 82];
+        // Both COption tags left at 0 (None) - a fully-revoked mint.
+        let authorities = decode_mint_authorities(&data).unwrap();
+        assert!(authorities.mint_authority.is_none());
+        assert!(authorities.freeze_authority.is_none());
+
+        // Flip the freeze authority tag on and fill in a pubkey.
+        data[46..50].copy_from_slice(&1u32.to_le_bytes());
+        data[50..82].copy_from_slice(&[7u8; 32]);
+        let authorities = decode_mint_authorities(&data).unwrap();
+        assert!(authorities.mint_authority.is_none());
+        assert_eq!(authorities.freeze_authority, Some(Pubkey::new_from_array([7u8; 32])));
+    }
 }
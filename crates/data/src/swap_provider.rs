@@ -0,0 +1,343 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use solana_sdk::pubkey::Pubkey;
+use tracing::{debug, warn};
+use trading_core::{Result, TradingError};
+
+use crate::jupiter::{JupiterClient, QuoteResponse, SwapResponse};
+use crate::mock_swap::{MockJupiterClient, MockPriceTable, MockQuote, MockSwap};
+use crate::sanctum::{SanctumClient, SanctumQuoteResponse, SanctumSwapResponse};
+use trading_core::DataSourcesConfig;
+
+/// A quote normalized enough for `BestRouteProvider` to compare across venues, carrying
+/// the provider-native payload so that same provider's `get_swap_transaction` can build
+/// the real swap request back out of it.
+#[derive(Debug, Clone)]
+pub struct SwapQuote {
+    pub provider: &'static str,
+    pub in_amount: u64,
+    pub out_amount: u64,
+    pub fee_bps: u16,
+    pub price_impact_pct: f64,
+    pub raw: ProviderQuote,
+}
+
+impl SwapQuote {
+    /// Output net of the venue's own fee, used to rank quotes from different providers
+    /// against each other rather than comparing raw `out_amount`.
+    pub fn net_out_amount(&self) -> u64 {
+        let fee = self.out_amount as u128 * self.fee_bps as u128 / 10_000;
+        self.out_amount.saturating_sub(fee as u64)
+    }
+}
+
+/// The provider-native quote payload, kept typed (rather than `serde_json::Value`) so
+/// each `SwapProvider` impl can match its own variant back out in `get_swap_transaction`.
+#[derive(Debug, Clone)]
+pub enum ProviderQuote {
+    Jupiter(QuoteResponse),
+    Sanctum(SanctumQuoteResponse),
+    Mock(MockQuote),
+}
+
+/// The signed-and-ready (base64) transaction a provider returns for a chosen quote.
+#[derive(Debug, Clone)]
+pub struct SwapTransaction {
+    pub provider: &'static str,
+    pub transaction_base64: String,
+}
+
+/// One venue `BestRouteProvider` can quote against - implemented by `JupiterClient` and
+/// `SanctumClient` so a new aggregator can fan a quote request out across all of them.
+#[async_trait]
+pub trait SwapProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn get_quote(
+        &self,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> Result<SwapQuote>;
+
+    async fn get_swap_transaction(
+        &self,
+        quote: &SwapQuote,
+        user_pubkey: &Pubkey,
+        priority_fee_lamports: Option<u64>,
+    ) -> Result<SwapTransaction>;
+
+    async fn get_price(&self, input_mint: &Pubkey, output_mint: &Pubkey, decimals: u8) -> Result<rust_decimal::Decimal>;
+}
+
+#[async_trait]
+impl SwapProvider for JupiterClient {
+    fn name(&self) -> &'static str {
+        "jupiter"
+    }
+
+    async fn get_quote(
+        &self,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> Result<SwapQuote> {
+        let quote = JupiterClient::get_quote(self, input_mint, output_mint, amount, slippage_bps).await?;
+
+        let in_amount = quote.in_amount.parse::<u64>().unwrap_or(0);
+        let out_amount = quote.out_amount.parse::<u64>().unwrap_or(0);
+        let fee_bps = quote
+            .route_plan
+            .first()
+            .and_then(|leg| leg.swap_info.fee_amount.parse::<u64>().ok())
+            .map(|fee_amount| {
+                if in_amount == 0 {
+                    0
+                } else {
+                    ((fee_amount as u128 * 10_000) / in_amount as u128) as u16
+                }
+            })
+            .unwrap_or(0);
+
+        Ok(SwapQuote {
+            provider: self.name(),
+            in_amount,
+            out_amount,
+            fee_bps,
+            price_impact_pct: quote.price_impact_pct,
+            raw: ProviderQuote::Jupiter(quote),
+        })
+    }
+
+    async fn get_swap_transaction(
+        &self,
+        quote: &SwapQuote,
+        user_pubkey: &Pubkey,
+        priority_fee_lamports: Option<u64>,
+    ) -> Result<SwapTransaction> {
+        let ProviderQuote::Jupiter(raw_quote) = &quote.raw else {
+            return Err(TradingError::ExecutionError(
+                "Jupiter provider received a non-Jupiter quote".to_string(),
+            ));
+        };
+
+        let swap: SwapResponse =
+            JupiterClient::get_swap_transaction(self, raw_quote.clone(), user_pubkey, priority_fee_lamports).await?;
+
+        Ok(SwapTransaction {
+            provider: self.name(),
+            transaction_base64: swap.swap_transaction,
+        })
+    }
+
+    async fn get_price(&self, input_mint: &Pubkey, output_mint: &Pubkey, decimals: u8) -> Result<rust_decimal::Decimal> {
+        JupiterClient::get_price(self, input_mint, output_mint, decimals).await
+    }
+}
+
+#[async_trait]
+impl SwapProvider for SanctumClient {
+    fn name(&self) -> &'static str {
+        "sanctum"
+    }
+
+    async fn get_quote(
+        &self,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> Result<SwapQuote> {
+        let quote = SanctumClient::get_quote(self, input_mint, output_mint, amount, slippage_bps).await?;
+
+        let in_amount = quote.in_amount.parse::<u64>().unwrap_or(0);
+        let out_amount = quote.out_amount.parse::<u64>().unwrap_or(0);
+
+        Ok(SwapQuote {
+            provider: self.name(),
+            in_amount,
+            out_amount,
+            fee_bps: quote.fee_bps,
+            price_impact_pct: quote.price_impact_pct,
+            raw: ProviderQuote::Sanctum(quote),
+        })
+    }
+
+    async fn get_swap_transaction(
+        &self,
+        quote: &SwapQuote,
+        user_pubkey: &Pubkey,
+        priority_fee_lamports: Option<u64>,
+    ) -> Result<SwapTransaction> {
+        let ProviderQuote::Sanctum(raw_quote) = &quote.raw else {
+            return Err(TradingError::ExecutionError(
+                "Sanctum provider received a non-Sanctum quote".to_string(),
+            ));
+        };
+
+        let swap: SanctumSwapResponse =
+            SanctumClient::get_swap_transaction(self, raw_quote, user_pubkey, priority_fee_lamports).await?;
+
+        Ok(SwapTransaction {
+            provider: self.name(),
+            transaction_base64: swap.swap_transaction,
+        })
+    }
+
+    async fn get_price(&self, input_mint: &Pubkey, output_mint: &Pubkey, decimals: u8) -> Result<rust_decimal::Decimal> {
+        SanctumClient::get_price(self, input_mint, output_mint, decimals).await
+    }
+}
+
+#[async_trait]
+impl SwapProvider for MockJupiterClient {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    async fn get_quote(
+        &self,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> Result<SwapQuote> {
+        let quote = MockJupiterClient::get_quote(self, input_mint, output_mint, amount, slippage_bps);
+
+        Ok(SwapQuote {
+            provider: self.name(),
+            in_amount: quote.in_amount,
+            out_amount: quote.out_amount,
+            fee_bps: quote.fee_bps,
+            price_impact_pct: quote.price_impact_pct,
+            raw: ProviderQuote::Mock(quote),
+        })
+    }
+
+    async fn get_swap_transaction(
+        &self,
+        quote: &SwapQuote,
+        _user_pubkey: &Pubkey,
+        _priority_fee_lamports: Option<u64>,
+    ) -> Result<SwapTransaction> {
+        let ProviderQuote::Mock(raw_quote) = &quote.raw else {
+            return Err(TradingError::ExecutionError(
+                "Mock provider received a non-mock quote".to_string(),
+            ));
+        };
+
+        let swap: MockSwap = MockJupiterClient::get_swap_transaction(self, raw_quote);
+
+        Ok(SwapTransaction {
+            provider: self.name(),
+            transaction_base64: swap.swap_transaction,
+        })
+    }
+
+    async fn get_price(&self, input_mint: &Pubkey, output_mint: &Pubkey, _decimals: u8) -> Result<rust_decimal::Decimal> {
+        MockJupiterClient::get_price(self, input_mint, output_mint)
+    }
+}
+
+/// Builds the swap provider(s) `BestRouteProvider` should quote against: the venues
+/// listed in `data_sources.swap_providers` when trading is enabled, or a single
+/// `MockJupiterClient` reading `mock_price_table_path` when it's not - so strategy and
+/// risk-limit code exercises the exact same quoting path in dry-run as it would live,
+/// just against synthesized quotes, and never reaches the network to submit a swap.
+pub fn build_swap_providers(
+    data_sources: &DataSourcesConfig,
+    trading_enabled: bool,
+) -> Vec<Arc<dyn SwapProvider>> {
+    if !trading_enabled {
+        let table = data_sources
+            .mock_price_table_path
+            .as_deref()
+            .and_then(|path| match MockPriceTable::from_file(path) {
+                Ok(table) => Some(table),
+                Err(e) => {
+                    warn!("Failed to load mock price table from {}: {}, using flat fallback prices", path, e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        debug!("Trading disabled, quoting exclusively against MockJupiterClient");
+        return vec![Arc::new(MockJupiterClient::new(table))];
+    }
+
+    let mut providers: Vec<Arc<dyn SwapProvider>> = Vec::new();
+    for name in &data_sources.swap_providers {
+        match name.as_str() {
+            "jupiter" => providers.push(Arc::new(JupiterClient::new(data_sources.jupiter_api_url.clone()))),
+            "sanctum" => providers.push(Arc::new(SanctumClient::new(data_sources.sanctum_api_url.clone()))),
+            other => warn!("Unknown swap provider '{}' in config, skipping", other),
+        }
+    }
+    providers
+}
+
+/// Fans a quote request out to every configured `SwapProvider` concurrently and picks
+/// the one with the best fee-adjusted `out_amount`, mirroring how liquidators run
+/// Jupiter and Sanctum side by side and route each trade to the better venue.
+pub struct BestRouteProvider {
+    providers: Vec<Arc<dyn SwapProvider>>,
+}
+
+impl BestRouteProvider {
+    pub fn new(providers: Vec<Arc<dyn SwapProvider>>) -> Self {
+        Self { providers }
+    }
+
+    pub async fn get_best_quote(
+        &self,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> Result<SwapQuote> {
+        let mut handles = Vec::with_capacity(self.providers.len());
+
+        for provider in self.providers.clone() {
+            let input_mint = *input_mint;
+            let output_mint = *output_mint;
+            handles.push(tokio::spawn(async move {
+                let name = provider.name();
+                let result = provider.get_quote(&input_mint, &output_mint, amount, slippage_bps).await;
+                (name, result)
+            }));
+        }
+
+        let mut best: Option<SwapQuote> = None;
+
+        for handle in handles {
+            let (name, result) = match handle.await {
+                Ok(outcome) => outcome,
+                Err(e) => {
+                    warn!("Swap provider quote task panicked: {}", e);
+                    continue;
+                }
+            };
+
+            match result {
+                Ok(quote) => {
+                    debug!(
+                        "{} quote: {} out ({} net of {}bps fee)",
+                        name,
+                        quote.out_amount,
+                        quote.net_out_amount(),
+                        quote.fee_bps
+                    );
+                    if best.as_ref().map_or(true, |b| quote.net_out_amount() > b.net_out_amount()) {
+                        best = Some(quote);
+                    }
+                }
+                Err(e) => warn!("{} quote failed: {}", name, e),
+            }
+        }
+
+        best.ok_or_else(|| TradingError::DataFetchError("No swap provider returned a quote".to_string()))
+    }
+}
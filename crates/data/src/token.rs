@@ -1,3 +1,4 @@
+use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
@@ -11,12 +12,210 @@ use trading_core::{MarketData, Result, Token, TokenMetadata, TradingError};
 use rust_decimal::Decimal;
 use chrono::Utc;
 
+use crate::jupiter::JupiterClient;
+
+/// One venue `TokenDataFetcher` can pull market data from. Named distinctly from
+/// `price_oracle::PriceSource` (ranked first-fresh-wins) - this trait backs
+/// `TokenDataFetcher`'s own multi-source aggregation instead.
+#[async_trait]
+pub trait MarketDataSource: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn fetch(&self, mint: &Pubkey) -> Result<MarketData>;
+}
+
+/// DexScreener, queried directly (not through `TokenDataFetcher`'s own client) so it can
+/// be used standalone as one of several prioritized sources.
+pub struct DexScreenerMarketSource {
+    client: Client,
+    dexscreener_url: String,
+}
+
+impl DexScreenerMarketSource {
+    pub fn new(dexscreener_url: String) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .expect("Failed to create HTTP client"),
+            dexscreener_url,
+        }
+    }
+}
+
+#[async_trait]
+impl MarketDataSource for DexScreenerMarketSource {
+    fn name(&self) -> &'static str {
+        "DexScreener"
+    }
+
+    async fn fetch(&self, mint: &Pubkey) -> Result<MarketData> {
+        let url = format!("{}/dex/tokens/{}", self.dexscreener_url, mint);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| TradingError::DataFetchError(format!("DexScreener request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(TradingError::DataFetchError(format!(
+                "DexScreener returned status: {}",
+                response.status()
+            )));
+        }
+
+        let data: DexScreenerResponse = response
+            .json()
+            .await
+            .map_err(|e| TradingError::ParseError(format!("Failed to parse DexScreener response: {}", e)))?;
+
+        let pair = data
+            .pairs
+            .and_then(|pairs| pairs.into_iter().next())
+            .ok_or_else(|| TradingError::DataFetchError("No pairs found for token".to_string()))?;
+
+        Ok(market_data_from_dexscreener_pair(&pair))
+    }
+}
+
+/// CoinGecko's `/simple/token_price` style endpoint, keyed by contract address on the
+/// Solana platform. A thinner read than DexScreener's: price and 24h volume/change only,
+/// no liquidity or dex breakdown.
+pub struct CoinGeckoMarketSource {
+    client: Client,
+    api_url: String,
+}
+
+impl CoinGeckoMarketSource {
+    pub fn new(api_url: String) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .expect("Failed to create HTTP client"),
+            api_url,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinGeckoTicker {
+    usd: Option<f64>,
+    usd_24h_vol: Option<f64>,
+    usd_24h_change: Option<f64>,
+}
+
+#[async_trait]
+impl MarketDataSource for CoinGeckoMarketSource {
+    fn name(&self) -> &'static str {
+        "CoinGecko"
+    }
+
+    async fn fetch(&self, mint: &Pubkey) -> Result<MarketData> {
+        let url = format!(
+            "{}/simple/token_price/solana?contract_addresses={}&vs_currencies=usd&include_24hr_vol=true&include_24hr_change=true",
+            self.api_url, mint
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| TradingError::DataFetchError(format!("CoinGecko request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(TradingError::DataFetchError(format!(
+                "CoinGecko returned status: {}",
+                response.status()
+            )));
+        }
+
+        let data: HashMap<String, CoinGeckoTicker> = response
+            .json()
+            .await
+            .map_err(|e| TradingError::ParseError(format!("Failed to parse CoinGecko response: {}", e)))?;
+
+        let ticker = data
+            .get(&mint.to_string().to_lowercase())
+            .ok_or_else(|| TradingError::DataFetchError("No CoinGecko ticker found for token".to_string()))?;
+
+        let price_usd = ticker
+            .usd
+            .map(|v| Decimal::from_f64_retain(v).unwrap_or(Decimal::ZERO))
+            .ok_or_else(|| TradingError::DataFetchError("CoinGecko ticker missing usd price".to_string()))?;
+
+        Ok(MarketData {
+            price_usd,
+            price_sol: Decimal::ZERO,
+            market_cap: Decimal::ZERO,
+            liquidity_usd: Decimal::ZERO,
+            volume_24h: ticker
+                .usd_24h_vol
+                .map(|v| Decimal::from_f64_retain(v).unwrap_or(Decimal::ZERO))
+                .unwrap_or(Decimal::ZERO),
+            price_change_24h: ticker.usd_24h_change.unwrap_or(0.0),
+            price_change_1h: 0.0,
+            price_change_5m: 0.0,
+            holders: None,
+            dex: Some("CoinGecko".to_string()),
+        })
+    }
+}
+
+/// Jupiter's aggregated quote against USDC, the last-resort source - it only yields a
+/// price, no liquidity/volume, but it's available for anything routable at all.
+pub struct JupiterMarketSource {
+    client: JupiterClient,
+    quote_mint: Pubkey,
+    quote_decimals: u8,
+}
+
+impl JupiterMarketSource {
+    pub fn new(client: JupiterClient, quote_mint: Pubkey, quote_decimals: u8) -> Self {
+        Self {
+            client,
+            quote_mint,
+            quote_decimals,
+        }
+    }
+}
+
+#[async_trait]
+impl MarketDataSource for JupiterMarketSource {
+    fn name(&self) -> &'static str {
+        "Jupiter"
+    }
+
+    async fn fetch(&self, mint: &Pubkey) -> Result<MarketData> {
+        let price_usd = self
+            .client
+            .get_price(mint, &self.quote_mint, self.quote_decimals)
+            .await?;
+
+        Ok(MarketData {
+            price_usd,
+            price_sol: Decimal::ZERO,
+            market_cap: Decimal::ZERO,
+            liquidity_usd: Decimal::ZERO,
+            volume_24h: Decimal::ZERO,
+            price_change_24h: 0.0,
+            price_change_1h: 0.0,
+            price_change_5m: 0.0,
+            holders: None,
+            dex: Some("Jupiter".to_string()),
+        })
+    }
+}
+
 /// Token data fetcher using free APIs
 pub struct TokenDataFetcher {
     client: Client,
     dexscreener_url: String,
     cache: Arc<RwLock<HashMap<Pubkey, (Token, i64)>>>,
     cache_ttl_seconds: i64,
+    sources: Vec<Box<dyn MarketDataSource>>,
+    price_window: crate::price_window::PriceWindowTracker,
 }
 
 #[derive(Debug, Deserialize)]
@@ -82,8 +281,52 @@ struct Social {
     url: String,
 }
 
+/// Shared DexScreener pair -> `MarketData` conversion, used by both
+/// `DexScreenerMarketSource::fetch` and `TokenDataFetcher::fetch_dexscreener_metadata`.
+fn market_data_from_dexscreener_pair(pair: &DexScreenerPair) -> MarketData {
+    let price_usd = pair
+        .price_usd
+        .as_ref()
+        .and_then(|p| Decimal::from_str(p).ok())
+        .unwrap_or(Decimal::ZERO);
+
+    let liquidity_usd = pair
+        .liquidity
+        .as_ref()
+        .and_then(|l| l.usd)
+        .map(|v| Decimal::from_f64_retain(v).unwrap_or(Decimal::ZERO))
+        .unwrap_or(Decimal::ZERO);
+
+    let volume_24h = pair
+        .volume
+        .as_ref()
+        .and_then(|v| v.h24)
+        .map(|v| Decimal::from_f64_retain(v).unwrap_or(Decimal::ZERO))
+        .unwrap_or(Decimal::ZERO);
+
+    let price_change_24h = pair.price_change.as_ref().and_then(|pc| pc.h24).unwrap_or(0.0);
+    let price_change_1h = pair.price_change.as_ref().and_then(|pc| pc.h1).unwrap_or(0.0);
+    let price_change_5m = pair.price_change.as_ref().and_then(|pc| pc.m5).unwrap_or(0.0);
+
+    MarketData {
+        price_usd,
+        price_sol: Decimal::ZERO,
+        market_cap: liquidity_usd * Decimal::from(2), // Simple approximation
+        liquidity_usd,
+        volume_24h,
+        price_change_24h,
+        price_change_1h,
+        price_change_5m,
+        holders: None,
+        dex: pair.dex_id.clone(),
+    }
+}
+
 impl TokenDataFetcher {
     pub fn new(dexscreener_url: String) -> Self {
+        let sources: Vec<Box<dyn MarketDataSource>> =
+            vec![Box::new(DexScreenerMarketSource::new(dexscreener_url.clone()))];
+
         Self {
             client: Client::builder()
                 .timeout(Duration::from_secs(10))
@@ -92,10 +335,40 @@ impl TokenDataFetcher {
             dexscreener_url,
             cache: Arc::new(RwLock::new(HashMap::new())),
             cache_ttl_seconds: 60, // Cache for 1 minute
+            sources,
+            price_window: crate::price_window::PriceWindowTracker::new(),
         }
     }
 
-    /// Get token data from DexScreener
+    /// Volume-weighted average price for `mint` over the trailing `window`. `None` if
+    /// no ticks have been recorded for it yet.
+    pub async fn vwap(&self, mint: &Pubkey, window: std::time::Duration) -> Option<Decimal> {
+        self.price_window.vwap(mint, window).await
+    }
+
+    /// Exponentially time-weighted mean price for `mint` with decay constant `tau`.
+    pub async fn twap(&self, mint: &Pubkey, tau: std::time::Duration) -> Option<Decimal> {
+        self.price_window.twap(mint, tau).await
+    }
+
+    /// Replace the prioritized source chain (DexScreener first by default). Sources are
+    /// all queried on every lookup so their successes can be aggregated, rather than
+    /// stopping at the first one that answers.
+    pub fn with_sources(mut self, sources: Vec<Box<dyn MarketDataSource>>) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    /// Shared handle to the token cache, so a `PriceStream` can push live ticks into
+    /// the same cache `get_token_data` reads from.
+    pub fn cache_handle(&self) -> Arc<RwLock<HashMap<Pubkey, (Token, i64)>>> {
+        self.cache.clone()
+    }
+
+    /// Get token data, querying every configured source and aggregating the results:
+    /// the median `price_usd` across venues that answered, summed `liquidity_usd`, and
+    /// the highest-liquidity venue's `dex` kept as canonical. A single source failing
+    /// (timeout, no pairs, bad JSON) doesn't block the others from contributing.
     pub async fn get_token_data(&self, mint: &Pubkey) -> Result<Token> {
         // Check cache first
         {
@@ -109,9 +382,94 @@ impl TokenDataFetcher {
             }
         }
 
-        // Fetch from API
+        let mut quotes = Vec::with_capacity(self.sources.len());
+        for source in &self.sources {
+            match source.fetch(mint).await {
+                Ok(market_data) => quotes.push(market_data),
+                Err(e) => warn!("{} market data lookup failed for {}: {}", source.name(), mint, e),
+            }
+        }
+
+        if quotes.is_empty() {
+            return Err(TradingError::DataFetchError(format!(
+                "No configured source returned market data for {}",
+                mint
+            )));
+        }
+
+        let market_data = Self::aggregate_market_data(quotes);
+
+        // DexScreener is the only source that also carries symbol/name/metadata, so
+        // fetch its pair info for that whether or not it's in the chain; fall back to a
+        // bare token identified only by its mint if that fetch fails.
+        let (symbol, name, metadata) = match self.fetch_dexscreener_metadata(mint).await {
+            Ok(meta) => meta,
+            Err(_) => (mint.to_string(), mint.to_string(), TokenMetadata::default()),
+        };
+
+        let token = Token {
+            mint: *mint,
+            symbol,
+            name,
+            decimals: 9, // Most Solana tokens use 9 decimals
+            metadata,
+            security: Default::default(), // Will be filled by scam checker
+            market_data,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        // Update cache
+        {
+            let mut cache = self.cache.write().await;
+            cache.insert(*mint, (token.clone(), Utc::now().timestamp()));
+        }
+
+        self.price_window
+            .record(*mint, token.market_data.price_usd, token.market_data.volume_24h, token.updated_at)
+            .await;
+
+        Ok(token)
+    }
+
+    /// Median `price_usd`, summed `liquidity_usd`, and the `dex` of the most liquid
+    /// venue among the successful quotes.
+    fn aggregate_market_data(mut quotes: Vec<MarketData>) -> MarketData {
+        if quotes.len() == 1 {
+            return quotes.remove(0);
+        }
+
+        let mut prices: Vec<Decimal> = quotes.iter().map(|q| q.price_usd).collect();
+        prices.sort();
+        let median_price = prices[prices.len() / 2];
+
+        let liquidity_usd: Decimal = quotes.iter().map(|q| q.liquidity_usd).sum();
+        let volume_24h: Decimal = quotes.iter().map(|q| q.volume_24h).sum();
+
+        let canonical = quotes
+            .iter()
+            .max_by_key(|q| q.liquidity_usd)
+            .expect("quotes is non-empty");
+
+        MarketData {
+            price_usd: median_price,
+            price_sol: Decimal::ZERO,
+            market_cap: liquidity_usd * Decimal::from(2),
+            liquidity_usd,
+            volume_24h,
+            price_change_24h: canonical.price_change_24h,
+            price_change_1h: canonical.price_change_1h,
+            price_change_5m: canonical.price_change_5m,
+            holders: canonical.holders,
+            dex: canonical.dex.clone(),
+        }
+    }
+
+    /// Fetch just the DexScreener pair's symbol/name/metadata, independent of the
+    /// pluggable source chain (that chain only deals in `MarketData`).
+    async fn fetch_dexscreener_metadata(&self, mint: &Pubkey) -> Result<(String, String, TokenMetadata)> {
         let url = format!("{}/dex/tokens/{}", self.dexscreener_url, mint);
-        debug!("Fetching token data from DexScreener: {}", url);
+        debug!("Fetching token metadata from DexScreener: {}", url);
 
         let response = self
             .client
@@ -137,95 +495,26 @@ impl TokenDataFetcher {
             .and_then(|pairs| pairs.into_iter().next())
             .ok_or_else(|| TradingError::DataFetchError("No pairs found for token".to_string()))?;
 
-        let token = self.parse_dexscreener_data(mint, pair)?;
-
-        // Update cache
-        {
-            let mut cache = self.cache.write().await;
-            cache.insert(*mint, (token.clone(), Utc::now().timestamp()));
-        }
-
-        Ok(token)
-    }
-
-    /// Parse DexScreener data into Token struct
-    fn parse_dexscreener_data(&self, mint: &Pubkey, pair: DexScreenerPair) -> Result<Token> {
-        let price_usd = pair
-            .price_usd
-            .and_then(|p| Decimal::from_str(&p).ok())
-            .unwrap_or(Decimal::ZERO);
-
-        let liquidity_usd = pair
-            .liquidity
-            .and_then(|l| l.usd)
-            .map(|v| Decimal::from_f64_retain(v).unwrap_or(Decimal::ZERO))
-            .unwrap_or(Decimal::ZERO);
-
-        let volume_24h = pair
-            .volume
-            .and_then(|v| v.h24)
-            .map(|v| Decimal::from_f64_retain(v).unwrap_or(Decimal::ZERO))
-            .unwrap_or(Decimal::ZERO);
-
-        let price_change_24h = pair
-            .price_change
-            .as_ref()
-            .and_then(|pc| pc.h24)
-            .unwrap_or(0.0);
-
-        let price_change_1h = pair
-            .price_change
-            .as_ref()
-            .and_then(|pc| pc.h1)
-            .unwrap_or(0.0);
-
-        let price_change_5m = pair
-            .price_change
-            .as_ref()
-            .and_then(|pc| pc.m5)
-            .unwrap_or(0.0);
-
-        // Calculate market cap (approximate)
-        let market_cap = liquidity_usd * Decimal::from(2); // Simple approximation
-
         let mut metadata = TokenMetadata::default();
-        if let Some(info) = pair.info {
-            metadata.logo_url = info.image_url;
-            metadata.website = info.websites.and_then(|w| w.first().map(|w| w.url.clone()));
-
-            if let Some(socials) = info.socials {
+        if let Some(info) = &pair.info {
+            metadata.logo_url = info.image_url.clone();
+            metadata.website = info
+                .websites
+                .as_ref()
+                .and_then(|w| w.first().map(|w| w.url.clone()));
+
+            if let Some(socials) = &info.socials {
                 for social in socials {
                     match social.r#type.as_str() {
-                        "twitter" => metadata.twitter = Some(social.url),
-                        "telegram" => metadata.telegram = Some(social.url),
+                        "twitter" => metadata.twitter = Some(social.url.clone()),
+                        "telegram" => metadata.telegram = Some(social.url.clone()),
                         _ => {}
                     }
                 }
             }
         }
 
-        Ok(Token {
-            mint: *mint,
-            symbol: pair.base_token.symbol,
-            name: pair.base_token.name,
-            decimals: 9, // Most Solana tokens use 9 decimals
-            metadata,
-            security: Default::default(), // Will be filled by scam checker
-            market_data: MarketData {
-                price_usd,
-                price_sol: Decimal::ZERO, // Will be calculated separately
-                market_cap,
-                liquidity_usd,
-                volume_24h,
-                price_change_24h,
-                price_change_1h,
-                price_change_5m,
-                holders: None,
-                dex: pair.dex_id,
-            },
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        })
+        Ok((pair.base_token.symbol.clone(), pair.base_token.name.clone(), metadata))
     }
 
     /// Get multiple tokens in batch (with rate limiting)
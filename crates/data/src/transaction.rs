@@ -1,23 +1,70 @@
 use chrono::{DateTime, Utc};
+use dashmap::DashMap;
 use solana_sdk::pubkey::Pubkey;
 use solana_transaction_status::{
     EncodedConfirmedTransactionWithStatusMeta, UiInstruction, UiMessage, UiParsedInstruction,
     UiTransaction, UiTransactionTokenBalance,
 };
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
 use tracing::{debug, warn};
 use trading_core::{Result, Trade, TradeSide, TradingError};
 use rust_decimal::Decimal;
 use uuid::Uuid;
 
+use crate::rpc::FallbackRpcClient;
+
+/// Mints treated as the "quote" side of a trade: wrapped SOL and the two major USD
+/// stablecoins. A Jupiter route that hops through one or more intermediate mints still
+/// nets down to a single quote-mint delta once every leg lands, so the route's length
+/// never needs to be known up front - see `detect_swap`.
+fn is_quote_mint(mint: &Pubkey) -> bool {
+    static QUOTE_MINTS: OnceLock<[Pubkey; 3]> = OnceLock::new();
+    let quote_mints = QUOTE_MINTS.get_or_init(|| {
+        [
+            Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap(),
+            Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap(),
+            Pubkey::from_str("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB").unwrap(),
+        ]
+    });
+    quote_mints.contains(mint)
+}
+
+/// Process-wide mint decimals cache shared by every `TransactionParser` call site, so a
+/// mint's decimals are only ever queried via RPC once (`UiTokenAmount::decimals` already
+/// covers the common case without an RPC call; its value is stored in the cache).
+fn decimals_cache() -> &'static Arc<DashMap<Pubkey, u8>> {
+    static CACHE: OnceLock<Arc<DashMap<Pubkey, u8>>> = OnceLock::new();
+    CACHE.get_or_init(|| Arc::new(DashMap::new()))
+}
+
+/// Resolve a mint's decimals: trust `hint` (from `UiTokenAmount::decimals`) when present,
+/// otherwise fall back to the cache and finally a `getTokenSupply` RPC call.
+async fn resolve_decimals(rpc: &FallbackRpcClient, mint: &Pubkey, hint: Option<u8>) -> Result<u8> {
+    if let Some(decimals) = hint {
+        decimals_cache().insert(*mint, decimals);
+        return Ok(decimals);
+    }
+
+    if let Some(decimals) = decimals_cache().get(mint) {
+        return Ok(*decimals);
+    }
+
+    let decimals = rpc.get_mint_decimals(mint).await?;
+    decimals_cache().insert(*mint, decimals);
+    Ok(decimals)
+}
+
 /// Transaction parser for extracting trade data
 pub struct TransactionParser;
 
 impl TransactionParser {
     /// Parse a transaction and extract trade information
-    pub fn parse_trade(
+    pub async fn parse_trade(
         transaction: &EncodedConfirmedTransactionWithStatusMeta,
         wallet: &Pubkey,
+        rpc_client: &FallbackRpcClient,
     ) -> Result<Option<Trade>> {
         // EncodedConfirmedTransactionWithStatusMeta contains transaction and meta fields
         let encoded_tx_with_meta = &transaction.transaction;
@@ -53,7 +100,17 @@ impl TransactionParser {
             .unwrap_or_else(|| "unknown".to_string());
 
         // Detect token swap by analyzing balance changes
-        if let Some(trade) = Self::detect_swap(wallet, pre_balances, post_balances, &tx.message, transaction.block_time, signature)? {
+        if let Some(trade) = Self::detect_swap(
+            wallet,
+            pre_balances,
+            post_balances,
+            &tx.message,
+            transaction.block_time,
+            signature,
+            rpc_client,
+        )
+        .await?
+        {
             return Ok(Some(trade));
         }
 
@@ -61,16 +118,21 @@ impl TransactionParser {
     }
 
     /// Detect swap transaction
-    fn detect_swap(
+    async fn detect_swap(
         wallet: &Pubkey,
         pre_balances: &[UiTransactionTokenBalance],
         post_balances: &[UiTransactionTokenBalance],
         message: &UiMessage,
         block_time: Option<i64>,
         signature: String,
+        rpc_client: &FallbackRpcClient,
     ) -> Result<Option<Trade>> {
-        // Find balance changes for this wallet
-        let mut balance_changes: Vec<(String, i128)> = Vec::new();
+        // Net balance change per mint, already scaled to `Decimal` by that mint's own
+        // decimals. A multi-hop route can touch the same mint across several of the
+        // wallet's token accounts (or leave an intermediate mint at a nonzero residual
+        // after fees), so changes are summed per mint rather than collected as one
+        // entry per balance record.
+        let mut net_by_mint: HashMap<Pubkey, Decimal> = HashMap::new();
 
         for post in post_balances {
             let owner_str = match &post.owner {
@@ -100,41 +162,59 @@ impl TransactionParser {
 
             let change = post_amount - pre_amount;
             if change != 0 {
-                balance_changes.push((mint, change));
+                let mint_pubkey = Pubkey::from_str(&mint)
+                    .map_err(|e| TradingError::ParseError(format!("Invalid mint: {}", e)))?;
+                let decimals = resolve_decimals(rpc_client, &mint_pubkey, Some(post.ui_token_amount.decimals)).await?;
+                let scaled_change = Decimal::from_i128_with_scale(change, decimals as u32);
+                *net_by_mint.entry(mint_pubkey).or_insert(Decimal::ZERO) += scaled_change;
             }
         }
 
-        // Swap should have exactly 2 balance changes (one positive, one negative)
-        if balance_changes.len() != 2 {
-            return Ok(None);
-        }
-
-        // Determine which is input and which is output
-        let (token_in, amount_in_raw) = balance_changes
+        // Exactly one non-quote mint should have moved - that's the token being
+        // bought or sold. Zero means nothing net happened (e.g. a failed/no-op route);
+        // more than one means this isn't a simple swap against SOL/USDC/USDT and we
+        // don't have enough context to classify it.
+        let mut base_changes = net_by_mint
             .iter()
-            .find(|(_, change)| *change < 0)
-            .ok_or_else(|| TradingError::ParseError("No negative balance change found".to_string()))?;
-        let (token_out, amount_out_raw) = balance_changes
+            .filter(|(mint, change)| !is_quote_mint(mint) && !change.is_zero())
+            .map(|(mint, change)| (*mint, *change));
+        let (token_mint, base_change) = match (base_changes.next(), base_changes.next()) {
+            (Some(only), None) => only,
+            _ => return Ok(None),
+        };
+        drop(base_changes);
+
+        // The counter-leg is the aggregate of every quote-mint delta, not a single
+        // balance entry - this is what lets sandwiched or fee-split routes (where the
+        // quote amount lands across more than one instruction) still net to one clean
+        // trade.
+        let quote_delta: Decimal = net_by_mint
             .iter()
-            .find(|(_, change)| *change > 0)
-            .ok_or_else(|| TradingError::ParseError("No positive balance change found".to_string()))?;
+            .filter(|(mint, _)| is_quote_mint(mint))
+            .map(|(_, change)| *change)
+            .sum();
 
-        let token_mint_in = Pubkey::from_str(token_in)
-            .map_err(|e| TradingError::ParseError(format!("Invalid mint: {}", e)))?;
-        let token_mint_out = Pubkey::from_str(token_out)
-            .map_err(|e| TradingError::ParseError(format!("Invalid mint: {}", e)))?;
+        if quote_delta.is_zero() {
+            return Ok(None);
+        }
 
-        let amount_in = Decimal::from_i128_with_scale(amount_in_raw.abs(), 9);
-        let amount_out = Decimal::from_i128_with_scale(*amount_out_raw, 9);
+        let (side, amount_in, amount_out) = if base_change.is_sign_positive() {
+            (TradeSide::Buy, quote_delta.abs(), base_change)
+        } else {
+            (TradeSide::Sell, base_change.abs(), quote_delta)
+        };
 
-        // Determine if this is a buy or sell (relative to SOL or USDC)
+        // USDC/USDT are dollar-denominated, so when the quote leg is pure stablecoin the
+        // on-chain execution price is directly computable - no external price oracle
+        // needed. A route that touches SOL still needs enrichment against a SOL/USD
+        // price elsewhere, since the aggregate delta here mixes units.
         let sol_mint = Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap();
-        let usdc_mint = Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap();
-
-        let (side, token_mint) = if token_mint_in == sol_mint || token_mint_in == usdc_mint {
-            (TradeSide::Buy, token_mint_out)
+        let sol_moved = net_by_mint.get(&sol_mint).is_some_and(|change| !change.is_zero());
+        let price_usd = if !sol_moved && !amount_in.is_zero() && !amount_out.is_zero() {
+            // Dollars per token on both sides: the quote leg over the base leg.
+            quote_delta.abs() / base_change.abs()
         } else {
-            (TradeSide::Sell, token_mint_in)
+            Decimal::ZERO // Enriched later against a SOL/USD price
         };
 
         // Extract DEX name from instructions
@@ -147,7 +227,7 @@ impl TransactionParser {
             side,
             amount_in,
             amount_out,
-            price_usd: Decimal::ZERO, // Will be enriched later
+            price_usd,
             market_cap_at_trade: Decimal::ZERO, // Will be enriched later
             signature,
             timestamp: block_time
@@ -202,6 +282,92 @@ impl TransactionParser {
         None
     }
 
+    /// Walk a wallet's full transaction history in 1000-signature pages, using the
+    /// oldest signature of each page as the next `before` cursor. Stops once
+    /// `until_signature` is reached (incremental backfill) or `max_pages` is exhausted.
+    ///
+    /// Returns the extracted trades along with the newest signature observed, so the
+    /// caller can persist it as the cursor for the next incremental pass.
+    pub async fn get_wallet_trades_paginated(
+        rpc_client: &crate::rpc::FallbackRpcClient,
+        wallet: &Pubkey,
+        until_signature: Option<solana_sdk::signature::Signature>,
+        max_pages: usize,
+    ) -> Result<(Vec<Trade>, Option<solana_sdk::signature::Signature>)> {
+        const PAGE_SIZE: usize = 1000;
+
+        let mut trades = Vec::new();
+        let mut newest_signature = None;
+        let mut before = None;
+        let mut pages_fetched = 0;
+
+        for page in 0..max_pages {
+            let signatures = rpc_client
+                .get_signatures_for_address_page(wallet, before, until_signature, PAGE_SIZE)
+                .await?;
+
+            if signatures.is_empty() {
+                break;
+            }
+            pages_fetched = page + 1;
+
+            debug!(
+                "Backfill page {} for {}: {} signatures",
+                page + 1,
+                wallet,
+                signatures.len()
+            );
+
+            if newest_signature.is_none() {
+                newest_signature = solana_sdk::signature::Signature::from_str(&signatures[0].signature).ok();
+            }
+
+            let oldest_in_page = signatures
+                .last()
+                .and_then(|s| solana_sdk::signature::Signature::from_str(&s.signature).ok());
+
+            for sig_info in &signatures {
+                let signature = match solana_sdk::signature::Signature::from_str(&sig_info.signature) {
+                    Ok(sig) => sig,
+                    Err(e) => {
+                        warn!("Skipping invalid signature {}: {}", sig_info.signature, e);
+                        continue;
+                    }
+                };
+
+                match rpc_client.get_transaction(&signature).await {
+                    Ok(transaction) => {
+                        if let Some(trade) = Self::parse_trade(&transaction, wallet, rpc_client).await? {
+                            trades.push(trade);
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Failed to fetch transaction {}: {}", sig_info.signature, e);
+                        continue;
+                    }
+                }
+
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            }
+
+            if signatures.len() < PAGE_SIZE {
+                // Reached the start of the wallet's history
+                break;
+            }
+
+            before = oldest_in_page;
+        }
+
+        debug!(
+            "Backfilled {} trades for {} ({} page(s))",
+            trades.len(),
+            wallet,
+            pages_fetched
+        );
+
+        Ok((trades, newest_signature))
+    }
+
     /// Get all trades for a wallet from transaction history
     pub async fn get_wallet_trades(
         rpc_client: &crate::rpc::FallbackRpcClient,
@@ -219,7 +385,7 @@ impl TransactionParser {
 
             match rpc_client.get_transaction(&signature).await {
                 Ok(transaction) => {
-                    if let Some(trade) = Self::parse_trade(&transaction, wallet)? {
+                    if let Some(trade) = Self::parse_trade(&transaction, wallet, rpc_client).await? {
                         trades.push(trade);
                     }
                 }
@@ -237,6 +403,34 @@ impl TransactionParser {
 
         Ok(trades)
     }
+
+    /// Incremental variant of `get_wallet_trades` for a single already-known signature, e.g.
+    /// one surfaced by a `logsSubscribe` notification, so a streaming consumer doesn't have
+    /// to re-walk `getSignaturesForAddress` just to parse one new transaction.
+    pub async fn get_wallet_trade_for_signature(
+        rpc_client: &crate::rpc::FallbackRpcClient,
+        wallet: &Pubkey,
+        signature: &solana_sdk::signature::Signature,
+    ) -> Result<Option<Trade>> {
+        let transaction = rpc_client.get_transaction(signature).await?;
+        Self::parse_trade(&transaction, wallet, rpc_client).await
+    }
+}
+
+/// The fee payer (first signing account) of a confirmed transaction - the closest thing to
+/// "the wallet that sent this" without decoding the instruction list. Shared by
+/// `wallet_discovery` (funding-source checks) and `scam_check` (bundle detection).
+pub(crate) fn first_signer(
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Option<Pubkey> {
+    let solana_transaction_status::EncodedTransaction::Json(ui_tx) = &tx.transaction.transaction else {
+        return None;
+    };
+    let UiMessage::Parsed(message) = &ui_tx.message else {
+        return None;
+    };
+    let key = message.account_keys.first()?;
+    key.pubkey.parse().ok()
 }
 
 #[cfg(test)]
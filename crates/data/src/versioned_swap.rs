@@ -0,0 +1,58 @@
+use base64::Engine;
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::transaction::VersionedTransaction;
+use tracing::debug;
+use trading_core::{Result, TradingError};
+
+use crate::jupiter::SwapResponse;
+use crate::rpc::FallbackRpcClient;
+
+/// A decoded Jupiter swap transaction plus every Address Lookup Table it references,
+/// resolved and ready to hand to the executor - `VersionedTransaction::message` only
+/// carries each table's pubkey and the indexes it uses, not the table contents, so those
+/// still have to be fetched before the transaction can be simulated or signed against.
+pub struct ResolvedSwapTransaction {
+    pub transaction: VersionedTransaction,
+    pub lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+/// Base64-decode and deserialize Jupiter's `swap_transaction`, then resolve every
+/// Address Lookup Table it references via `rpc`. Legacy (non-versioned) transactions
+/// have no lookup tables, so `lookup_tables` is simply empty for those.
+pub async fn resolve_swap_transaction(
+    rpc: &FallbackRpcClient,
+    swap: &SwapResponse,
+) -> Result<ResolvedSwapTransaction> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(&swap.swap_transaction)
+        .map_err(|e| TradingError::ParseError(format!("Invalid base64 swap transaction: {}", e)))?;
+
+    let transaction: VersionedTransaction = bincode::deserialize(&bytes)
+        .map_err(|e| TradingError::ParseError(format!("Failed to deserialize swap transaction: {}", e)))?;
+
+    let table_keys = match &transaction.message {
+        VersionedMessage::Legacy(_) => Vec::new(),
+        VersionedMessage::V0(message) => message.address_table_lookups.iter().map(|lookup| lookup.account_key).collect(),
+    };
+
+    debug!("Swap transaction references {} address lookup table(s)", table_keys.len());
+
+    let mut lookup_tables = Vec::with_capacity(table_keys.len());
+    for key in table_keys {
+        let data = rpc.get_account_data(&key).await?;
+        let table = AddressLookupTable::deserialize(&data)
+            .map_err(|e| TradingError::ParseError(format!("Failed to deserialize lookup table {}: {}", key, e)))?;
+
+        lookup_tables.push(AddressLookupTableAccount {
+            key,
+            addresses: table.addresses.to_vec(),
+        });
+    }
+
+    Ok(ResolvedSwapTransaction {
+        transaction,
+        lookup_tables,
+    })
+}
@@ -0,0 +1,133 @@
+// Seeds candidate wallet addresses from chain state so `find-wallets` can run with zero
+// manual input, instead of requiring hand-pasted addresses.
+use std::collections::HashSet;
+
+use solana_client::rpc_filter::RpcFilterType;
+use solana_sdk::pubkey::Pubkey;
+use tracing::{debug, warn};
+use trading_core::Result;
+
+use crate::rpc::FallbackRpcClient;
+
+/// Where to pull candidate wallet addresses from. Each variant produces candidates via a
+/// `FallbackRpcClient` call; `discover_candidates` dedups across all of them and caps the
+/// total returned to bound RPC cost.
+#[derive(Debug, Clone)]
+pub enum CandidateSource {
+    /// Top holders of a trending mint, resolved from their token accounts back to owner wallets.
+    TopHolders { mint: Pubkey, n: usize },
+    /// Signers of the most recent transactions mentioning `program` (a DEX/AMM program),
+    /// over a window of roughly `slots` slots of recent activity.
+    RecentTraders { program: Pubkey, slots: u64 },
+    /// Addresses of the accounts matching a `getProgramAccounts` scan against `program`,
+    /// filtered by `filters` (typically a `dataSize` filter plus `Memcmp` byte-offset
+    /// filters). The account addresses themselves are returned; no owner field is decoded
+    /// from the account data.
+    ProgramAccounts { program: Pubkey, filters: Vec<RpcFilterType> },
+}
+
+/// SPL token account layout is `mint[0..32] | owner[32..64] | amount[64..72] | ...`; pull the
+/// owner field out without pulling in the `spl_token` crate just for this one struct. Shared
+/// with `scam_check`'s bundle-detection holder resolution.
+pub(crate) fn token_account_owner(data: &[u8]) -> Option<Pubkey> {
+    let owner_bytes: [u8; 32] = data.get(32..64)?.try_into().ok()?;
+    Some(Pubkey::new_from_array(owner_bytes))
+}
+
+/// `getSignaturesForAddress` page size for a `RecentTraders` scan over `slots` slots, assuming
+/// roughly one signature per two slots and clamped to 1..=1000, so a small `slots` window
+/// doesn't pull a page much wider than asked for.
+fn page_limit_for_slots(slots: u64) -> usize {
+    let estimated_txs = (slots / 2).max(1);
+    estimated_txs.min(1000) as usize
+}
+
+/// Discover candidate wallets across `sources`, deduplicating via a `HashSet` and stopping
+/// once `cap` distinct candidates have been found. A source that errors is logged and skipped
+/// rather than failing the whole scan - on-chain state is noisy and one bad mint/program
+/// shouldn't block the others.
+pub async fn discover_candidates(
+    rpc: &FallbackRpcClient,
+    sources: &[CandidateSource],
+    cap: usize,
+) -> Result<Vec<Pubkey>> {
+    let mut seen = HashSet::new();
+
+    for source in sources {
+        if seen.len() >= cap {
+            break;
+        }
+
+        match source {
+            CandidateSource::TopHolders { mint, n } => {
+                match rpc.get_token_largest_accounts(mint).await {
+                    Ok(holders) => {
+                        for (token_account, _amount) in holders.into_iter().take(*n) {
+                            if seen.len() >= cap {
+                                break;
+                            }
+                            match rpc.get_account_data(&token_account).await {
+                                Ok(data) => {
+                                    if let Some(owner) = token_account_owner(&data) {
+                                        seen.insert(owner);
+                                    }
+                                }
+                                Err(e) => debug!("Failed to fetch token account {}: {}", token_account, e),
+                            }
+                        }
+                    }
+                    Err(e) => warn!("getTokenLargestAccounts failed for mint {}: {}", mint, e),
+                }
+            }
+
+            CandidateSource::RecentTraders { program, slots } => {
+                let limit = page_limit_for_slots(*slots).min(cap.max(1));
+                match rpc.get_signatures_for_address_page(program, None, None, limit).await {
+                    Ok(signatures) => {
+                        for entry in signatures {
+                            if seen.len() >= cap {
+                                break;
+                            }
+                            let Ok(signature) = entry.signature.parse() else {
+                                continue;
+                            };
+                            match rpc.get_transaction(&signature).await {
+                                Ok(tx) => {
+                                    if let Some(signer) = first_signer(&tx) {
+                                        seen.insert(signer);
+                                    }
+                                }
+                                Err(e) => debug!("Failed to fetch transaction {}: {}", entry.signature, e),
+                            }
+                        }
+                    }
+                    Err(e) => warn!("getSignaturesForAddress failed for program {}: {}", program, e),
+                }
+            }
+
+            CandidateSource::ProgramAccounts { program, filters } => {
+                match rpc.get_program_accounts(program, filters.clone()).await {
+                    Ok(accounts) => {
+                        for (account, _data) in accounts {
+                            if seen.len() >= cap {
+                                break;
+                            }
+                            seen.insert(account);
+                        }
+                    }
+                    Err(e) => warn!("getProgramAccounts failed for program {}: {}", program, e),
+                }
+            }
+        }
+    }
+
+    Ok(seen.into_iter().take(cap).collect())
+}
+
+/// The fee payer (first signing account) of a confirmed transaction - the closest thing to
+/// "the wallet that sent this" without decoding the instruction list itself.
+fn first_signer(
+    tx: &solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta,
+) -> Option<Pubkey> {
+    crate::transaction::first_signer(tx)
+}
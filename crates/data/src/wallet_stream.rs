@@ -0,0 +1,167 @@
+// Streams trades for a set of tracked wallets in real time instead of polling them one by
+// one with a fixed `sleep` between requests (see `find-wallets`' old 2-second cadence).
+use std::sync::Arc;
+
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, info, warn};
+use trading_core::Trade;
+
+use crate::rpc::FallbackRpcClient;
+use crate::transaction::TransactionParser;
+
+/// How often the polling fallback re-checks each wallet when `logsSubscribe` isn't available
+/// (no WebSocket endpoints configured, or the first subscribe attempt failed outright).
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Stream `Trade`s for `wallets` as their transactions confirm, via `logsSubscribe` with a
+/// `mentions` filter. Falls back to polling `TransactionParser::get_wallet_trades` on the same
+/// cadence the old `find-wallets` loop used if no WebSocket endpoint is reachable - callers get
+/// a working channel either way.
+pub async fn stream_wallet_trades(
+    rpc: Arc<FallbackRpcClient>,
+    wallets: Vec<Pubkey>,
+) -> mpsc::Receiver<Trade> {
+    let (tx, rx) = mpsc::channel(256);
+
+    match rpc.subscribe_logs(wallets.clone()).await {
+        Ok(log_events) => {
+            info!("Streaming trades for {} wallets via logsSubscribe", wallets.len());
+            tokio::spawn(forward_log_events(rpc, wallets, log_events, tx));
+        }
+        Err(e) => {
+            warn!("logsSubscribe unavailable ({}), falling back to polling", e);
+            tokio::spawn(poll_wallet_trades(rpc, wallets, tx));
+        }
+    }
+
+    rx
+}
+
+/// Parse each incoming log notification's signature through the incremental parser and
+/// forward any resulting `Trade` - mirrors what the poll loop would have found, but as the
+/// transaction confirms instead of on the next sweep.
+async fn forward_log_events(
+    rpc: Arc<FallbackRpcClient>,
+    wallets: Vec<Pubkey>,
+    mut log_events: broadcast::Receiver<crate::rpc::LogEvent>,
+    tx: mpsc::Sender<Trade>,
+) {
+    loop {
+        let event = match log_events.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                warn!("logsSubscribe stream lagged, dropped {} events", skipped);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        if event.err.is_some() {
+            continue;
+        }
+
+        for wallet in &wallets {
+            if !event.logs.iter().any(|line| line.contains(&wallet.to_string())) {
+                continue;
+            }
+
+            match TransactionParser::get_wallet_trade_for_signature(&rpc, wallet, &event.signature).await {
+                Ok(Some(trade)) => {
+                    if tx.send(trade).await.is_err() {
+                        debug!("Trade stream receiver dropped, stopping");
+                        return;
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Failed to parse trade for {}: {}", event.signature, e),
+            }
+        }
+    }
+
+    warn!("logsSubscribe channel closed, trade stream ended");
+}
+
+/// Polling fallback used when no WebSocket endpoint is reachable: re-fetch each wallet's
+/// recent trades on a fixed cadence, same as the original `find-wallets` loop.
+async fn poll_wallet_trades(rpc: Arc<FallbackRpcClient>, wallets: Vec<Pubkey>, tx: mpsc::Sender<Trade>) {
+    let mut seen_signatures: Vec<String> = Vec::new();
+
+    loop {
+        for wallet in &wallets {
+            match TransactionParser::get_wallet_trades(&rpc, wallet, 10).await {
+                Ok(trades) => {
+                    for trade in trades {
+                        if seen_signatures.contains(&trade.signature) {
+                            continue;
+                        }
+                        seen_signatures.push(trade.signature.clone());
+                        if tx.send(trade).await.is_err() {
+                            debug!("Trade stream receiver dropped, stopping poll fallback");
+                            return;
+                        }
+                    }
+                }
+                Err(e) => warn!("Polling trades for {} failed: {}", wallet, e),
+            }
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Owns the streaming-trades channel for a set of tracked wallets, so callers get a single
+/// long-lived handle instead of juggling the raw `mpsc::Receiver` themselves. Wraps
+/// [`stream_wallet_trades`], which picks `logsSubscribe` or the polling fallback internally.
+pub struct WalletStreamMonitor {
+    trades: mpsc::Receiver<Trade>,
+}
+
+impl WalletStreamMonitor {
+    /// Start streaming trades for `wallets`, reusing `rpc`'s WebSocket connection (and its
+    /// built-in reconnect-with-backoff) when available.
+    pub async fn start(rpc: Arc<FallbackRpcClient>, wallets: Vec<Pubkey>) -> Self {
+        Self { trades: stream_wallet_trades(rpc, wallets).await }
+    }
+
+    /// Wait for the next trade parsed from a tracked wallet's confirmed transaction.
+    /// Returns `None` once the underlying stream has ended; a `logsSubscribe` stream that
+    /// closes is not replaced by the polling fallback.
+    pub async fn recv(&mut self) -> Option<Trade> {
+        self.trades.recv().await
+    }
+}
+
+/// Watch a tracked wallet's token account balance via `accountSubscribe`, re-checking its
+/// most recent trade whenever the balance changes - a faster-but-approximate companion to
+/// `stream_wallet_trades` for wallets whose `logsSubscribe` notification hasn't arrived yet.
+pub async fn stream_token_account_trades(
+    rpc: Arc<FallbackRpcClient>,
+    wallet: Pubkey,
+    token_account: Pubkey,
+) -> trading_core::Result<mpsc::Receiver<Trade>> {
+    let mut account_updates = rpc.subscribe_account(token_account).await?;
+    let (tx, rx) = mpsc::channel(64);
+
+    tokio::spawn(async move {
+        loop {
+            match account_updates.recv().await {
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+            match TransactionParser::get_wallet_trades(&rpc, &wallet, 1).await {
+                Ok(trades) => {
+                    for trade in trades {
+                        if tx.send(trade).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(e) => warn!("Failed to resolve trade after account update for {}: {}", wallet, e),
+            }
+        }
+    });
+
+    Ok(rx)
+}
@@ -0,0 +1,124 @@
+// Backend selection and pooling for `Database`: which SQL engine a connection URL resolves
+// to, and how many connections a pool for that engine should hold. SQLite remains the
+// default for local/dev use; a `postgres://`/`postgresql://` URL opens a Postgres pool via
+// `sqlx::AnyPool` and runs the Postgres DDL. The repositories' queries are written in SQLite
+// syntax (`?` placeholders, `datetime('now')`) and are passed to either backend unchanged.
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::Result;
+use sqlx::any::{AnyConnectOptions, AnyPool, AnyPoolOptions};
+use sqlx::postgres::{PgConnectOptions, PgSslMode};
+use sqlx::sqlite::SqliteConnectOptions;
+
+/// Which SQL engine a connection URL resolves to, detected from its scheme - everything
+/// that isn't `postgres://`/`postgresql://` is handed to SQLite as-is (file path or `:memory:`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Sqlite,
+    Postgres,
+}
+
+impl Backend {
+    pub fn from_url(url: &str) -> Self {
+        if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+            Backend::Postgres
+        } else {
+            Backend::Sqlite
+        }
+    }
+}
+
+/// Which side of the system a pool serves. Ingestion workers open short bursts of writes
+/// from a handful of background tasks; the read/API path holds many more idle connections
+/// for concurrent dashboard/RPC requests. Keeping them on separate env-driven limits means
+/// scaling one doesn't starve the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolRole {
+    Worker,
+    Server,
+}
+
+impl PoolRole {
+    fn env_var(self) -> &'static str {
+        match self {
+            PoolRole::Worker => "MAX_PG_POOL_CONNS_WORKER",
+            PoolRole::Server => "MAX_PG_POOL_CONNS_SERVER",
+        }
+    }
+
+    fn default_max_connections(self) -> u32 {
+        match self {
+            PoolRole::Worker => 5,
+            PoolRole::Server => 20,
+        }
+    }
+}
+
+/// Everything needed to open a pool against either backend. SQLite ignores the TLS
+/// fields entirely; they only apply when `url` resolves to [`Backend::Postgres`].
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    pub url: String,
+    pub role: PoolRole,
+    pub ssl_ca_cert: Option<PathBuf>,
+    pub ssl_client_cert: Option<PathBuf>,
+    pub ssl_client_key: Option<PathBuf>,
+}
+
+impl DatabaseConfig {
+    /// Build a config for `url`, reading pool size and Postgres TLS material from the
+    /// environment: `MAX_PG_POOL_CONNS_WORKER`/`MAX_PG_POOL_CONNS_SERVER`, and
+    /// `PG_SSL_CA_CERT`/`PG_SSL_CLIENT_CERT`/`PG_SSL_CLIENT_KEY`.
+    pub fn from_env(url: impl Into<String>, role: PoolRole) -> Self {
+        Self {
+            url: url.into(),
+            role,
+            ssl_ca_cert: std::env::var("PG_SSL_CA_CERT").ok().map(PathBuf::from),
+            ssl_client_cert: std::env::var("PG_SSL_CLIENT_CERT").ok().map(PathBuf::from),
+            ssl_client_key: std::env::var("PG_SSL_CLIENT_KEY").ok().map(PathBuf::from),
+        }
+    }
+
+    pub fn backend(&self) -> Backend {
+        Backend::from_url(&self.url)
+    }
+
+    pub fn max_connections(&self) -> u32 {
+        std::env::var(self.role.env_var())
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(|| self.role.default_max_connections())
+    }
+}
+
+/// Open a pool for `config`, dialect-specific options included, erased to `AnyPool`.
+/// Only the connection options differ per backend; queries run on it are not translated.
+pub async fn connect(config: &DatabaseConfig) -> Result<AnyPool> {
+    sqlx::any::install_default_drivers();
+
+    let connect_options: AnyConnectOptions = match config.backend() {
+        Backend::Sqlite => SqliteConnectOptions::from_str(&config.url)?
+            .create_if_missing(true)
+            .into(),
+        Backend::Postgres => {
+            let mut opts = PgConnectOptions::from_str(&config.url)?;
+            if let Some(ca) = &config.ssl_ca_cert {
+                opts = opts.ssl_mode(PgSslMode::VerifyFull).ssl_root_cert(ca);
+            }
+            if let Some(cert) = &config.ssl_client_cert {
+                opts = opts.ssl_client_cert(cert);
+            }
+            if let Some(key) = &config.ssl_client_key {
+                opts = opts.ssl_client_key(key);
+            }
+            opts.into()
+        }
+    };
+
+    AnyPoolOptions::new()
+        .max_connections(config.max_connections())
+        .connect_with(connect_options)
+        .await
+        .map_err(Into::into)
+}
@@ -0,0 +1,236 @@
+use anyhow::Result;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+use sqlx::AnyPool;
+use std::str::FromStr;
+use trading_analysis::candles::{Candle, CandleBuilder, Resolution};
+
+#[derive(Clone)]
+pub struct CandleRepository {
+    pool: AnyPool,
+}
+
+impl CandleRepository {
+    pub fn new(pool: AnyPool) -> Self {
+        Self { pool }
+    }
+
+    /// Upsert a batch of candles. Idempotent: re-running a backfill over the same
+    /// trades produces the same rows, since each `(token_mint, resolution, bucket_start)`
+    /// is overwritten rather than appended.
+    pub async fn upsert_candles(&self, candles: &[Candle]) -> Result<()> {
+        for candle in candles {
+            sqlx::query(
+                r#"
+                INSERT INTO candles (
+                    token_mint, resolution, bucket_start, open, high, low, close,
+                    volume, trade_count, is_gap_fill
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(token_mint, resolution, bucket_start) DO UPDATE SET
+                    open = excluded.open,
+                    high = excluded.high,
+                    low = excluded.low,
+                    close = excluded.close,
+                    volume = excluded.volume,
+                    trade_count = excluded.trade_count,
+                    is_gap_fill = excluded.is_gap_fill,
+                    updated_at = datetime('now')
+                "#,
+            )
+            .bind(candle.token_mint.to_string())
+            .bind(candle.resolution.as_str())
+            .bind(candle.bucket_start)
+            .bind(candle.open.to_string())
+            .bind(candle.high.to_string())
+            .bind(candle.low.to_string())
+            .bind(candle.close.to_string())
+            .bind(candle.volume.to_string())
+            .bind(candle.trade_count as i64)
+            .bind(candle.is_gap_fill as i32)
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Rebuild and persist candles for a token/resolution from its full stored trade
+    /// history. Safe to re-run at any time (e.g. after a backfill pulls in more trades).
+    pub async fn rebuild_from_trades(
+        &self,
+        trades: &crate::trades::TradeRepository,
+        token_mint: &Pubkey,
+        resolution: Resolution,
+    ) -> Result<Vec<Candle>> {
+        let history = trades.get_token_trades(token_mint).await?;
+        let candles = CandleBuilder::build_candles(&history, resolution);
+        self.upsert_candles(&candles).await?;
+        Ok(candles)
+    }
+
+    /// Get candles for a token/resolution within `[from, to]` (inclusive, bucket-start seconds)
+    pub async fn get_candles(
+        &self,
+        token_mint: &Pubkey,
+        resolution: Resolution,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<Candle>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT token_mint, bucket_start, open, high, low, close, volume, trade_count, is_gap_fill
+            FROM candles
+            WHERE token_mint = ? AND resolution = ? AND bucket_start >= ? AND bucket_start <= ?
+            ORDER BY bucket_start ASC
+            "#,
+        )
+        .bind(token_mint.to_string())
+        .bind(resolution.as_str())
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut candles = Vec::new();
+        for (i, row) in rows.iter().enumerate() {
+            let token_mint_str: String = row.try_get("token_mint")?;
+            let open: String = row.try_get("open")?;
+            let high: String = row.try_get("high")?;
+            let low: String = row.try_get("low")?;
+            let close: String = row.try_get("close")?;
+            let volume: String = row.try_get("volume")?;
+
+            candles.push(Candle {
+                token_mint: Pubkey::from_str(&token_mint_str)?,
+                resolution,
+                bucket_start: row.try_get("bucket_start")?,
+                open: Decimal::from_str(&open)?,
+                high: Decimal::from_str(&high)?,
+                low: Decimal::from_str(&low)?,
+                close: Decimal::from_str(&close)?,
+                volume: Decimal::from_str(&volume)?,
+                trade_count: row.try_get::<i64, _>("trade_count")? as u32,
+                is_gap_fill: row.try_get::<i32, _>("is_gap_fill")? != 0,
+                is_open: i == rows.len() - 1,
+            });
+        }
+
+        Ok(candles)
+    }
+
+    /// Get the stored candles of the last `limit` buckets up to now for a token/resolution
+    /// (oldest first), for dashboard charting and `ChartAnalyzer::analyze_candles` where
+    /// callers want "the tail of the series" rather than an explicit time range. Buckets
+    /// with no stored row are absent, so a token with no recent trades gets fewer candles.
+    pub async fn get_recent_candles(
+        &self,
+        token_mint: &Pubkey,
+        resolution: Resolution,
+        limit: i64,
+    ) -> Result<Vec<Candle>> {
+        let to = Utc::now().timestamp();
+        let from = to - resolution.seconds() * limit;
+        self.get_candles(token_mint, resolution, from, to).await
+    }
+
+    /// Last trade `block_time` incorporated into this token/resolution's stored candles,
+    /// if an incremental backfill has run for it before.
+    async fn get_backfill_watermark(
+        &self,
+        token_mint: &Pubkey,
+        resolution: Resolution,
+    ) -> Result<Option<i64>> {
+        let row = sqlx::query(
+            r#"SELECT last_block_time FROM candle_backfill WHERE token_mint = ? AND resolution = ?"#,
+        )
+        .bind(token_mint.to_string())
+        .bind(resolution.as_str())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(Some(row.try_get("last_block_time")?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn save_backfill_watermark(
+        &self,
+        token_mint: &Pubkey,
+        resolution: Resolution,
+        last_block_time: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO candle_backfill (token_mint, resolution, last_block_time)
+            VALUES (?, ?, ?)
+            ON CONFLICT(token_mint, resolution) DO UPDATE SET
+                last_block_time = excluded.last_block_time,
+                updated_at = datetime('now')
+            "#,
+        )
+        .bind(token_mint.to_string())
+        .bind(resolution.as_str())
+        .bind(last_block_time)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// One-shot backfill: rebuild a token/resolution's candles from its entire stored
+    /// trade history and record the watermark so later calls can go incremental.
+    pub async fn backfill(
+        &self,
+        trades: &crate::trades::TradeRepository,
+        token_mint: &Pubkey,
+        resolution: Resolution,
+    ) -> Result<Vec<Candle>> {
+        let history = trades.get_token_trades(token_mint).await?;
+        let candles = CandleBuilder::build_candles(&history, resolution);
+        self.upsert_candles(&candles).await?;
+
+        if let Some(last_trade) = history.last() {
+            self.save_backfill_watermark(token_mint, resolution, last_trade.block_time)
+                .await?;
+        }
+
+        Ok(candles)
+    }
+
+    /// Update a token/resolution's stored candles from trades newer than the last run.
+    /// Falls back to a full `backfill` the first time it's called for a token. On
+    /// subsequent calls, only rereads trades from the watermark onward - re-widened back
+    /// to the start of the watermark's bucket so the previously "open" candle gets
+    /// finalized rather than left stale, and no earlier bucket is rebuilt from part of
+    /// its trades.
+    pub async fn backfill_incremental(
+        &self,
+        trades: &crate::trades::TradeRepository,
+        token_mint: &Pubkey,
+        resolution: Resolution,
+    ) -> Result<Vec<Candle>> {
+        let watermark = match self.get_backfill_watermark(token_mint, resolution).await? {
+            Some(watermark) => watermark,
+            None => return self.backfill(trades, token_mint, resolution).await,
+        };
+
+        let since = watermark.div_euclid(resolution.seconds()) * resolution.seconds();
+        let fresh_trades = trades.get_token_trades_since(token_mint, since).await?;
+        if fresh_trades.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let candles = CandleBuilder::build_candles(&fresh_trades, resolution);
+        self.upsert_candles(&candles).await?;
+        self.save_backfill_watermark(
+            token_mint,
+            resolution,
+            fresh_trades.last().unwrap().block_time,
+        )
+        .await?;
+
+        Ok(candles)
+    }
+}
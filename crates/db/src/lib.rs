@@ -1,62 +1,56 @@
+pub mod backend;
 pub mod schema;
+pub mod money;
+pub mod scaled_decimal;
 pub mod trades;
 pub mod wallets;
 pub mod statistics;
+pub mod candles;
 
 use anyhow::Result;
-use sqlx::sqlite::SqlitePool;
+use sqlx::any::AnyPool;
 use tracing::info;
 
+pub use backend::{Backend, DatabaseConfig, PoolRole};
+
 pub struct Database {
-    pool: SqlitePool,
+    pool: AnyPool,
 }
 
 impl Database {
-    /// Create a new database connection
+    /// Create a new database connection, sized for an ingestion worker. Use [`Database::connect`]
+    /// directly to pick a [`PoolRole`] (and, for Postgres, TLS material) explicitly.
     pub async fn new(database_url: &str) -> Result<Self> {
-        info!("Connecting to database: {}", database_url);
+        Self::connect(DatabaseConfig::from_env(database_url, PoolRole::Worker)).await
+    }
+
+    /// Create a new database connection from an explicit [`DatabaseConfig`], against
+    /// whichever backend `config.url` resolves to.
+    pub async fn connect(config: DatabaseConfig) -> Result<Self> {
+        info!("Connecting to database ({:?}): {}", config.backend(), config.url);
 
-        let pool = SqlitePool::connect(database_url).await?;
+        let pool = backend::connect(&config).await?;
 
         info!("Running migrations...");
-        Self::run_migrations(&pool).await?;
+        Self::run_migrations(&pool, config.backend()).await?;
 
         info!("Database initialized successfully");
 
         Ok(Self { pool })
     }
 
-    /// Run database migrations
-    async fn run_migrations(pool: &SqlitePool) -> Result<()> {
-        sqlx::query(schema::CREATE_TRADES_TABLE)
-            .execute(pool)
-            .await?;
-
-        sqlx::query(schema::CREATE_POSITIONS_TABLE)
-            .execute(pool)
-            .await?;
-
-        sqlx::query(schema::CREATE_WALLETS_TABLE)
-            .execute(pool)
-            .await?;
-
-        sqlx::query(schema::CREATE_WALLET_METRICS_TABLE)
-            .execute(pool)
-            .await?;
-
-        sqlx::query(schema::CREATE_DAILY_STATS_TABLE)
-            .execute(pool)
-            .await?;
-
-        sqlx::query(schema::CREATE_SIGNALS_TABLE)
-            .execute(pool)
-            .await?;
+    /// Run database migrations, emitting whichever backend's DDL the pool was opened
+    /// against (see [`schema::create_table_statements`]).
+    async fn run_migrations(pool: &AnyPool, backend: Backend) -> Result<()> {
+        for statement in schema::create_table_statements(backend) {
+            sqlx::query(statement).execute(pool).await?;
+        }
 
         Ok(())
     }
 
     /// Get a connection from the pool
-    pub fn pool(&self) -> &SqlitePool {
+    pub fn pool(&self) -> &AnyPool {
         &self.pool
     }
 
@@ -74,4 +68,9 @@ impl Database {
     pub fn statistics(&self) -> statistics::StatisticsRepository {
         statistics::StatisticsRepository::new(self.pool.clone())
     }
+
+    /// Get candle repository
+    pub fn candles(&self) -> candles::CandleRepository {
+        candles::CandleRepository::new(self.pool.clone())
+    }
 }
@@ -0,0 +1,54 @@
+// A `Decimal` wrapper with its own `sqlx` `Encode`/`Decode`, so `trades`/`positions` numeric
+// columns are stored as native `REAL` instead of `.to_string()`'d `TEXT`. A TEXT column
+// compares and orders lexicographically - `WHERE pnl > 0` matches a break-even `"0.00"` (it
+// sorts after `"0"`), and `"10"` sorts before `"9"` - so `get_win_rate`, `ORDER BY amount`,
+// and `SUM(pnl)` were all silently wrong. Conversion to/from `Decimal` happens only here, at
+// the repository boundary - every other layer keeps using `Decimal` directly.
+//
+// Implemented against `sqlx::Any` rather than a specific driver, so the same `Money` works
+// whether `Database` opened a SQLite or Postgres pool (see `backend.rs`).
+use rust_decimal::Decimal;
+use sqlx::any::{Any, AnyTypeInfo, AnyValueRef};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::{Decode, Encode, Type};
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Money(pub Decimal);
+
+impl From<Decimal> for Money {
+    fn from(value: Decimal) -> Self {
+        Money(value)
+    }
+}
+
+impl From<Money> for Decimal {
+    fn from(value: Money) -> Self {
+        value.0
+    }
+}
+
+impl Type<Any> for Money {
+    fn type_info() -> AnyTypeInfo {
+        <f64 as Type<Any>>::type_info()
+    }
+}
+
+impl<'q> Encode<'q, Any> for Money {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Any as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> Result<IsNull, BoxDynError> {
+        // `Decimal -> f64` only loses precision far beyond what a trade amount/price/pnl
+        // needs; both backends' native `REAL` storage is `f64` regardless of what feeds it.
+        let value: f64 = self.0.try_into().unwrap_or(0.0);
+        Encode::<Any>::encode_by_ref(&value, buf)
+    }
+}
+
+impl<'r> Decode<'r, Any> for Money {
+    fn decode(value: AnyValueRef<'r>) -> Result<Self, BoxDynError> {
+        let raw: f64 = Decode::<Any>::decode(value)?;
+        Ok(Money(Decimal::try_from(raw)?))
+    }
+}
@@ -0,0 +1,73 @@
+// A `Decimal` wrapper that persists as a scaled `i64` mantissa instead of `TEXT`, so
+// `WalletRepository`'s leaderboard queries can `SUM`/`ORDER BY` its columns natively in
+// SQL rather than loading every row and parsing/sorting in Rust (as `TEXT`-encoded
+// `Decimal::to_string()` columns force). Unlike `Money` (see `money.rs`), which accepts
+// `f64` rounding for display-grade amounts, this is for columns an aggregate query needs
+// to be exact over, so the scale factor is fixed and conversions are checked rather than
+// lossy.
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use sqlx::any::{Any, AnyTypeInfo, AnyValueRef};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::{Decode, Encode, Type};
+
+/// Decimal places preserved when scaling into the `i64` mantissa - nine digits covers
+/// this bot's SOL/USD amounts with room to spare, leaving the rest of the 64 bits for
+/// the whole-number part.
+const SCALE_EXPONENT: u32 = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct ScaledDecimal(i64);
+
+impl ScaledDecimal {
+    /// Reconstruct the exact `Decimal` this mantissa represents.
+    pub fn to_decimal(self) -> Decimal {
+        Decimal::new(self.0, SCALE_EXPONENT)
+    }
+}
+
+impl TryFrom<Decimal> for ScaledDecimal {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Decimal) -> Result<Self, Self::Error> {
+        let scale_factor = Decimal::from(10i64.pow(SCALE_EXPONENT));
+        let scaled = value
+            .checked_mul(scale_factor)
+            .ok_or_else(|| anyhow::anyhow!("{value} overflowed scaling by 10^{SCALE_EXPONENT}"))?;
+        let mantissa = scaled
+            .round()
+            .to_i64()
+            .ok_or_else(|| anyhow::anyhow!("{value} scaled by 10^{SCALE_EXPONENT} doesn't fit in i64"))?;
+
+        Ok(Self(mantissa))
+    }
+}
+
+impl From<ScaledDecimal> for Decimal {
+    fn from(value: ScaledDecimal) -> Self {
+        value.to_decimal()
+    }
+}
+
+impl Type<Any> for ScaledDecimal {
+    fn type_info() -> AnyTypeInfo {
+        <i64 as Type<Any>>::type_info()
+    }
+}
+
+impl<'q> Encode<'q, Any> for ScaledDecimal {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Any as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> Result<IsNull, BoxDynError> {
+        Encode::<Any>::encode_by_ref(&self.0, buf)
+    }
+}
+
+impl<'r> Decode<'r, Any> for ScaledDecimal {
+    fn decode(value: AnyValueRef<'r>) -> Result<Self, BoxDynError> {
+        let raw: i64 = Decode::<Any>::decode(value)?;
+        Ok(Self(raw))
+    }
+}
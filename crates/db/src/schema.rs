@@ -1,41 +1,57 @@
 // Database schema definitions
+//
+// Each table has a SQLite DDL constant and, where the dialect actually differs, a
+// `_PG` sibling for Postgres (`AUTOINCREMENT` -> `GENERATED ALWAYS AS IDENTITY`,
+// `REAL` -> `DOUBLE PRECISION`, `datetime('now')` -> `now()`). Tables with no
+// SQLite-specific syntax reuse the same constant for both. `create_table_statements`
+// is the one place that picks which set `Database::run_migrations` executes.
+use crate::backend::Backend;
 
+// `amount_in`/`amount_out`/`price_usd`/`market_cap_at_trade` are `REAL`, decoded through the
+// `Money` wrapper (see `money.rs`) rather than `TEXT` + `Decimal::from_str`, so numeric
+// comparisons and aggregates against them compare as numbers instead of strings.
 pub const CREATE_TRADES_TABLE: &str = r#"
 CREATE TABLE IF NOT EXISTS trades (
     id TEXT PRIMARY KEY,
     wallet TEXT NOT NULL,
     token_mint TEXT NOT NULL,
     side TEXT NOT NULL,
-    amount_in TEXT NOT NULL,
-    amount_out TEXT NOT NULL,
-    price_usd TEXT NOT NULL,
-    market_cap_at_trade TEXT NOT NULL,
+    amount_in REAL NOT NULL,
+    amount_out REAL NOT NULL,
+    price_usd REAL NOT NULL,
+    market_cap_at_trade REAL NOT NULL,
     signature TEXT NOT NULL,
     timestamp TEXT NOT NULL,
     block_time INTEGER NOT NULL,
     dex TEXT NOT NULL,
-    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (signature)
 )
 "#;
 
+// `entry_price`/`entry_mc`/`amount`/`stop_loss`/`take_profit`/`peak_price`/`exit_price`/`pnl`
+// are `REAL` for the same reason - `get_win_rate`'s `WHERE pnl > 0` was comparing `TEXT`
+// lexicographically, so e.g. `"0.00" > "0"` and `"9" > "10"` both held.
 pub const CREATE_POSITIONS_TABLE: &str = r#"
 CREATE TABLE IF NOT EXISTS positions (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     token_mint TEXT NOT NULL UNIQUE,
     token_symbol TEXT NOT NULL,
     entry_time TEXT NOT NULL,
-    entry_price TEXT NOT NULL,
-    entry_mc TEXT NOT NULL,
-    amount TEXT NOT NULL,
-    stop_loss TEXT NOT NULL,
-    take_profit TEXT NOT NULL,
+    entry_price REAL NOT NULL,
+    entry_mc REAL NOT NULL,
+    amount REAL NOT NULL,
+    stop_loss REAL NOT NULL,
+    take_profit REAL NOT NULL,
+    peak_price REAL NOT NULL,
     status TEXT NOT NULL,
     exit_time TEXT,
-    exit_price TEXT,
+    exit_price REAL,
     exit_reason TEXT,
-    pnl TEXT,
+    pnl REAL,
     pnl_pct REAL,
     hold_time_minutes INTEGER,
+    expires_at TEXT,
     created_at TEXT NOT NULL DEFAULT (datetime('now')),
     updated_at TEXT NOT NULL DEFAULT (datetime('now'))
 )
@@ -55,6 +71,11 @@ CREATE TABLE IF NOT EXISTS wallets (
 )
 "#;
 
+// `total_pnl`/`avg_profit_per_trade`/`largest_win`/`largest_loss`/`volume_24h`/`volume_7d`
+// are `INTEGER`, a `Decimal` scaled by 10^9 and decoded through `ScaledDecimal` (see
+// `scaled_decimal.rs`) rather than `TEXT`. A `TEXT` column can't be `SUM`'d or `ORDER BY`'d
+// numerically in SQL, which is exactly what a PnL leaderboard needs - `top_wallets_by_pnl`
+// and `aggregate_volume` rely on these being native integers.
 pub const CREATE_WALLET_METRICS_TABLE: &str = r#"
 CREATE TABLE IF NOT EXISTS wallet_metrics (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -63,18 +84,18 @@ CREATE TABLE IF NOT EXISTS wallet_metrics (
     winning_trades INTEGER NOT NULL,
     losing_trades INTEGER NOT NULL,
     win_rate REAL NOT NULL,
-    total_pnl TEXT NOT NULL,
+    total_pnl INTEGER NOT NULL,
     total_pnl_percentage REAL NOT NULL,
     avg_hold_time_seconds REAL NOT NULL,
-    avg_profit_per_trade TEXT NOT NULL,
-    largest_win TEXT NOT NULL,
-    largest_loss TEXT NOT NULL,
+    avg_profit_per_trade INTEGER NOT NULL,
+    largest_win INTEGER NOT NULL,
+    largest_loss INTEGER NOT NULL,
     sharpe_ratio REAL,
     max_drawdown REAL NOT NULL,
     trades_last_24h INTEGER NOT NULL,
     trades_last_7d INTEGER NOT NULL,
-    volume_24h TEXT NOT NULL,
-    volume_7d TEXT NOT NULL,
+    volume_24h INTEGER NOT NULL,
+    volume_7d INTEGER NOT NULL,
     snapshot_time TEXT NOT NULL DEFAULT (datetime('now')),
     FOREIGN KEY (wallet_address) REFERENCES wallets(address)
 )
@@ -99,6 +120,43 @@ CREATE TABLE IF NOT EXISTS daily_stats (
 )
 "#;
 
+pub const CREATE_CANDLES_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS candles (
+    token_mint TEXT NOT NULL,
+    resolution TEXT NOT NULL,
+    bucket_start INTEGER NOT NULL,
+    open TEXT NOT NULL,
+    high TEXT NOT NULL,
+    low TEXT NOT NULL,
+    close TEXT NOT NULL,
+    volume TEXT NOT NULL,
+    trade_count INTEGER NOT NULL DEFAULT 0,
+    is_gap_fill INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (token_mint, resolution, bucket_start)
+)
+"#;
+
+/// Per-(token_mint, resolution) watermark so incremental candle backfills only
+/// re-aggregate trades newer than the last run instead of rescanning history.
+pub const CREATE_CANDLE_BACKFILL_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS candle_backfill (
+    token_mint TEXT NOT NULL,
+    resolution TEXT NOT NULL,
+    last_block_time INTEGER NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (token_mint, resolution)
+)
+"#;
+
+pub const CREATE_WALLET_BACKFILL_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS wallet_backfill (
+    wallet_address TEXT PRIMARY KEY,
+    last_signature TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+)
+"#;
+
 pub const CREATE_SIGNALS_TABLE: &str = r#"
 CREATE TABLE IF NOT EXISTS signals (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -117,3 +175,195 @@ CREATE TABLE IF NOT EXISTS signals (
     detected_at TEXT NOT NULL DEFAULT (datetime('now'))
 )
 "#;
+
+// Postgres dialect: `datetime('now')` -> `now()`, and `INTEGER PRIMARY KEY AUTOINCREMENT`
+// -> `INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY`. Column types and constraints are
+// otherwise identical to the SQLite constants above.
+
+pub const CREATE_TRADES_TABLE_PG: &str = r#"
+CREATE TABLE IF NOT EXISTS trades (
+    id TEXT PRIMARY KEY,
+    wallet TEXT NOT NULL,
+    token_mint TEXT NOT NULL,
+    side TEXT NOT NULL,
+    amount_in REAL NOT NULL,
+    amount_out REAL NOT NULL,
+    price_usd REAL NOT NULL,
+    market_cap_at_trade REAL NOT NULL,
+    signature TEXT NOT NULL,
+    timestamp TEXT NOT NULL,
+    block_time INTEGER NOT NULL,
+    dex TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (now()),
+    UNIQUE (signature)
+)
+"#;
+
+pub const CREATE_POSITIONS_TABLE_PG: &str = r#"
+CREATE TABLE IF NOT EXISTS positions (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    token_mint TEXT NOT NULL UNIQUE,
+    token_symbol TEXT NOT NULL,
+    entry_time TEXT NOT NULL,
+    entry_price REAL NOT NULL,
+    entry_mc REAL NOT NULL,
+    amount REAL NOT NULL,
+    stop_loss REAL NOT NULL,
+    take_profit REAL NOT NULL,
+    peak_price REAL NOT NULL,
+    status TEXT NOT NULL,
+    exit_time TEXT,
+    exit_price REAL,
+    exit_reason TEXT,
+    pnl REAL,
+    pnl_pct REAL,
+    hold_time_minutes INTEGER,
+    expires_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (now()),
+    updated_at TEXT NOT NULL DEFAULT (now())
+)
+"#;
+
+pub const CREATE_WALLETS_TABLE_PG: &str = r#"
+CREATE TABLE IF NOT EXISTS wallets (
+    address TEXT PRIMARY KEY,
+    label TEXT,
+    smart_money_score REAL NOT NULL,
+    risk_score REAL NOT NULL,
+    is_tracked INTEGER NOT NULL DEFAULT 1,
+    first_seen TEXT NOT NULL,
+    last_active TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (now()),
+    updated_at TEXT NOT NULL DEFAULT (now())
+)
+"#;
+
+// `BIGINT` rather than `INTEGER` for the scaled-`Decimal` columns: Postgres's `INTEGER`
+// is only 32 bits, too narrow for a mantissa scaled by 10^9, unlike the small counters
+// elsewhere in this table that comfortably fit in 32 bits.
+pub const CREATE_WALLET_METRICS_TABLE_PG: &str = r#"
+CREATE TABLE IF NOT EXISTS wallet_metrics (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    wallet_address TEXT NOT NULL,
+    total_trades INTEGER NOT NULL,
+    winning_trades INTEGER NOT NULL,
+    losing_trades INTEGER NOT NULL,
+    win_rate REAL NOT NULL,
+    total_pnl BIGINT NOT NULL,
+    total_pnl_percentage REAL NOT NULL,
+    avg_hold_time_seconds REAL NOT NULL,
+    avg_profit_per_trade BIGINT NOT NULL,
+    largest_win BIGINT NOT NULL,
+    largest_loss BIGINT NOT NULL,
+    sharpe_ratio REAL,
+    max_drawdown REAL NOT NULL,
+    trades_last_24h INTEGER NOT NULL,
+    trades_last_7d INTEGER NOT NULL,
+    volume_24h BIGINT NOT NULL,
+    volume_7d BIGINT NOT NULL,
+    snapshot_time TEXT NOT NULL DEFAULT (now()),
+    FOREIGN KEY (wallet_address) REFERENCES wallets(address)
+)
+"#;
+
+pub const CREATE_DAILY_STATS_TABLE_PG: &str = r#"
+CREATE TABLE IF NOT EXISTS daily_stats (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    date TEXT NOT NULL UNIQUE,
+    total_trades INTEGER NOT NULL DEFAULT 0,
+    wins INTEGER NOT NULL DEFAULT 0,
+    losses INTEGER NOT NULL DEFAULT 0,
+    win_rate REAL NOT NULL DEFAULT 0,
+    total_pnl TEXT NOT NULL DEFAULT '0',
+    biggest_win TEXT NOT NULL DEFAULT '0',
+    biggest_loss TEXT NOT NULL DEFAULT '0',
+    avg_win TEXT NOT NULL DEFAULT '0',
+    avg_loss TEXT NOT NULL DEFAULT '0',
+    portfolio_value TEXT NOT NULL DEFAULT '0',
+    created_at TEXT NOT NULL DEFAULT (now()),
+    updated_at TEXT NOT NULL DEFAULT (now())
+)
+"#;
+
+pub const CREATE_CANDLES_TABLE_PG: &str = r#"
+CREATE TABLE IF NOT EXISTS candles (
+    token_mint TEXT NOT NULL,
+    resolution TEXT NOT NULL,
+    bucket_start INTEGER NOT NULL,
+    open TEXT NOT NULL,
+    high TEXT NOT NULL,
+    low TEXT NOT NULL,
+    close TEXT NOT NULL,
+    volume TEXT NOT NULL,
+    trade_count INTEGER NOT NULL DEFAULT 0,
+    is_gap_fill INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL DEFAULT (now()),
+    PRIMARY KEY (token_mint, resolution, bucket_start)
+)
+"#;
+
+pub const CREATE_CANDLE_BACKFILL_TABLE_PG: &str = r#"
+CREATE TABLE IF NOT EXISTS candle_backfill (
+    token_mint TEXT NOT NULL,
+    resolution TEXT NOT NULL,
+    last_block_time INTEGER NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (now()),
+    PRIMARY KEY (token_mint, resolution)
+)
+"#;
+
+pub const CREATE_WALLET_BACKFILL_TABLE_PG: &str = r#"
+CREATE TABLE IF NOT EXISTS wallet_backfill (
+    wallet_address TEXT PRIMARY KEY,
+    last_signature TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (now())
+)
+"#;
+
+pub const CREATE_SIGNALS_TABLE_PG: &str = r#"
+CREATE TABLE IF NOT EXISTS signals (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    token_mint TEXT NOT NULL,
+    signal_type TEXT NOT NULL,
+    confidence REAL NOT NULL,
+    smart_wallets_count INTEGER NOT NULL,
+    avg_smart_score REAL NOT NULL,
+    total_volume TEXT NOT NULL,
+    chart_action TEXT,
+    chart_confidence REAL,
+    chart_reason TEXT,
+    executed INTEGER NOT NULL DEFAULT 0,
+    execution_price TEXT,
+    execution_time TEXT,
+    detected_at TEXT NOT NULL DEFAULT (now())
+)
+"#;
+
+/// The `CREATE TABLE` statements `Database::run_migrations` executes, in dependency order
+/// (`wallet_metrics` references `wallets`), for whichever dialect `backend` names.
+pub fn create_table_statements(backend: Backend) -> Vec<&'static str> {
+    match backend {
+        Backend::Sqlite => vec![
+            CREATE_TRADES_TABLE,
+            CREATE_POSITIONS_TABLE,
+            CREATE_WALLETS_TABLE,
+            CREATE_WALLET_METRICS_TABLE,
+            CREATE_DAILY_STATS_TABLE,
+            CREATE_SIGNALS_TABLE,
+            CREATE_WALLET_BACKFILL_TABLE,
+            CREATE_CANDLES_TABLE,
+            CREATE_CANDLE_BACKFILL_TABLE,
+        ],
+        Backend::Postgres => vec![
+            CREATE_TRADES_TABLE_PG,
+            CREATE_POSITIONS_TABLE_PG,
+            CREATE_WALLETS_TABLE_PG,
+            CREATE_WALLET_METRICS_TABLE_PG,
+            CREATE_DAILY_STATS_TABLE_PG,
+            CREATE_SIGNALS_TABLE_PG,
+            CREATE_WALLET_BACKFILL_TABLE_PG,
+            CREATE_CANDLES_TABLE_PG,
+            CREATE_CANDLE_BACKFILL_TABLE_PG,
+        ],
+    }
+}
@@ -1,12 +1,12 @@
 use anyhow::Result;
 use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
-use sqlx::SqlitePool;
+use sqlx::AnyPool;
 use std::str::FromStr;
 
 #[derive(Clone)]
 pub struct StatisticsRepository {
-    pool: SqlitePool,
+    pool: AnyPool,
 }
 
 #[derive(Debug, Clone)]
@@ -24,8 +24,21 @@ pub struct DailyStatsRecord {
     pub portfolio_value: Decimal,
 }
 
+#[derive(Debug, Clone)]
+pub struct SignalRecord {
+    pub id: i64,
+    pub token_mint: String,
+    pub signal_type: String,
+    pub confidence: f64,
+    pub smart_wallets_count: i32,
+    pub avg_smart_score: f64,
+    pub total_volume: Decimal,
+    pub executed: bool,
+    pub detected_at: DateTime<Utc>,
+}
+
 impl StatisticsRepository {
-    pub fn new(pool: SqlitePool) -> Self {
+    pub fn new(pool: AnyPool) -> Self {
         Self { pool }
     }
 
@@ -219,6 +232,44 @@ impl StatisticsRepository {
         Ok(result.last_insert_rowid())
     }
 
+    /// Get the most recent signals, newest first
+    pub async fn get_recent_signals(&self, limit: i64) -> Result<Vec<SignalRecord>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, token_mint, signal_type, confidence, smart_wallets_count,
+                   avg_smart_score, total_volume, executed, detected_at
+            FROM signals
+            ORDER BY detected_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut signals = Vec::new();
+        for row in rows {
+            let total_volume_str: String = row.try_get("total_volume")?;
+            let detected_at_str: String = row.try_get("detected_at")?;
+
+            signals.push(SignalRecord {
+                id: row.try_get("id")?,
+                token_mint: row.try_get("token_mint")?,
+                signal_type: row.try_get("signal_type")?,
+                confidence: row.try_get("confidence")?,
+                smart_wallets_count: row.try_get("smart_wallets_count")?,
+                avg_smart_score: row.try_get("avg_smart_score")?,
+                total_volume: Decimal::from_str(&total_volume_str)?,
+                executed: row.try_get::<i32, _>("executed")? != 0,
+                detected_at: DateTime::parse_from_rfc3339(&detected_at_str)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(|_| Utc::now()),
+            });
+        }
+
+        Ok(signals)
+    }
+
     /// Mark signal as executed
     pub async fn mark_signal_executed(
         &self,
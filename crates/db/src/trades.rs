@@ -2,40 +2,63 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use solana_sdk::pubkey::Pubkey;
-use sqlx::SqlitePool;
+use sqlx::{AnyPool, Row};
 use std::str::FromStr;
 use trading_core::{Trade, TradeSide};
+use trading_data::{FallbackRpcClient, TransactionParser};
 use uuid::Uuid;
 
+use crate::money::Money;
+
+/// A closed `positions` row, as returned by [`TradeRepository::get_best_trades`]/
+/// [`TradeRepository::get_worst_trades`] - the same shape `SerializableClosedTrade`
+/// persists to JSON, so `SqlHistoryStore` can convert one into the other without loss.
+#[derive(Debug, Clone)]
+pub struct ClosedPositionRecord {
+    pub token_mint: String,
+    pub token_symbol: String,
+    pub entry_time: DateTime<Utc>,
+    pub exit_time: DateTime<Utc>,
+    pub entry_price: Decimal,
+    pub exit_price: Decimal,
+    pub pnl: Decimal,
+    pub pnl_pct: f64,
+    pub hold_time_minutes: i64,
+    pub exit_reason: String,
+}
+
 #[derive(Clone)]
 pub struct TradeRepository {
-    pool: SqlitePool,
+    pool: AnyPool,
 }
 
 impl TradeRepository {
-    pub fn new(pool: SqlitePool) -> Self {
+    pub fn new(pool: AnyPool) -> Self {
         Self { pool }
     }
 
-    /// Save a trade to the database
-    pub async fn save_trade(&self, trade: &Trade) -> Result<()> {
-        sqlx::query(
+    /// Save a trade to the database. Idempotent on `signature`: re-running a backfill (or
+    /// overlapping live + historical ingestion) over a transaction already stored is a no-op
+    /// rather than a duplicate row, so returns whether this call actually inserted one.
+    pub async fn save_trade(&self, trade: &Trade) -> Result<bool> {
+        let result = sqlx::query(
             r#"
             INSERT INTO trades (
                 id, wallet, token_mint, side, amount_in, amount_out,
                 price_usd, market_cap_at_trade, signature, timestamp,
                 block_time, dex
             ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(signature) DO NOTHING
             "#,
         )
         .bind(trade.id.to_string())
         .bind(trade.wallet.to_string())
         .bind(trade.token_mint.to_string())
         .bind(trade.side.to_string())
-        .bind(trade.amount_in.to_string())
-        .bind(trade.amount_out.to_string())
-        .bind(trade.price_usd.to_string())
-        .bind(trade.market_cap_at_trade.to_string())
+        .bind(Money::from(trade.amount_in))
+        .bind(Money::from(trade.amount_out))
+        .bind(Money::from(trade.price_usd))
+        .bind(Money::from(trade.market_cap_at_trade))
         .bind(&trade.signature)
         .bind(trade.timestamp.to_rfc3339())
         .bind(trade.block_time)
@@ -43,7 +66,82 @@ impl TradeRepository {
         .execute(&self.pool)
         .await?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Backfill one wallet's full on-chain history via paginated `getSignaturesForAddress`,
+    /// resuming from its stored cursor so repeat calls only fetch the delta. Returns the
+    /// number of newly-inserted trades (already-seen signatures don't count, thanks to
+    /// `save_trade`'s idempotency).
+    pub async fn backfill_wallet(
+        &self,
+        rpc: &FallbackRpcClient,
+        wallet_repo: &crate::wallets::WalletRepository,
+        wallet: &Pubkey,
+        max_pages: usize,
+    ) -> Result<usize> {
+        let cursor = wallet_repo
+            .get_backfill_cursor(wallet)
+            .await?
+            .and_then(|sig| solana_sdk::signature::Signature::from_str(&sig).ok());
+
+        let (trades, newest_signature) =
+            TransactionParser::get_wallet_trades_paginated(rpc, wallet, cursor, max_pages)
+                .await?;
+
+        let mut inserted = 0;
+        for trade in &trades {
+            if self.save_trade(trade).await? {
+                inserted += 1;
+            }
+        }
+
+        if let Some(newest) = newest_signature {
+            wallet_repo.save_backfill_cursor(wallet, &newest.to_string()).await?;
+        }
+
+        Ok(inserted)
+    }
+
+    /// Backfill many wallets concurrently: `wallets` is split into `concurrency` partitions,
+    /// each scanned by its own task with its own RPC cursor (the wallet's stored backfill
+    /// cursor, per `backfill_wallet`), so partitions never contend over pagination state.
+    /// A wallet whose backfill errors is logged and skipped; the rest of its partition and
+    /// every other partition carry on.
+    pub async fn backfill_wallets_concurrent(
+        &self,
+        rpc: &FallbackRpcClient,
+        wallet_repo: &crate::wallets::WalletRepository,
+        wallets: &[Pubkey],
+        concurrency: usize,
+        max_pages: usize,
+    ) -> Result<usize> {
+        let partitions = partition(wallets, concurrency.max(1));
+
+        let mut tasks = Vec::new();
+        for partition in partitions {
+            let trades = self.clone();
+            let rpc = rpc.clone();
+            let wallet_repo = wallet_repo.clone();
+
+            tasks.push(tokio::spawn(async move {
+                let mut inserted = 0;
+                for wallet in partition {
+                    match trades.backfill_wallet(&rpc, &wallet_repo, &wallet, max_pages).await {
+                        Ok(count) => inserted += count,
+                        Err(e) => tracing::warn!("Backfill failed for wallet {}: {}", wallet, e),
+                    }
+                }
+                inserted
+            }));
+        }
+
+        let mut total = 0;
+        for task in tasks {
+            total += task.await?;
+        }
+
+        Ok(total)
     }
 
     /// Get all trades for a wallet
@@ -70,10 +168,124 @@ impl TradeRepository {
             let wallet: String = row.try_get("wallet")?;
             let token_mint: String = row.try_get("token_mint")?;
             let side: String = row.try_get("side")?;
-            let amount_in: String = row.try_get("amount_in")?;
-            let amount_out: String = row.try_get("amount_out")?;
-            let price_usd: String = row.try_get("price_usd")?;
-            let market_cap_at_trade: String = row.try_get("market_cap_at_trade")?;
+            let amount_in: Money = row.try_get("amount_in")?;
+            let amount_out: Money = row.try_get("amount_out")?;
+            let price_usd: Money = row.try_get("price_usd")?;
+            let market_cap_at_trade: Money = row.try_get("market_cap_at_trade")?;
+            let signature: String = row.try_get("signature")?;
+            let timestamp: String = row.try_get("timestamp")?;
+            let block_time: i64 = row.try_get("block_time")?;
+            let dex: String = row.try_get("dex")?;
+
+            trades.push(Trade {
+                id: Uuid::from_str(&id)?,
+                wallet: Pubkey::from_str(&wallet)?,
+                token_mint: Pubkey::from_str(&token_mint)?,
+                side: match side.as_str() {
+                    "Buy" => TradeSide::Buy,
+                    "Sell" => TradeSide::Sell,
+                    _ => continue,
+                },
+                amount_in: amount_in.into(),
+                amount_out: amount_out.into(),
+                price_usd: price_usd.into(),
+                market_cap_at_trade: market_cap_at_trade.into(),
+                signature,
+                timestamp: DateTime::parse_from_rfc3339(&timestamp)?.with_timezone(&Utc),
+                block_time,
+                dex,
+            });
+        }
+
+        Ok(trades)
+    }
+
+    /// Get all trades for a token mint, oldest first, for candle bucketing
+    pub async fn get_token_trades(&self, token_mint: &Pubkey) -> Result<Vec<Trade>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, wallet, token_mint, side, amount_in, amount_out,
+                   price_usd, market_cap_at_trade, signature, timestamp,
+                   block_time, dex
+            FROM trades
+            WHERE token_mint = ?
+            ORDER BY block_time ASC
+            "#,
+        )
+        .bind(token_mint.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut trades = Vec::new();
+        for row in rows {
+            let id: String = row.try_get("id")?;
+            let wallet: String = row.try_get("wallet")?;
+            let token_mint: String = row.try_get("token_mint")?;
+            let side: String = row.try_get("side")?;
+            let amount_in: Money = row.try_get("amount_in")?;
+            let amount_out: Money = row.try_get("amount_out")?;
+            let price_usd: Money = row.try_get("price_usd")?;
+            let market_cap_at_trade: Money = row.try_get("market_cap_at_trade")?;
+            let signature: String = row.try_get("signature")?;
+            let timestamp: String = row.try_get("timestamp")?;
+            let block_time: i64 = row.try_get("block_time")?;
+            let dex: String = row.try_get("dex")?;
+
+            trades.push(Trade {
+                id: Uuid::from_str(&id)?,
+                wallet: Pubkey::from_str(&wallet)?,
+                token_mint: Pubkey::from_str(&token_mint)?,
+                side: match side.as_str() {
+                    "Buy" => TradeSide::Buy,
+                    "Sell" => TradeSide::Sell,
+                    _ => continue,
+                },
+                amount_in: amount_in.into(),
+                amount_out: amount_out.into(),
+                price_usd: price_usd.into(),
+                market_cap_at_trade: market_cap_at_trade.into(),
+                signature,
+                timestamp: DateTime::parse_from_rfc3339(&timestamp)?.with_timezone(&Utc),
+                block_time,
+                dex,
+            });
+        }
+
+        Ok(trades)
+    }
+
+    /// Get trades for a token mint with `block_time >= since_block_time`, oldest first.
+    /// Used by incremental candle backfill to avoid rescanning a token's full history.
+    pub async fn get_token_trades_since(
+        &self,
+        token_mint: &Pubkey,
+        since_block_time: i64,
+    ) -> Result<Vec<Trade>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, wallet, token_mint, side, amount_in, amount_out,
+                   price_usd, market_cap_at_trade, signature, timestamp,
+                   block_time, dex
+            FROM trades
+            WHERE token_mint = ? AND block_time >= ?
+            ORDER BY block_time ASC
+            "#,
+        )
+        .bind(token_mint.to_string())
+        .bind(since_block_time)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut trades = Vec::new();
+        for row in rows {
+            let id: String = row.try_get("id")?;
+            let wallet: String = row.try_get("wallet")?;
+            let token_mint: String = row.try_get("token_mint")?;
+            let side: String = row.try_get("side")?;
+            let amount_in: Money = row.try_get("amount_in")?;
+            let amount_out: Money = row.try_get("amount_out")?;
+            let price_usd: Money = row.try_get("price_usd")?;
+            let market_cap_at_trade: Money = row.try_get("market_cap_at_trade")?;
             let signature: String = row.try_get("signature")?;
             let timestamp: String = row.try_get("timestamp")?;
             let block_time: i64 = row.try_get("block_time")?;
@@ -88,10 +300,10 @@ impl TradeRepository {
                     "Sell" => TradeSide::Sell,
                     _ => continue,
                 },
-                amount_in: Decimal::from_str(&amount_in)?,
-                amount_out: Decimal::from_str(&amount_out)?,
-                price_usd: Decimal::from_str(&price_usd)?,
-                market_cap_at_trade: Decimal::from_str(&market_cap_at_trade)?,
+                amount_in: amount_in.into(),
+                amount_out: amount_out.into(),
+                price_usd: price_usd.into(),
+                market_cap_at_trade: market_cap_at_trade.into(),
                 signature,
                 timestamp: DateTime::parse_from_rfc3339(&timestamp)?.with_timezone(&Utc),
                 block_time,
@@ -124,10 +336,10 @@ impl TradeRepository {
             let wallet: String = row.try_get("wallet")?;
             let token_mint: String = row.try_get("token_mint")?;
             let side: String = row.try_get("side")?;
-            let amount_in: String = row.try_get("amount_in")?;
-            let amount_out: String = row.try_get("amount_out")?;
-            let price_usd: String = row.try_get("price_usd")?;
-            let market_cap_at_trade: String = row.try_get("market_cap_at_trade")?;
+            let amount_in: Money = row.try_get("amount_in")?;
+            let amount_out: Money = row.try_get("amount_out")?;
+            let price_usd: Money = row.try_get("price_usd")?;
+            let market_cap_at_trade: Money = row.try_get("market_cap_at_trade")?;
             let signature: String = row.try_get("signature")?;
             let timestamp: String = row.try_get("timestamp")?;
             let block_time: i64 = row.try_get("block_time")?;
@@ -142,10 +354,10 @@ impl TradeRepository {
                     "Sell" => TradeSide::Sell,
                     _ => continue,
                 },
-                amount_in: Decimal::from_str(&amount_in)?,
-                amount_out: Decimal::from_str(&amount_out)?,
-                price_usd: Decimal::from_str(&price_usd)?,
-                market_cap_at_trade: Decimal::from_str(&market_cap_at_trade)?,
+                amount_in: amount_in.into(),
+                amount_out: amount_out.into(),
+                price_usd: price_usd.into(),
+                market_cap_at_trade: market_cap_at_trade.into(),
                 signature,
                 timestamp: DateTime::parse_from_rfc3339(&timestamp)?.with_timezone(&Utc),
                 block_time,
@@ -186,16 +398,16 @@ impl TradeRepository {
         .bind(token_mint.to_string())
         .bind(token_symbol)
         .bind(entry_time.to_rfc3339())
-        .bind(entry_price.to_string())
-        .bind(entry_mc.to_string())
-        .bind(amount.to_string())
-        .bind(stop_loss.to_string())
-        .bind(take_profit.to_string())
+        .bind(Money::from(entry_price))
+        .bind(Money::from(entry_mc))
+        .bind(Money::from(amount))
+        .bind(Money::from(stop_loss))
+        .bind(Money::from(take_profit))
         .bind("closed")
         .bind(exit_time.to_rfc3339())
-        .bind(exit_price.to_string())
+        .bind(Money::from(exit_price))
         .bind(exit_reason)
-        .bind(pnl.to_string())
+        .bind(Money::from(pnl))
         .bind(pnl_pct)
         .bind(hold_time_minutes)
         .execute(&self.pool)
@@ -213,7 +425,9 @@ impl TradeRepository {
         Ok(row.try_get("count")?)
     }
 
-    /// Get win rate
+    /// Get win rate. `pnl` is stored as `REAL` (via `Money`'s `sqlx::Encode`), so `pnl > 0`
+    /// compares numerically - previously it was `TEXT`, so this comparison (and `SUM`/
+    /// `ORDER BY` against the same columns) silently did lexicographic string comparison.
     pub async fn get_win_rate(&self) -> Result<f64> {
         let row = sqlx::query(
             r#"
@@ -236,4 +450,96 @@ impl TradeRepository {
 
         Ok((wins as f64 / total as f64) * 100.0)
     }
+
+    /// Count of closed positions, i.e. how many trades `HistoryStore::get_total_trades`
+    /// should report - distinct from [`TradeRepository::get_trade_count`], which counts
+    /// the raw `trades` ingestion log instead.
+    pub async fn get_closed_trade_count(&self) -> Result<i64> {
+        let row = sqlx::query("SELECT COUNT(*) as count FROM positions WHERE status = 'closed'")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(row.try_get("count")?)
+    }
+
+    /// Sum of `pnl` across every closed position, computed server-side instead of
+    /// summing `SerializableClosedTrade::pnl` over a fully-loaded JSON file.
+    pub async fn get_total_pnl(&self) -> Result<Decimal> {
+        let row = sqlx::query("SELECT COALESCE(SUM(pnl), 0) as total_pnl FROM positions WHERE status = 'closed'")
+            .fetch_one(&self.pool)
+            .await?;
+
+        let total_pnl: f64 = row.try_get("total_pnl")?;
+        Decimal::try_from(total_pnl).map_err(Into::into)
+    }
+
+    /// The `limit` closed positions with the highest `pnl`, most profitable first.
+    pub async fn get_best_trades(&self, limit: i64) -> Result<Vec<ClosedPositionRecord>> {
+        self.get_trades_ordered_by_pnl(limit, true).await
+    }
+
+    /// The `limit` closed positions with the lowest `pnl`, least profitable first.
+    pub async fn get_worst_trades(&self, limit: i64) -> Result<Vec<ClosedPositionRecord>> {
+        self.get_trades_ordered_by_pnl(limit, false).await
+    }
+
+    async fn get_trades_ordered_by_pnl(&self, limit: i64, descending: bool) -> Result<Vec<ClosedPositionRecord>> {
+        let order = if descending { "DESC" } else { "ASC" };
+        let query = format!(
+            r#"
+            SELECT token_mint, token_symbol, entry_time, exit_time, entry_price, exit_price,
+                   pnl, pnl_pct, hold_time_minutes, exit_reason
+            FROM positions
+            WHERE status = 'closed'
+            ORDER BY pnl {}
+            LIMIT ?
+            "#,
+            order
+        );
+
+        let rows = sqlx::query(&query).bind(limit).fetch_all(&self.pool).await?;
+
+        let mut trades = Vec::with_capacity(rows.len());
+        for row in rows {
+            let entry_time: String = row.try_get("entry_time")?;
+            let exit_time: String = row.try_get("exit_time")?;
+            let entry_price: f64 = row.try_get("entry_price")?;
+            let exit_price: f64 = row.try_get("exit_price")?;
+            let pnl: f64 = row.try_get("pnl")?;
+
+            trades.push(ClosedPositionRecord {
+                token_mint: row.try_get("token_mint")?,
+                token_symbol: row.try_get("token_symbol")?,
+                entry_time: DateTime::parse_from_rfc3339(&entry_time)?.with_timezone(&Utc),
+                exit_time: DateTime::parse_from_rfc3339(&exit_time)?.with_timezone(&Utc),
+                entry_price: Decimal::try_from(entry_price)?,
+                exit_price: Decimal::try_from(exit_price)?,
+                pnl: Decimal::try_from(pnl)?,
+                pnl_pct: row.try_get("pnl_pct")?,
+                hold_time_minutes: row.try_get("hold_time_minutes")?,
+                exit_reason: row.try_get::<Option<String>, _>("exit_reason")?.unwrap_or_default(),
+            });
+        }
+
+        Ok(trades)
+    }
+}
+
+/// Split `items` into up to `n` roughly-even, contiguous chunks (round-robin sized so no
+/// partition gets more than one extra item), for `backfill_wallets_concurrent`'s task pool.
+fn partition(items: &[Pubkey], n: usize) -> Vec<Vec<Pubkey>> {
+    if items.is_empty() {
+        return Vec::new();
+    }
+
+    let n = n.min(items.len());
+    let (base, extra) = (items.len() / n, items.len() % n);
+    let mut partitions = Vec::with_capacity(n);
+    let mut start = 0;
+    for i in 0..n {
+        let len = base + usize::from(i < extra);
+        partitions.push(items[start..start + len].to_vec());
+        start += len;
+    }
+    partitions
 }
@@ -2,17 +2,19 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use solana_sdk::pubkey::Pubkey;
-use sqlx::SqlitePool;
+use sqlx::{AnyPool, Row};
 use std::str::FromStr;
 use trading_core::WalletMetrics;
 
+use crate::scaled_decimal::ScaledDecimal;
+
 #[derive(Clone)]
 pub struct WalletRepository {
-    pool: SqlitePool,
+    pool: AnyPool,
 }
 
 impl WalletRepository {
-    pub fn new(pool: SqlitePool) -> Self {
+    pub fn new(pool: AnyPool) -> Self {
         Self { pool }
     }
 
@@ -74,18 +76,18 @@ impl WalletRepository {
         .bind(metrics.winning_trades as i64)
         .bind(metrics.losing_trades as i64)
         .bind(metrics.win_rate)
-        .bind(metrics.total_pnl.to_string())
+        .bind(ScaledDecimal::try_from(metrics.total_pnl)?)
         .bind(metrics.total_pnl_percentage)
         .bind(metrics.avg_hold_time_seconds)
-        .bind(metrics.avg_profit_per_trade.to_string())
-        .bind(metrics.largest_win.to_string())
-        .bind(metrics.largest_loss.to_string())
+        .bind(ScaledDecimal::try_from(metrics.avg_profit_per_trade)?)
+        .bind(ScaledDecimal::try_from(metrics.largest_win)?)
+        .bind(ScaledDecimal::try_from(metrics.largest_loss)?)
         .bind(metrics.sharpe_ratio)
         .bind(metrics.max_drawdown)
         .bind(metrics.trades_last_24h as i64)
         .bind(metrics.trades_last_7d as i64)
-        .bind(metrics.volume_24h.to_string())
-        .bind(metrics.volume_7d.to_string())
+        .bind(ScaledDecimal::try_from(metrics.volume_24h)?)
+        .bind(ScaledDecimal::try_from(metrics.volume_7d)?)
         .execute(&self.pool)
         .await?;
 
@@ -115,6 +117,42 @@ impl WalletRepository {
         Ok(wallets)
     }
 
+    /// Get the last-seen signature for a wallet's incremental backfill cursor,
+    /// or `None` if this wallet has never been backfilled.
+    pub async fn get_backfill_cursor(&self, address: &Pubkey) -> Result<Option<String>> {
+        let row = sqlx::query(
+            r#"SELECT last_signature FROM wallet_backfill WHERE wallet_address = ?"#,
+        )
+        .bind(address.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(match row {
+            Some(row) => Some(row.try_get("last_signature")?),
+            None => None,
+        })
+    }
+
+    /// Persist the newest signature seen for a wallet so the next backfill only
+    /// fetches the delta since this cursor.
+    pub async fn save_backfill_cursor(&self, address: &Pubkey, last_signature: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO wallet_backfill (wallet_address, last_signature)
+            VALUES (?, ?)
+            ON CONFLICT(wallet_address) DO UPDATE SET
+                last_signature = excluded.last_signature,
+                updated_at = datetime('now')
+            "#,
+        )
+        .bind(address.to_string())
+        .bind(last_signature)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     /// Get wallet performance history
     pub async fn get_wallet_history(&self, address: &Pubkey, days: i64) -> Result<Vec<WalletMetrics>> {
         let cutoff = Utc::now() - chrono::Duration::days(days);
@@ -137,33 +175,119 @@ impl WalletRepository {
 
         let mut history = Vec::new();
         for row in rows {
-            let total_pnl: String = row.try_get("total_pnl")?;
-            let avg_profit_per_trade: String = row.try_get("avg_profit_per_trade")?;
-            let largest_win: String = row.try_get("largest_win")?;
-            let largest_loss: String = row.try_get("largest_loss")?;
-            let volume_24h: String = row.try_get("volume_24h")?;
-            let volume_7d: String = row.try_get("volume_7d")?;
+            let total_pnl: ScaledDecimal = row.try_get("total_pnl")?;
+            let avg_profit_per_trade: ScaledDecimal = row.try_get("avg_profit_per_trade")?;
+            let largest_win: ScaledDecimal = row.try_get("largest_win")?;
+            let largest_loss: ScaledDecimal = row.try_get("largest_loss")?;
+            let volume_24h: ScaledDecimal = row.try_get("volume_24h")?;
+            let volume_7d: ScaledDecimal = row.try_get("volume_7d")?;
 
             history.push(WalletMetrics {
                 total_trades: row.try_get::<i64, _>("total_trades")? as u64,
                 winning_trades: row.try_get::<i64, _>("winning_trades")? as u64,
                 losing_trades: row.try_get::<i64, _>("losing_trades")? as u64,
                 win_rate: row.try_get("win_rate")?,
-                total_pnl: Decimal::from_str(&total_pnl)?,
+                total_pnl: total_pnl.to_decimal(),
                 total_pnl_percentage: row.try_get("total_pnl_percentage")?,
                 avg_hold_time_seconds: row.try_get("avg_hold_time_seconds")?,
-                avg_profit_per_trade: Decimal::from_str(&avg_profit_per_trade)?,
-                largest_win: Decimal::from_str(&largest_win)?,
-                largest_loss: Decimal::from_str(&largest_loss)?,
+                avg_profit_per_trade: avg_profit_per_trade.to_decimal(),
+                largest_win: largest_win.to_decimal(),
+                largest_loss: largest_loss.to_decimal(),
                 sharpe_ratio: row.try_get("sharpe_ratio")?,
                 max_drawdown: row.try_get("max_drawdown")?,
+                // Not persisted in the wallet_metrics table - it's derived from the
+                // closed-position equity curve at analysis time, not snapshotted per-row.
+                max_drawdown_start: None,
+                max_drawdown_end: None,
+                underwater_seconds: 0.0,
                 trades_last_24h: row.try_get::<i64, _>("trades_last_24h")? as u64,
                 trades_last_7d: row.try_get::<i64, _>("trades_last_7d")? as u64,
-                volume_24h: Decimal::from_str(&volume_24h)?,
-                volume_7d: Decimal::from_str(&volume_7d)?,
+                volume_24h: volume_24h.to_decimal(),
+                volume_7d: volume_7d.to_decimal(),
+                // Not persisted in the wallet_metrics table - it's derived from the
+                // return series at analysis time, not snapshotted per-row.
+                return_metrics: Default::default(),
             });
         }
 
         Ok(history)
     }
+
+    /// Top `limit` wallets in `wallet_metrics` (tracked or not) by total PnL, ranked from each wallet's latest
+    /// snapshot in the last `days` - computed with `ORDER BY`/`LIMIT` directly in SQL
+    /// now that `total_pnl` is a scaled integer column rather than `TEXT`.
+    pub async fn top_wallets_by_pnl(&self, days: i64, limit: i64) -> Result<Vec<WalletPnlRanking>> {
+        let cutoff = Utc::now() - chrono::Duration::days(days);
+
+        let rows = sqlx::query(
+            r#"
+            SELECT wm.wallet_address AS wallet_address, wm.total_pnl AS total_pnl
+            FROM wallet_metrics wm
+            INNER JOIN (
+                SELECT wallet_address, MAX(snapshot_time) AS latest_snapshot
+                FROM wallet_metrics
+                WHERE snapshot_time >= ?
+                GROUP BY wallet_address
+            ) latest
+              ON latest.wallet_address = wm.wallet_address
+             AND latest.latest_snapshot = wm.snapshot_time
+            ORDER BY wm.total_pnl DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(cutoff.to_rfc3339())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut rankings = Vec::new();
+        for row in rows {
+            let wallet_address: String = row.try_get("wallet_address")?;
+            let total_pnl: ScaledDecimal = row.try_get("total_pnl")?;
+
+            if let Ok(wallet) = Pubkey::from_str(&wallet_address) {
+                rankings.push(WalletPnlRanking { wallet, total_pnl: total_pnl.to_decimal() });
+            }
+        }
+
+        Ok(rankings)
+    }
+
+    /// Total `volume_24h` summed across every wallet's latest snapshot in the last
+    /// `days`, computed with a native `SUM` now that the column is a scaled integer
+    /// rather than `TEXT`.
+    pub async fn aggregate_volume(&self, days: i64) -> Result<Decimal> {
+        let cutoff = Utc::now() - chrono::Duration::days(days);
+
+        // Postgres promotes SUM(bigint) to numeric, which ScaledDecimal's Decode (a plain
+        // i64 read) can't handle - the explicit CAST forces it back to bigint on every
+        // backend `AnyPool` can target.
+        let row = sqlx::query(
+            r#"
+            SELECT CAST(SUM(wm.volume_24h) AS BIGINT) AS total_volume
+            FROM wallet_metrics wm
+            INNER JOIN (
+                SELECT wallet_address, MAX(snapshot_time) AS latest_snapshot
+                FROM wallet_metrics
+                WHERE snapshot_time >= ?
+                GROUP BY wallet_address
+            ) latest
+              ON latest.wallet_address = wm.wallet_address
+             AND latest.latest_snapshot = wm.snapshot_time
+            "#,
+        )
+        .bind(cutoff.to_rfc3339())
+        .fetch_one(&self.pool)
+        .await?;
+
+        let total: Option<ScaledDecimal> = row.try_get("total_volume")?;
+        Ok(total.map(ScaledDecimal::to_decimal).unwrap_or(Decimal::ZERO))
+    }
+}
+
+/// A wallet's ranking in [`WalletRepository::top_wallets_by_pnl`]'s leaderboard.
+#[derive(Debug, Clone)]
+pub struct WalletPnlRanking {
+    pub wallet: Pubkey,
+    pub total_pnl: Decimal,
 }